@@ -1,5 +1,7 @@
 // cargo run -p ex_01_ocp
 
+use std::fmt;
+
 // =========================
 // Naïve Solution - Reporter
 // =========================
@@ -13,29 +15,138 @@ pub enum ReportFormat {
 }
 
 // This struct represents the report data
+// A report is a title followed by sections
 pub struct Report {
-    title: String,
-    data: Vec<String>,
+    pub title: String,
+    pub sections: Vec<Section>,
 }
 
 impl Report {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn section(mut self, section: Section) -> Self {
+        self.sections.push(section);
+        self
+    }
+
     // This method violates the Open-Closed Principle
     // Each new format requires modifying this method
     pub fn generate(&self, format: ReportFormat) -> String {
         match format {
             ReportFormat::Text => {
                 let mut output = format!("=== {} ===", self.title);
-                for item in &self.data {
-                    output.push_str(&format!("\n- {}", item));
+                for section in &self.sections {
+                    if let Some(heading) = &section.heading {
+                        output.push_str(&format!("\n\n--- {} ---", heading));
+                    }
+                    for block in &section.blocks {
+                        match block {
+                            Block::Items(items) => {
+                                for item in items {
+                                    output.push_str(&format!("\n- {}", item));
+                                }
+                            }
+                            Block::Table(table) => {
+                                // Columns are padded to their widest cell, numbers are right-aligned
+                                let widths: Vec<usize> = (0..table.columns.len())
+                                    .map(|i| {
+                                        table
+                                            .rows
+                                            .iter()
+                                            .map(|row| row[i].to_string().chars().count())
+                                            .chain(std::iter::once(
+                                                table.columns[i].name.chars().count(),
+                                            ))
+                                            .max()
+                                            .unwrap_or(0)
+                                    })
+                                    .collect();
+                                let header: Vec<String> = table
+                                    .columns
+                                    .iter()
+                                    .zip(&widths)
+                                    .map(|(c, &w)| pad(&c.name, w, c.kind.is_numeric()))
+                                    .collect();
+                                output.push_str(&format!("\n{}", header.join("  ").trim_end()));
+                                let rule: Vec<String> =
+                                    widths.iter().map(|&w| "-".repeat(w)).collect();
+                                output.push_str(&format!("\n{}", rule.join("  ")));
+                                for row in &table.rows {
+                                    let cells: Vec<String> = row
+                                        .iter()
+                                        .zip(&table.columns)
+                                        .zip(&widths)
+                                        .map(|((v, c), &w)| {
+                                            pad(&v.to_string(), w, c.kind.is_numeric())
+                                        })
+                                        .collect();
+                                    output.push_str(&format!("\n{}", cells.join("  ").trim_end()));
+                                }
+                            }
+                            Block::KeyValues(pairs) => {
+                                let width = pairs
+                                    .iter()
+                                    .map(|(k, _)| k.chars().count())
+                                    .max()
+                                    .unwrap_or(0);
+                                for (key, value) in pairs {
+                                    output.push_str(&format!("\n{:<width$} : {}", key, value));
+                                }
+                            }
+                        }
+                    }
                 }
                 output
             }
             ReportFormat::Html => {
-                let mut output = format!("<h1>{}</h1>\n<ul>\n", self.title);
-                for item in &self.data {
-                    output.push_str(&format!("  <li>{}</li>\n", item));
+                let mut output = format!("<h1>{}</h1>\n", self.title);
+                for section in &self.sections {
+                    if let Some(heading) = &section.heading {
+                        output.push_str(&format!("<h2>{}</h2>\n", heading));
+                    }
+                    for block in &section.blocks {
+                        match block {
+                            Block::Items(items) => {
+                                output.push_str("<ul>\n");
+                                for item in items {
+                                    output.push_str(&format!("  <li>{}</li>\n", item));
+                                }
+                                output.push_str("</ul>\n");
+                            }
+                            Block::Table(table) => {
+                                output.push_str("<table>\n  <tr>");
+                                for column in &table.columns {
+                                    output.push_str(&format!("<th>{}</th>", column.name));
+                                }
+                                output.push_str("</tr>\n");
+                                for row in &table.rows {
+                                    output.push_str("  <tr>");
+                                    for value in row {
+                                        output.push_str(&format!("<td>{}</td>", value));
+                                    }
+                                    output.push_str("</tr>\n");
+                                }
+                                output.push_str("</table>\n");
+                            }
+                            Block::KeyValues(pairs) => {
+                                output.push_str("<dl>\n");
+                                for (key, value) in pairs {
+                                    output.push_str(&format!(
+                                        "  <dt>{}</dt><dd>{}</dd>\n",
+                                        key, value
+                                    ));
+                                }
+                                output.push_str("</dl>\n");
+                            }
+                        }
+                    }
                 }
-                output.push_str("</ul>");
+                output.truncate(output.trim_end().len());
                 output
             }
             ReportFormat::Pdf => {
@@ -46,16 +157,231 @@ impl Report {
     }
 }
 
+// A section groups blocks under an optional heading
+pub struct Section {
+    pub heading: Option<String>,
+    pub blocks: Vec<Block>,
+}
+
+impl Section {
+    pub fn new(heading: impl Into<String>) -> Self {
+        Self {
+            heading: Some(heading.into()),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn untitled() -> Self {
+        Self {
+            heading: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn items<I, S>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.blocks
+            .push(Block::Items(items.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    pub fn table(mut self, table: Table) -> Self {
+        self.blocks.push(Block::Table(table));
+        self
+    }
+
+    pub fn key_values<I, K>(mut self, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Value)>,
+        K: Into<String>,
+    {
+        self.blocks.push(Block::KeyValues(
+            pairs.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        ));
+        self
+    }
+}
+
+// The building blocks every formatter must be able to render
+pub enum Block {
+    // A bullet list of free text
+    Items(Vec<String>),
+    // Rows of typed cells under named columns
+    Table(Table),
+    // Label / value pairs (totals, metadata...)
+    KeyValues(Vec<(String, Value)>),
+}
+
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(mut self, cells: Vec<Value>) -> Self {
+        assert_eq!(
+            cells.len(),
+            self.columns.len(),
+            "a table row must have one cell per column"
+        );
+        self.rows.push(cells);
+        self
+    }
+}
+
+pub struct Column {
+    pub name: String,
+    pub kind: ColumnType,
+}
+
+impl Column {
+    pub fn new(name: impl Into<String>, kind: ColumnType) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Decimal,
+    Date,
+}
+
+impl ColumnType {
+    // Numbers read better right-aligned
+    pub fn is_numeric(self) -> bool {
+        matches!(self, ColumnType::Integer | ColumnType::Decimal)
+    }
+}
+
+// A typed cell or key/value value
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Date(Date),
+}
+
+impl Value {
+    pub fn text(s: impl Into<String>) -> Self {
+        Value::Text(s.into())
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Empty => Ok(()),
+            Value::Text(s) => f.write_str(s),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Decimal(x) => write!(f, "{:.2}", x),
+            Value::Date(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+// A calendar date, rendered as ISO 8601 (YYYY-MM-DD)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Self {
+        assert!((1..=12).contains(&month), "month out of range");
+        assert!(
+            (1..=days_in_month(year, month)).contains(&day),
+            "day out of range"
+        );
+        Self { year, month, day }
+    }
+}
+
+// Gregorian calendar: February has 29 days every 4 years, except centuries not divisible by 400
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// Left or right align a cell in a text table
+fn pad(value: &str, width: usize, right: bool) -> String {
+    if right {
+        format!("{:>width$}", value)
+    } else {
+        format!("{:<width$}", value)
+    }
+}
+
 fn main() {
     // Sample report data
-    let report = Report {
-        title: String::from("Monthly Sales"),
-        data: vec![
-            String::from("Product A: 120 units"),
-            String::from("Product B: 98 units"),
-            String::from("Product C: 143 units"),
-        ],
-    };
+    let report = Report::new("Monthly Sales")
+        .section(Section::untitled().items([
+            "Product A: 120 units",
+            "Product B: 98 units",
+            "Product C: 143 units",
+        ]))
+        .section(
+            Section::new("Sales by product").table(
+                Table::new(vec![
+                    Column::new("Product", ColumnType::Text),
+                    Column::new("Units", ColumnType::Integer),
+                    Column::new("Revenue", ColumnType::Decimal),
+                    Column::new("Last order", ColumnType::Date),
+                ])
+                .row(vec![
+                    Value::text("Product A"),
+                    Value::Integer(120),
+                    Value::Decimal(2_398.80),
+                    Value::Date(Date::new(2025, 1, 30)),
+                ])
+                .row(vec![
+                    Value::text("Product B"),
+                    Value::Integer(98),
+                    Value::Decimal(4_890.20),
+                    Value::Date(Date::new(2025, 1, 28)),
+                ])
+                .row(vec![
+                    Value::text("Product C"),
+                    Value::Integer(143),
+                    Value::Decimal(1_429.99),
+                    Value::Empty,
+                ]),
+            ),
+        )
+        .section(Section::new("Summary").key_values([
+            ("Period", Value::text("January 2025")),
+            ("Total units", Value::Integer(361)),
+            ("Total revenue", Value::Decimal(8_718.99)),
+            ("Generated on", Value::Date(Date::new(2025, 2, 1))),
+        ]));
 
     // Generate reports in different formats
     let text_report = report.generate(ReportFormat::Text);
@@ -0,0 +1,99 @@
+// cargo build -p ex_02_ocp_rst_plugin
+
+// =========================
+// A third-party formatter, shipped as a plugin
+// =========================
+
+// reStructuredText output, built outside ex_02_ocp and loaded at runtime
+// The only thing shared with the host is the C contract in abi.rs
+
+#[path = "../../../src/plugin/abi.rs"]
+mod abi;
+
+use abi::{FfiBlock, FfiReport, FfiStr, FfiWriter, PluginVTable};
+
+static VTABLE: PluginVTable = PluginVTable {
+    abi_version: abi::ABI_VERSION,
+    name: FfiStr::new("rst"),
+    extensions: FfiStr::new("rst,rest"),
+    mime_type: FfiStr::new("text/x-rst"),
+    render,
+};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn report_formatter_plugin() -> *const PluginVTable {
+    &VTABLE
+}
+
+// Error codes returned to the host
+const INVALID_UTF8: i32 = 1;
+const WRITE_FAILED: i32 = 2;
+
+unsafe extern "C" fn render(report: &FfiReport<'_>, out: &FfiWriter) -> i32 {
+    match to_rst(report) {
+        Some(text) if out.write_all(text.as_bytes()) => 0,
+        Some(_) => WRITE_FAILED,
+        None => INVALID_UTF8,
+    }
+}
+
+fn to_rst(report: &FfiReport<'_>) -> Option<String> {
+    let title = report.title.to_str()?;
+    let rule = "=".repeat(title.chars().count().max(1));
+    let mut out = format!("{}\n{}\n{}\n", rule, title, rule);
+    for section in report.sections.as_slice() {
+        if section.has_heading {
+            let heading = section.heading.to_str()?;
+            out += &format!(
+                "\n{}\n{}\n",
+                heading,
+                "-".repeat(heading.chars().count().max(1))
+            );
+        }
+        for block in section.blocks.as_slice() {
+            out.push('\n');
+            write_block(block, &mut out)?;
+        }
+    }
+    Some(out)
+}
+
+fn write_block(block: &FfiBlock<'_>, out: &mut String) -> Option<()> {
+    let cells = block
+        .cells
+        .as_slice()
+        .iter()
+        .map(|c| c.text.to_str().map(|t| t.replace('\n', " ")))
+        .collect::<Option<Vec<String>>>()?;
+    match block.kind {
+        abi::BLOCK_TABLE => {
+            let columns = block
+                .columns
+                .as_slice()
+                .iter()
+                .map(|c| c.text.to_str().map(csv_field))
+                .collect::<Option<Vec<String>>>()?;
+            out.push_str(".. csv-table::\n");
+            out.push_str(&format!("   :header: {}\n\n", columns.join(", ")));
+            for row in cells.chunks(columns.len().max(1)) {
+                let row: Vec<String> = row.iter().map(|c| csv_field(c)).collect();
+                out.push_str(&format!("   {}\n", row.join(", ")));
+            }
+        }
+        abi::BLOCK_KEY_VALUES => {
+            for pair in cells.chunks(2) {
+                out.push_str(&format!(":{}: {}\n", pair[0], pair.get(1)?));
+            }
+        }
+        _ => {
+            for item in &cells {
+                out.push_str(&format!("- {}\n", item));
+            }
+        }
+    }
+    Some(())
+}
+
+fn csv_field(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
@@ -0,0 +1,277 @@
+// src/batch.rs
+// Many reports, many formats, all cores
+// Every (report, formatter) pair is one job. A fixed set of worker threads takes the jobs one by
+// one and writes each output to <directory>/<report title>/<format>.<ext>.
+// A job that fails (I/O error, or even a panicking formatter) is reported and the others go on.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::formatters::ReportFormatter;
+use crate::report::Report;
+
+pub struct BatchRenderer<'a> {
+    directory: PathBuf,
+    formatters: Vec<&'a dyn ReportFormatter>,
+    threads: usize,
+}
+
+// What went wrong for one report in one format
+#[derive(Debug)]
+pub struct JobFailure {
+    pub report: String,
+    pub format: String,
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl fmt::Display for JobFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\" as {} ({}): {}",
+            self.report,
+            self.format,
+            self.path.display(),
+            self.error
+        )
+    }
+}
+
+impl std::error::Error for JobFailure {}
+
+// The files written and the jobs that failed, both in job order (report by report)
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    pub written: Vec<PathBuf>,
+    pub failures: Vec<JobFailure>,
+}
+
+struct Job<'r> {
+    report: &'r Report,
+    formatter: usize,
+    path: PathBuf,
+}
+
+impl<'a> BatchRenderer<'a> {
+    // One thread per core by default
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            formatters: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn format(mut self, formatter: &'a dyn ReportFormatter) -> Self {
+        self.formatters.push(formatter);
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn render(&self, reports: &[Report]) -> BatchSummary {
+        let jobs = self.jobs(reports);
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<(usize, io::Result<()>)>> =
+            Mutex::new(Vec::with_capacity(jobs.len()));
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(jobs.len()) {
+                scope.spawn(|| {
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(job) = jobs.get(index) else {
+                            break;
+                        };
+                        let result = self.run(job);
+                        results.lock().unwrap().push((index, result));
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(index, _)| *index);
+        let mut summary = BatchSummary::default();
+        for (index, result) in results {
+            let job = &jobs[index];
+            match result {
+                Ok(()) => summary.written.push(job.path.clone()),
+                Err(error) => summary.failures.push(JobFailure {
+                    report: job.report.title.clone(),
+                    format: self.formatters[job.formatter].name().to_string(),
+                    path: job.path.clone(),
+                    error,
+                }),
+            }
+        }
+        summary
+    }
+
+    // Paths are decided up front, so two reports with the same title never write the same file
+    fn jobs<'r>(&self, reports: &'r [Report]) -> Vec<Job<'r>> {
+        let mut folders: Vec<String> = Vec::new();
+        let mut jobs = Vec::with_capacity(reports.len() * self.formatters.len());
+        for report in reports {
+            let base = folder_name(&report.title);
+            let mut folder = base.clone();
+            let mut n = 2;
+            while folders.iter().any(|f| f.eq_ignore_ascii_case(&folder)) {
+                folder = format!("{} ({})", base, n);
+                n += 1;
+            }
+            for (formatter, f) in self.formatters.iter().enumerate() {
+                let file = match f.extensions().first() {
+                    Some(extension) => format!("{}.{}", f.name(), extension),
+                    None => f.name().to_string(),
+                };
+                jobs.push(Job {
+                    report,
+                    formatter,
+                    path: self.directory.join(&folder).join(file),
+                });
+            }
+            folders.push(folder);
+        }
+        jobs
+    }
+
+    // Written next to the target, then renamed: a failed job leaves no half-written file behind
+    fn run(&self, job: &Job) -> io::Result<()> {
+        let formatter = self.formatters[job.formatter];
+        let mut partial = job.path.clone().into_os_string();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            write_file(&partial, |out| formatter.write(job.report, out))
+        }))
+        .unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Err(io::Error::other(format!("formatter panicked: {}", message)))
+        });
+        match result {
+            Ok(()) => fs::rename(&partial, &job.path),
+            Err(error) => {
+                let _ = fs::remove_file(&partial);
+                Err(error)
+            }
+        }
+    }
+}
+
+fn write_file(path: &Path, write: impl FnOnce(&mut dyn Write) -> io::Result<()>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut out = BufWriter::new(File::create(path)?);
+    write(&mut out)?;
+    out.flush()
+}
+
+// A report title as a single, portable path component
+pub(crate) fn folder_name(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows drops trailing dots and spaces, and "." or ".." would leave the directory
+    let name = name.trim_start().trim_end_matches(['.', ' ']);
+    if name.is_empty() {
+        "untitled".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::text::TextFormatter;
+    use crate::formatters::xml::XmlFormatter;
+
+    struct Broken;
+
+    impl ReportFormatter for Broken {
+        fn name(&self) -> &str {
+            "broken"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn mime_type(&self) -> &str {
+            "text/plain"
+        }
+
+        fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+            out.write_all(b"half")?;
+            match report.title.as_str() {
+                "Q2" => Err(io::Error::other("disk full")),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn every_report_in_every_format_failures_included() {
+        let directory = std::env::temp_dir().join(format!("batch_test_{}", std::process::id()));
+        let reports = ["Q1", "Q2", "Q1", "a/b: c."].map(Report::new);
+
+        let summary = BatchRenderer::new(&directory)
+            .format(&TextFormatter::default())
+            .format(&XmlFormatter::default())
+            .format(&Broken)
+            .threads(3)
+            .render(&reports);
+
+        assert_eq!(summary.written.len(), 11);
+        assert_eq!(summary.written[0], directory.join("Q1").join("text.txt"));
+        assert_eq!(
+            summary.written[7],
+            directory.join("Q1 (2)").join("broken.txt")
+        );
+        assert_eq!(
+            summary.written[10],
+            directory.join("a_b_ c").join("broken.txt")
+        );
+        assert!(summary.written.iter().all(|path| path.is_file()));
+        assert_eq!(summary.failures.len(), 1);
+        let failure = &summary.failures[0];
+        assert_eq!(
+            (failure.report.as_str(), failure.format.as_str()),
+            ("Q2", "broken")
+        );
+        assert!(!failure.path.exists() && !failure.path.with_added_extension("part").exists());
+        assert_eq!(
+            fs::read(&summary.written[0]).unwrap(),
+            TextFormatter::default().format(&reports[0]).unwrap()
+        );
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn titles_become_safe_folder_names() {
+        assert_eq!(folder_name("Sales 2025/01"), "Sales 2025_01");
+        assert_eq!(folder_name(".."), "untitled");
+        assert_eq!(folder_name("  "), "untitled");
+    }
+}
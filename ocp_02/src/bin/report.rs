@@ -0,0 +1,307 @@
+// src/bin/report.rs
+// report: render report data in any format, from the command line
+//
+//   report sales.json --format html,xml,pdf --output out/
+//   report sales.csv --format terminal
+//   curl -s https://example.com/sales.json | report --format md
+//
+// The input is the JSON or CSV written by the json and csv formatters, or any CSV table
+// (a header line, then rows). Formats are looked up in the registry by name, extension or MIME type,
+// so a new formatter is available here as soon as it is registered.
+// One format goes to stdout by default. With --output, every format is written to
+// <directory>/<input name>.<ext> and a failed one doesn't stop the others.
+
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use ex_02_ocp::config;
+use ex_02_ocp::formatters::ReportFormatter;
+use ex_02_ocp::formatters::csv::HEADER;
+use ex_02_ocp::parsers::csv::{CsvParser, parse_table};
+use ex_02_ocp::parsers::json::JsonParser;
+use ex_02_ocp::parsers::{ParseError, ReportParser};
+use ex_02_ocp::registry::FormatterRegistry;
+use ex_02_ocp::report::Report;
+
+const USAGE: &str = "\
+Usage: report [OPTIONS] [INPUT]
+
+Reads a report from INPUT (a .json or .csv file, or stdin when missing or \"-\")
+and renders it in each of the requested formats.
+
+Options:
+  -f, --format LIST    formats, by name, extension or MIME type (default: text)
+                       e.g. --format html,xml,pdf
+  -o, --output DIR     write each format to DIR/<input name>.<ext> instead of stdout
+      --from FORMAT    input format, json or csv, when the file name doesn't tell
+  -c, --config FILE    formatter options (CSV delimiter, HTML theme...)
+      --list           list the available formats
+  -h, --help           show this help
+";
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    input: Option<PathBuf>,
+    from: Option<String>,
+    formats: Vec<String>,
+    output: Option<PathBuf>,
+    config: Option<PathBuf>,
+    list: bool,
+    help: bool,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // --format=html is --format html
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+        match flag.as_str() {
+            "-f" | "--format" => parsed.formats.extend(
+                value("--format")?
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty())
+                    .map(String::from),
+            ),
+            "-o" | "--output" => parsed.output = Some(PathBuf::from(value("--output")?)),
+            "--from" => parsed.from = Some(value("--from")?.to_lowercase()),
+            "-c" | "--config" => parsed.config = Some(PathBuf::from(value("--config")?)),
+            "--list" => parsed.list = true,
+            "-h" | "--help" => parsed.help = true,
+            "-" => parsed.input = None,
+            _ if flag.starts_with('-') => return Err(format!("unknown option {}", flag)),
+            _ if parsed.input.is_some() => return Err(format!("only one input, got {}", arg)),
+            _ => parsed.input = Some(PathBuf::from(arg)),
+        }
+    }
+    if parsed.formats.is_empty() {
+        parsed.formats.push("text".to_string());
+    }
+    if let Some(from) = &parsed.from
+        && from != "json"
+        && from != "csv"
+    {
+        return Err(format!("--from takes json or csv, not {}", from));
+    }
+    Ok(parsed)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("report: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if args.help {
+        print!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let registry = match &args.config {
+        Some(path) => match config::read(path).and_then(FormatterRegistry::from_config) {
+            Ok(registry) => registry,
+            Err(e) => {
+                eprintln!("report: {}: {}", path.display(), e);
+                return ExitCode::from(2);
+            }
+        },
+        None => FormatterRegistry::with_builtin_formatters(),
+    };
+    if args.list {
+        for formatter in registry.formats() {
+            println!(
+                "{:<8} {:<18} .{}",
+                formatter.name(),
+                formatter.mime_type(),
+                formatter.extensions().join(", .")
+            );
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut formatters = Vec::new();
+    for name in &args.formats {
+        match registry.lookup(name) {
+            Some(formatter) => formatters.push(formatter),
+            None => {
+                eprintln!("report: no format \"{}\" (see report --list)", name);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if args.output.is_none() && formatters.len() > 1 {
+        eprintln!("report: several formats need --output DIR");
+        return ExitCode::from(2);
+    }
+
+    let source = args
+        .input
+        .as_ref()
+        .map_or("stdin".to_string(), |path| path.display().to_string());
+    let input = match &args.input {
+        Some(path) => fs::read(path),
+        None => {
+            let mut input = Vec::new();
+            io::stdin().read_to_end(&mut input).map(|_| input)
+        }
+    };
+    let report = match input.map_err(|e| e.to_string()).and_then(|input| {
+        read_report(&input, args.from.as_deref(), args.input.as_deref()).map_err(|e| e.to_string())
+    }) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("report: {}: {}", source, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match &args.output {
+        None => match write_stdout(&report, formatters[0]) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("report: {}: {}", formatters[0].name(), e);
+                ExitCode::FAILURE
+            }
+        },
+        Some(directory) => {
+            let stem = args
+                .input
+                .as_deref()
+                .and_then(Path::file_stem)
+                .map_or("report".into(), |stem| stem.to_string_lossy());
+            let mut failed = false;
+            for formatter in formatters {
+                let path = match formatter.extensions().first() {
+                    Some(extension) => directory.join(format!("{}.{}", stem, extension)),
+                    None => directory.join(stem.as_ref()),
+                };
+                match write_file(&report, formatter, &path) {
+                    Ok(()) => println!("{}", path.display()),
+                    Err(e) => {
+                        eprintln!("report: {}: {}", path.display(), e);
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+    }
+}
+
+// --from, else the file extension, else a guess from the content
+fn read_report(
+    input: &[u8],
+    from: Option<&str>,
+    path: Option<&Path>,
+) -> Result<Report, ParseError> {
+    let extension = path
+        .and_then(Path::extension)
+        .map(|e| e.to_string_lossy().to_lowercase());
+    let json = match from.or(extension.as_deref()) {
+        Some("json") => true,
+        Some("csv") => false,
+        _ => input.trim_ascii_start().starts_with(b"{"),
+    };
+    if json {
+        return JsonParser.parse(input);
+    }
+
+    // The csv formatter's own layout, or any table
+    let first_line = input.split(|&b| b == b'\n').next().unwrap_or_default();
+    if first_line.trim_ascii_end() == HEADER.join(",").as_bytes() {
+        CsvParser.parse(input)
+    } else {
+        let title = path
+            .and_then(Path::file_stem)
+            .map_or("Report".into(), |stem| stem.to_string_lossy());
+        parse_table(&title, input)
+    }
+}
+
+// Binary output (PDF, XLSX...) would only garble a terminal
+fn write_stdout(report: &Report, formatter: &dyn ReportFormatter) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    if stdout.is_terminal() {
+        let mut output = Vec::new();
+        formatter.write(report, &mut output)?;
+        if std::str::from_utf8(&output).is_err() {
+            return Err(io::Error::other(
+                "binary output, use --output DIR or redirect stdout to a file",
+            ));
+        }
+        stdout.write_all(&output)?;
+    } else {
+        formatter.write(report, &mut stdout)?;
+    }
+    stdout.flush()
+}
+
+fn write_file(report: &Report, formatter: &dyn ReportFormatter, path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut out = io::BufWriter::new(fs::File::create(path)?);
+    formatter.write(report, &mut out)?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Result<Args, String> {
+        parse_args(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn options_and_input() {
+        let parsed = args("sales.json --format html,xml -f pdf --output=out").unwrap();
+
+        assert_eq!(parsed.input, Some(PathBuf::from("sales.json")));
+        assert_eq!(parsed.formats, ["html", "xml", "pdf"]);
+        assert_eq!(parsed.output, Some(PathBuf::from("out")));
+        assert_eq!(args("-").unwrap().formats, ["text"]);
+        assert_eq!(args("--format").unwrap_err(), "--format needs a value");
+        assert_eq!(
+            args("a.csv b.csv").unwrap_err(),
+            "only one input, got b.csv"
+        );
+        assert_eq!(
+            args("--from xlsx").unwrap_err(),
+            "--from takes json or csv, not xlsx"
+        );
+    }
+
+    #[test]
+    fn input_format_from_option_extension_or_content() {
+        let table = b"Product,Units\nA,3\n";
+
+        let report = read_report(table, None, Some(Path::new("data/sales.csv"))).unwrap();
+        assert_eq!(report.title, "sales");
+
+        let json = b" {\"title\": \"T\", \"sections\": []}";
+        assert_eq!(read_report(json, None, None).unwrap().title, "T");
+        assert!(read_report(table, Some("json"), None).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+// src/config.rs
+// Formatter settings from a key/value file, one [section] per formatter:
+//
+//   # Excel in a European locale
+//   [csv]
+//   delimiter = ;
+//   decimal_locale = de
+//
+//   [html]
+//   theme = dark
+//
+// Each configurable formatter reads its own typed options (see Configurable) and rejects the
+// ones it doesn't know, so a typo is an error instead of a silently ignored setting.
+// Values may be double-quoted to keep surrounding spaces; '#' or ';' starts a comment line.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::formatters::ReportFormatter;
+
+#[derive(Debug)]
+pub struct ConfigError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ConfigError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "config line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// A formatter that can be built from its options, with a default for every option left out
+pub trait Configurable: ReportFormatter + Sized {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError>;
+}
+
+// The options of one [section], consumed by the formatter as it reads them
+pub struct Options {
+    pub formatter: String,
+    // Line of the [section] header
+    pub line: usize,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    key: String,
+    value: String,
+    line: usize,
+}
+
+impl Options {
+    // The raw value of an option, or None when it isn't set
+    pub fn take_text(&mut self, key: &str) -> Option<(String, usize)> {
+        let index = self.entries.iter().position(|e| e.key == key)?;
+        let entry = self.entries.remove(index);
+        Some((entry.value, entry.line))
+    }
+
+    // Any type that parses from text: numbers, bool ("true"/"false"), char...
+    pub fn take<T: FromStr>(&mut self, key: &str) -> Result<Option<T>, ConfigError> {
+        let Some((value, line)) = self.take_text(key) else {
+            return Ok(None);
+        };
+        value
+            .parse()
+            .map(Some)
+            .map_err(|_| self.invalid(line, key, &value, None))
+    }
+
+    // One of a fixed set of words, case-insensitive
+    pub fn take_choice<T: Clone>(
+        &mut self,
+        key: &str,
+        choices: &[(&str, T)],
+    ) -> Result<Option<T>, ConfigError> {
+        let Some((value, line)) = self.take_text(key) else {
+            return Ok(None);
+        };
+        match choices
+            .iter()
+            .find(|(word, _)| word.eq_ignore_ascii_case(&value))
+        {
+            Some((_, choice)) => Ok(Some(choice.clone())),
+            None => {
+                let words: Vec<&str> = choices.iter().map(|(word, _)| *word).collect();
+                Err(self.invalid(line, key, &value, Some(&words)))
+            }
+        }
+    }
+
+    pub fn invalid(
+        &self,
+        line: usize,
+        key: &str,
+        value: &str,
+        expected: Option<&[&str]>,
+    ) -> ConfigError {
+        let mut message = format!("[{}] {}: invalid value \"{}\"", self.formatter, key, value);
+        if let Some(expected) = expected {
+            message.push_str(&format!(", expected {}", expected.join(", ")));
+        }
+        ConfigError::new(line, message)
+    }
+
+    // Whatever the formatter didn't read is unknown to it
+    pub fn finish(self) -> Result<(), ConfigError> {
+        match self.entries.first() {
+            Some(entry) => Err(ConfigError::new(
+                entry.line,
+                format!("[{}] has no option \"{}\"", self.formatter, entry.key),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+// =========================
+// File format
+// =========================
+
+pub fn parse(source: &str) -> Result<Vec<Options>, ConfigError> {
+    let mut sections: Vec<Options> = Vec::new();
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| ConfigError::new(number, "missing ] after the section name"))?
+                .trim()
+                .to_lowercase();
+            if let Some(first) = sections.iter().find(|s| s.formatter == name) {
+                return Err(ConfigError::new(
+                    number,
+                    format!("[{}] already configured on line {}", name, first.line),
+                ));
+            }
+            sections.push(Options {
+                formatter: name,
+                line: number,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| ConfigError::new(number, "expected key = value or [formatter]"))?;
+        let (key, value) = (key.trim().to_lowercase(), value.trim());
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        let section = sections.last_mut().ok_or_else(|| {
+            ConfigError::new(
+                number,
+                format!("\"{}\" is outside of a [formatter] section", key),
+            )
+        })?;
+        if section.entries.iter().any(|e| e.key == key) {
+            return Err(ConfigError::new(
+                number,
+                format!("[{}] {} is set twice", section.formatter, key),
+            ));
+        }
+        section.entries.push(Entry {
+            key,
+            value: value.to_string(),
+            line: number,
+        });
+    }
+    Ok(sections)
+}
+
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<Options>, ConfigError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|e| ConfigError::new(0, format!("cannot read {}: {}", path.display(), e)))?;
+    parse(&source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_of_typed_options() {
+        let mut sections =
+            parse("# comment\n[CSV]\ndelimiter = \";\"\n\n[terminal]\nwidth = 100\ncolors=false\n")
+                .unwrap();
+
+        let terminal = &mut sections[1];
+        assert_eq!(terminal.take::<usize>("width").unwrap(), Some(100));
+        assert_eq!(terminal.take::<bool>("colors").unwrap(), Some(false));
+        assert_eq!(terminal.take::<bool>("colors").unwrap(), None);
+        let csv = &mut sections[0];
+        assert_eq!(csv.formatter, "csv");
+        assert_eq!(csv.take_text("delimiter"), Some((";".to_string(), 3)));
+    }
+
+    #[test]
+    fn mistakes_point_to_their_line() {
+        let error = |source: &str| parse(source).err().unwrap().to_string();
+
+        assert_eq!(
+            error("width = 3"),
+            "config line 1: \"width\" is outside of a [formatter] section"
+        );
+        assert_eq!(
+            error("[csv]\n[csv]"),
+            "config line 2: [csv] already configured on line 1"
+        );
+        assert_eq!(
+            error("[csv]\nx = 1\nx = 2"),
+            "config line 3: [csv] x is set twice"
+        );
+
+        let mut options = parse("[svg]\nchart = pie\nsize = 3").unwrap().remove(0);
+        let chart = options.take_choice("chart", &[("bar", 0), ("line", 1)]);
+        assert_eq!(
+            chart.err().unwrap().to_string(),
+            "config line 2: [svg] chart: invalid value \"pie\", expected bar, line"
+        );
+        assert_eq!(
+            options.finish().err().unwrap().to_string(),
+            "config line 3: [svg] has no option \"size\""
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// src/decorators/contents.rs
+// A "Contents" section listing the headings, for reports that have several sections
+
+use std::io::{self, Write};
+
+use crate::formatters::ReportFormatter;
+use crate::report::{Report, Section};
+
+pub struct TableOfContents<F> {
+    inner: F,
+    // Below this many headed sections, the report is short enough without one
+    min_sections: usize,
+}
+
+impl<F: ReportFormatter> TableOfContents<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            min_sections: 2,
+        }
+    }
+
+    pub fn min_sections(mut self, min_sections: usize) -> Self {
+        self.min_sections = min_sections;
+        self
+    }
+}
+
+impl<F: ReportFormatter> ReportFormatter for TableOfContents<F> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.inner.extensions()
+    }
+
+    fn mime_type(&self) -> &str {
+        self.inner.mime_type()
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        self.write_framed(report, None, None, out)
+    }
+
+    // A header or footer isn't a section: it stays out of the contents
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let headings: Vec<String> = report
+            .sections
+            .iter()
+            .filter_map(|s| s.heading.as_ref())
+            .enumerate()
+            .map(|(i, heading)| format!("{}. {}", i + 1, heading))
+            .collect();
+        if headings.len() < self.min_sections.max(1) {
+            return self.inner.write_framed(report, header, footer, out);
+        }
+
+        // Right before the first heading: an untitled introduction stays under the title
+        let first = report
+            .sections
+            .iter()
+            .position(|s| s.heading.is_some())
+            .unwrap_or(0);
+        let mut decorated = report.clone();
+        decorated
+            .sections
+            .insert(first, Section::new("Contents").items(headings));
+        self.inner.write_framed(&decorated, header, footer, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::markdown::MarkdownFormatter;
+
+    #[test]
+    fn lists_headings_only_for_multi_section_reports() {
+        let one = Report::new("T").section(Section::new("Only").items(["x"]));
+        let two = Report::new("T")
+            .section(Section::untitled().items(["intro"]))
+            .section(Section::new("Sales"))
+            .section(Section::new("Summary"));
+        let formatter = TableOfContents::new(MarkdownFormatter::default());
+
+        assert_eq!(
+            formatter.format(&one).unwrap(),
+            MarkdownFormatter::default().format(&one).unwrap()
+        );
+        assert_eq!(
+            String::from_utf8(formatter.format(&two).unwrap()).unwrap(),
+            "# T\n\n- intro\n\n## Contents\n\n- 1\\. Sales\n- 2\\. Summary\n\n## Sales\n\n## Summary\n"
+        );
+    }
+}
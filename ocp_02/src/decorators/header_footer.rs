@@ -0,0 +1,123 @@
+// src/decorators/header_footer.rs
+// A header line before the report body and a footer line after it, in any format
+// The lines are templates: {title}, {date} (YYYY-MM-DD) and {timestamp} (YYYY-MM-DD hh:mm UTC)
+// are replaced when the report is rendered.
+
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::formatters::ReportFormatter;
+use crate::report::{Date, Report};
+
+pub struct HeaderFooter<F> {
+    inner: F,
+    header: Option<String>,
+    footer: Option<String>,
+    // Fixed time for reproducible output, the current time otherwise
+    at: Option<SystemTime>,
+}
+
+impl<F: ReportFormatter> HeaderFooter<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            header: None,
+            footer: None,
+            at: None,
+        }
+    }
+
+    pub fn header(mut self, template: impl Into<String>) -> Self {
+        self.header = Some(template.into());
+        self
+    }
+
+    pub fn footer(mut self, template: impl Into<String>) -> Self {
+        self.footer = Some(template.into());
+        self
+    }
+
+    pub fn at(mut self, time: SystemTime) -> Self {
+        self.at = Some(time);
+        self
+    }
+}
+
+impl<F: ReportFormatter> ReportFormatter for HeaderFooter<F> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.inner.extensions()
+    }
+
+    fn mime_type(&self) -> &str {
+        self.inner.mime_type()
+    }
+
+    // The wrapped formatter decides where they go: see ReportFormatter::write_framed
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let (date, time) = utc(self.at.unwrap_or_else(SystemTime::now));
+        let fill = |template: &str| {
+            template
+                .replace("{title}", &report.title)
+                .replace("{timestamp}", &format!("{} {} UTC", date, time))
+                .replace("{date}", &date)
+        };
+
+        let header = self.header.as_deref().map(fill);
+        let footer = self.footer.as_deref().map(fill);
+        self.inner
+            .write_framed(report, header.as_deref(), footer.as_deref(), out)
+    }
+}
+
+// ("YYYY-MM-DD", "hh:mm") in UTC
+fn utc(time: SystemTime) -> (String, String) {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    let (days, seconds) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    (
+        Date::from_unix_days(days).to_string(),
+        format!("{:02}:{:02}", seconds / 3_600, seconds % 3_600 / 60),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decorators::contents::TableOfContents;
+    use crate::formatters::text::TextFormatter;
+    use crate::report::Section;
+    use std::time::Duration;
+
+    #[test]
+    fn header_and_footer_surround_the_report() {
+        // 2025-02-01 09:30 UTC
+        let at = UNIX_EPOCH + Duration::from_secs(1_738_402_200);
+        let formatter = HeaderFooter::new(TableOfContents::new(TextFormatter::default()))
+            .header("{title} as of {date}")
+            .footer("Generated {timestamp}")
+            .at(at);
+        let report = Report::new("Sales")
+            .section(Section::untitled().items(["intro"]))
+            .section(Section::new("S").items(["x"]))
+            .section(Section::new("T").items(["y"]));
+
+        let text = String::from_utf8(formatter.format(&report).unwrap()).unwrap();
+
+        assert_eq!(
+            text,
+            "Sales as of 2025-02-01\n\n=== Sales ===\n- intro\n\n--- Contents ---\n- 1. S\n- 2. T\n\n\
+             --- S ---\n- x\n\n--- T ---\n- y\n\nGenerated 2025-02-01 09:30 UTC"
+        );
+        assert_eq!(utc(UNIX_EPOCH).0, "1970-01-01");
+        assert_eq!(
+            utc(UNIX_EPOCH + Duration::from_secs(951_782_400)).0,
+            "2000-02-29"
+        );
+    }
+}
@@ -0,0 +1,11 @@
+// src/decorators/mod.rs
+// Presentation that cuts across formats, added by wrapping a formatter instead of changing it
+// Each decorator is a ReportFormatter holding another one, so they stack:
+//   HeaderFooter::new(Paginated::new(TextFormatter::default(), 60)).footer("Printed {timestamp}")
+// - TableOfContents reworks the report before handing it on: it works with every format
+// - HeaderFooter hands its lines to the formatter underneath (see ReportFormatter::write_framed)
+// - Paginated rewrites the rendered text: it needs a text formatter underneath
+
+pub mod contents;
+pub mod header_footer;
+pub mod pagination;
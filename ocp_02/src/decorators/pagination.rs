@@ -0,0 +1,194 @@
+// src/decorators/pagination.rs
+// Cuts text output into fixed-height pages, for printers and pagers
+// Every page has the same number of lines: the body (padded with blank lines), a blank line
+// and a centered "Page n of N" footer. Pages are separated by a form feed.
+// Under a HeaderFooter, its lines are repeated on every page: the header and a blank line on
+// top, the footer above the page number. They don't count as body lines.
+
+use std::io::{self, Write};
+
+use crate::formatters::ReportFormatter;
+use crate::report::Report;
+
+pub struct Paginated<F> {
+    inner: F,
+    page_height: usize,
+}
+
+impl<F: ReportFormatter> Paginated<F> {
+    // page_height counts every line of the page, footer included
+    pub fn new(inner: F, page_height: usize) -> Self {
+        assert!(
+            page_height >= 3,
+            "a page needs room for a line and its footer"
+        );
+        Self { inner, page_height }
+    }
+}
+
+impl<F: ReportFormatter> ReportFormatter for Paginated<F> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.inner.extensions()
+    }
+
+    fn mime_type(&self) -> &str {
+        self.inner.mime_type()
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        self.write_framed(report, None, None, out)
+    }
+
+    // The whole output is needed first: the footer tells the page count
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let furniture = 2 * usize::from(header.is_some()) + usize::from(footer.is_some());
+        if self.page_height < furniture + 3 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} lines per page leave no room for the header and footer",
+                    self.page_height
+                ),
+            ));
+        }
+        let rendered = String::from_utf8(self.inner.format(report)?).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} output is not text, it can't be paginated",
+                    self.inner.name()
+                ),
+            )
+        })?;
+        let lines: Vec<&str> = rendered.lines().collect();
+        let body = self.page_height - 2 - furniture;
+        let pages: Vec<&[&str]> = if lines.is_empty() {
+            vec![&[]]
+        } else {
+            lines.chunks(body).collect()
+        };
+        let width = lines
+            .iter()
+            .chain(&header)
+            .chain(&footer)
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for (n, page) in pages.iter().enumerate() {
+            if n > 0 {
+                out.write_all(b"\x0c")?;
+            }
+            if let Some(header) = header {
+                writeln!(out, "{}\n", header)?;
+            }
+            for line in page.iter() {
+                writeln!(out, "{}", line)?;
+            }
+            for _ in page.len()..body {
+                writeln!(out)?;
+            }
+            writeln!(out)?;
+            if let Some(footer) = footer {
+                writeln!(out, "{}", footer)?;
+            }
+            let number = format!("Page {} of {}", n + 1, pages.len());
+            let centered = format!("{:^width$}", number, width = width.max(number.len()));
+            writeln!(out, "{}", centered.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decorators::header_footer::HeaderFooter;
+    use crate::formatters::pdf::PdfFormatter;
+    use crate::formatters::text::TextFormatter;
+    use crate::report::Section;
+
+    #[test]
+    fn header_and_footer_are_on_every_page() {
+        let report = Report::new("Items").section(Section::untitled().items(["a", "b", "c"]));
+        let framed = |height| {
+            HeaderFooter::new(Paginated::new(TextFormatter::default(), height))
+                .header("Internal")
+                .footer("Printed")
+                .format(&report)
+        };
+
+        let text = String::from_utf8(framed(7).unwrap()).unwrap();
+
+        let pages: Vec<&str> = text.split('\x0c').collect();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1], "Internal\n\n- b\n- c\n\nPrinted\n Page 2 of 2\n");
+        assert!(framed(5).is_err());
+    }
+
+    #[test]
+    fn every_page_has_the_same_height() {
+        let report = Report::new("Items").section(Section::untitled().items(["a", "b", "c"]));
+
+        let text = String::from_utf8(
+            Paginated::new(TextFormatter::default(), 5)
+                .format(&report)
+                .unwrap(),
+        )
+        .unwrap();
+
+        let pages: Vec<&str> = text.split('\x0c').collect();
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().all(|p| p.lines().count() == 5));
+        assert_eq!(pages[1], "- c\n\n\n\n Page 2 of 2\n");
+        let mut out = Vec::new();
+        assert!(
+            Paginated::new(PdfFormatter::default(), 40)
+                .write(&report, &mut out)
+                .is_err()
+        );
+    }
+
+    // A template or plugin that fails
+    struct Failing;
+
+    impl ReportFormatter for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn mime_type(&self) -> &str {
+            "text/plain"
+        }
+
+        fn write(&self, _: &Report, _: &mut dyn Write) -> io::Result<()> {
+            Err(io::Error::other("plugin exited with code 3"))
+        }
+    }
+
+    #[test]
+    fn errors_of_the_wrapped_formatter_are_returned() {
+        let report = Report::new("T");
+        let mut out = Vec::new();
+
+        let paginated = Paginated::new(Failing, 5).write(&report, &mut out);
+        assert_eq!(
+            paginated.unwrap_err().to_string(),
+            "plugin exited with code 3"
+        );
+    }
+}
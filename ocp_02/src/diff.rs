@@ -0,0 +1,543 @@
+// src/diff.rs
+// Compares two reports of the same kind (this month against last month...)
+// The comparison is a Report itself, so every formatter renders it. DiffFormatter does it in one go:
+// it holds the previous report and formats the differences instead of the report it is given.
+//
+// Sections are matched by heading, blocks by kind and position, entries by label:
+// - items by their label ("Product A: 120 units" -> "Product A"), or their whole text
+// - table rows by their first non-numeric cell, compared on every numeric column
+// - key/value pairs by key
+// Each column of a comparison takes the type of its values: integers, decimals, or text when
+// they mix kinds (a key/value can hold anything). Integers among decimals become decimals.
+
+use std::io::{self, Write};
+
+use crate::formatters::ReportFormatter;
+use crate::report::{Block, Column, ColumnType, Report, Section, Table, Value, parse_item};
+
+// =========================
+// Comparison
+// =========================
+
+pub fn compare(previous: &Report, current: &Report) -> Report {
+    let title = if previous.title == current.title {
+        format!("{}: changes", current.title)
+    } else {
+        format!("{} vs {}", current.title, previous.title)
+    };
+
+    let mut counts = Counts::default();
+    let mut sections = Vec::new();
+    let mut matched = vec![false; previous.sections.len()];
+    for section in &current.sections {
+        let before = (0..previous.sections.len())
+            .find(|&i| !matched[i] && previous.sections[i].heading == section.heading);
+        if let Some(i) = before {
+            matched[i] = true;
+        }
+        let before = before.map(|i| &previous.sections[i]);
+        sections.push(compare_sections(before, Some(section), &mut counts));
+    }
+    for (section, _) in previous.sections.iter().zip(matched).filter(|(_, m)| !m) {
+        sections.push(compare_sections(Some(section), None, &mut counts));
+    }
+
+    let summary = Section::untitled().key_values([
+        ("Changed", Value::Integer(counts.changed)),
+        ("Added", Value::Integer(counts.added)),
+        ("Removed", Value::Integer(counts.removed)),
+    ]);
+    let mut report = Report::new(title).section(summary);
+    report
+        .sections
+        .extend(sections.into_iter().filter(|s| !s.blocks.is_empty()));
+    report
+}
+
+#[derive(Default)]
+struct Counts {
+    changed: i64,
+    added: i64,
+    removed: i64,
+}
+
+fn compare_sections(
+    previous: Option<&Section>,
+    current: Option<&Section>,
+    counts: &mut Counts,
+) -> Section {
+    let heading = current.or(previous).and_then(|s| s.heading.clone());
+    let blocks = |section: Option<&Section>| -> Vec<Entries> {
+        section
+            .map(|s| s.blocks.iter().map(entries).collect())
+            .unwrap_or_default()
+    };
+    let (mut before, after) = (blocks(previous), blocks(current));
+
+    let mut compared = Vec::new();
+    for entries in after {
+        let same_kind = before.iter().position(|b| b.kind == entries.kind);
+        let previous = same_kind.map(|i| before.remove(i));
+        compared.push(compare_entries(previous, Some(entries), counts));
+    }
+    for entries in before {
+        compared.push(compare_entries(Some(entries), None, counts));
+    }
+
+    Section {
+        heading,
+        blocks: compared.into_iter().map(Block::Table).collect(),
+    }
+}
+
+// =========================
+// Entries: any block seen as labelled rows of measures
+// =========================
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Items,
+    Table,
+    KeyValues,
+}
+
+struct Entries {
+    kind: Kind,
+    // Heading of the label column ("Product", "Item"...)
+    label: String,
+    // Name and type of each measure
+    measures: Vec<(String, ColumnType)>,
+    rows: Vec<(String, Vec<Value>)>,
+}
+
+fn entries(block: &Block) -> Entries {
+    match block {
+        Block::Items(items) => {
+            let parsed: Vec<_> = items.iter().map(|i| (i, parse_item(i))).collect();
+            let unit = parsed.iter().find_map(|(_, p)| p.map(|(_, _, unit)| unit));
+            let name = match unit {
+                Some("") => Some("Value".to_string()),
+                Some(unit) => Some(capitalize(unit)),
+                None => None,
+            };
+            let rows: Vec<(String, Vec<Value>)> = parsed
+                .into_iter()
+                .map(|(item, parsed)| match parsed {
+                    Some((label, value, _)) => (label.to_string(), vec![number(value)]),
+                    None => (item.clone(), vec![Value::Empty; name.iter().len()]),
+                })
+                .collect();
+            let measures = name
+                .map(|name| {
+                    let kind = kind_of(rows.iter().map(|(_, v)| &v[0]));
+                    (name, kind.unwrap_or(ColumnType::Decimal))
+                })
+                .into_iter()
+                .collect();
+            Entries {
+                kind: Kind::Items,
+                label: "Item".to_string(),
+                measures,
+                rows,
+            }
+        }
+        Block::Table(table) => {
+            let label = table.columns.iter().position(|c| !c.kind.is_numeric());
+            let numeric: Vec<usize> = (0..table.columns.len())
+                .filter(|&i| table.columns[i].kind.is_numeric())
+                .collect();
+            Entries {
+                kind: Kind::Table,
+                label: label.map_or("Row".to_string(), |i| table.columns[i].name.clone()),
+                measures: numeric
+                    .iter()
+                    .map(|&i| (table.columns[i].name.clone(), table.columns[i].kind))
+                    .collect(),
+                rows: table
+                    .rows
+                    .iter()
+                    .enumerate()
+                    .map(|(r, row)| {
+                        let label = label.map_or((r + 1).to_string(), |i| row[i].to_string());
+                        (label, numeric.iter().map(|&i| row[i].clone()).collect())
+                    })
+                    .collect(),
+            }
+        }
+        Block::KeyValues(pairs) => Entries {
+            kind: Kind::KeyValues,
+            label: "Key".to_string(),
+            measures: vec![(
+                "Value".to_string(),
+                kind_of(pairs.iter().map(|(_, v)| v)).unwrap_or(ColumnType::Text),
+            )],
+            rows: pairs
+                .iter()
+                .map(|(key, value)| (key.clone(), vec![value.clone()]))
+                .collect(),
+        },
+    }
+}
+
+// Whole numbers stay integers, so "120 units" doesn't come back as "120.00"
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        Value::Integer(value as i64)
+    } else {
+        Value::Decimal(value)
+    }
+}
+
+// The one kind of all these values, Decimal for integers and decimals together, Text otherwise.
+// None when they are all empty.
+fn kind_of<'a>(values: impl IntoIterator<Item = &'a Value>) -> Option<ColumnType> {
+    let mut kinds = values.into_iter().filter_map(Value::kind);
+    let first = kinds.next()?;
+    Some(kinds.fold(first, |kind, next| match (kind, next) {
+        (a, b) if a == b => a,
+        (a, b) if a.is_numeric() && b.is_numeric() => ColumnType::Decimal,
+        _ => ColumnType::Text,
+    }))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// One row per label: previous, current, change and change % of every measure, then a status
+fn compare_entries(
+    previous: Option<Entries>,
+    current: Option<Entries>,
+    counts: &mut Counts,
+) -> Table {
+    let shape = current
+        .as_ref()
+        .or(previous.as_ref())
+        .expect("one side at least");
+    let measures = shape.measures.clone();
+    let mut columns = vec![Column::new(shape.label.clone(), ColumnType::Text)];
+    for (name, kind) in &measures {
+        let named = |what: &str| match measures.len() {
+            1 => what.to_string(),
+            _ => format!("{} {}", name, what.to_lowercase()),
+        };
+        columns.push(Column::new(named("Previous"), *kind));
+        columns.push(Column::new(named("Current"), *kind));
+        columns.push(Column::new(named("Change"), *kind));
+        columns.push(Column::new(named("Change %"), ColumnType::Decimal));
+    }
+    columns.push(Column::new("Status", ColumnType::Text));
+
+    let before = previous.map(|e| (e.measures, e.rows)).unwrap_or_default();
+    let after = current.map(|e| e.rows).unwrap_or_default();
+    // A measure missing on one side reads as empty
+    let value = |row: Option<&Vec<Value>>, names: &[(String, ColumnType)], name: &str| {
+        let index = names.iter().position(|(n, _)| n == name)?;
+        row.map(|r| r[index].clone())
+    };
+
+    let mut table = Table::new(columns);
+    let mut matched = vec![false; before.1.len()];
+    let mut pairs = Vec::new();
+    for (label, values) in &after {
+        let found = (0..before.1.len()).find(|&i| !matched[i] && before.1[i].0 == *label);
+        if let Some(i) = found {
+            matched[i] = true;
+        }
+        pairs.push(Pair {
+            label,
+            old: found.map(|i| &before.1[i].1),
+            new: Some(values),
+        });
+    }
+    for ((label, values), _) in before.1.iter().zip(&matched).filter(|(_, m)| !**m) {
+        pairs.push(Pair {
+            label,
+            old: Some(values),
+            new: None,
+        });
+    }
+
+    for Pair { label, old, new } in pairs {
+        let mut cells = vec![Value::text(label)];
+        let mut changed = false;
+        for (name, _) in &measures {
+            let old_value = value(old, &before.0, name).unwrap_or(Value::Empty);
+            let new_value = value(new, &measures, name).unwrap_or(Value::Empty);
+            changed |= old.is_some() && new.is_some() && old_value != new_value;
+            let (change, percent) = change(&old_value, &new_value);
+            cells.extend([old_value, new_value, change, percent]);
+        }
+        let status = match (old, new) {
+            (None, _) => {
+                counts.added += 1;
+                "added"
+            }
+            (_, None) => {
+                counts.removed += 1;
+                "removed"
+            }
+            _ if changed => {
+                counts.changed += 1;
+                "changed"
+            }
+            _ => "unchanged",
+        };
+        cells.push(Value::text(status));
+        table = table.row(cells);
+    }
+    fit_columns(&mut table);
+    table
+}
+
+// Each measure column takes the kind of what it ended up holding: both sides can differ
+// (10 then 5.5), and a change can overflow into a decimal. Change % stays a decimal.
+fn fit_columns(table: &mut Table) {
+    let measures = 1..table.columns.len() - 1;
+    for index in measures.filter(|i| i % 4 != 0) {
+        let kind =
+            kind_of(table.rows.iter().map(|row| &row[index])).unwrap_or(table.columns[index].kind);
+        table.columns[index].kind = kind;
+        if kind == ColumnType::Decimal {
+            for row in &mut table.rows {
+                if let Value::Integer(n) = row[index] {
+                    row[index] = Value::Decimal(n as f64);
+                }
+            }
+        }
+    }
+}
+
+// The same label before and after. A side is missing for added and removed entries.
+struct Pair<'a> {
+    label: &'a str,
+    old: Option<&'a Vec<Value>>,
+    new: Option<&'a Vec<Value>>,
+}
+
+// Absolute and relative change, when both sides are numbers
+fn change(old: &Value, new: &Value) -> (Value, Value) {
+    let as_f64 = |v: &Value| match *v {
+        Value::Integer(n) => Some(n as f64),
+        Value::Decimal(x) => Some(x),
+        _ => None,
+    };
+    let absolute = match (old, new) {
+        (Value::Integer(a), Value::Integer(b)) => match b.checked_sub(*a) {
+            Some(n) => Value::Integer(n),
+            None => Value::Decimal(*b as f64 - *a as f64),
+        },
+        _ => match (as_f64(old), as_f64(new)) {
+            (Some(a), Some(b)) => Value::Decimal(b - a),
+            _ => return (Value::Empty, Value::Empty),
+        },
+    };
+    let percent = match (as_f64(old), as_f64(new)) {
+        (Some(a), Some(b)) if a != 0.0 => Value::Decimal((b - a) / a.abs() * 100.0),
+        _ => Value::Empty,
+    };
+    (absolute, percent)
+}
+
+// =========================
+// Formatter
+// =========================
+
+// Formats what changed since `previous`, with any formatter underneath
+pub struct DiffFormatter<F> {
+    previous: Report,
+    inner: F,
+}
+
+impl<F: ReportFormatter> DiffFormatter<F> {
+    pub fn new(previous: Report, inner: F) -> Self {
+        Self { previous, inner }
+    }
+}
+
+impl<F: ReportFormatter> ReportFormatter for DiffFormatter<F> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.inner.extensions()
+    }
+
+    fn mime_type(&self) -> &str {
+        self.inner.mime_type()
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        self.inner.write(&compare(&self.previous, report), out)
+    }
+
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let changes = compare(&self.previous, report);
+        self.inner.write_framed(&changes, header, footer, out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::csv::CsvFormatter;
+    use crate::formatters::text::TextFormatter;
+    use crate::parsers::ReportParser;
+    use crate::parsers::text::TextParser;
+    use crate::report::Date;
+
+    fn sales(title: &str, rows: &[(&str, i64)]) -> Report {
+        let table = rows.iter().fold(
+            Table::new(vec![
+                Column::new("Product", ColumnType::Text),
+                Column::new("Units", ColumnType::Integer),
+            ]),
+            |table, &(product, units)| table.row(vec![Value::text(product), Value::Integer(units)]),
+        );
+        Report::new(title).section(Section::new("Sales").table(table))
+    }
+
+    #[test]
+    fn rows_are_matched_by_label() {
+        let january = sales("Sales", &[("A", 100), ("B", 50), ("C", 10)]);
+        let february = sales("Sales", &[("B", 50), ("A", 80), ("D", 5)]);
+
+        let diff = compare(&january, &february);
+
+        assert_eq!(diff.title, "Sales: changes");
+        let Block::Table(table) = &diff.sections[1].blocks[0] else {
+            panic!("a comparison is a table");
+        };
+        let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Product", "Previous", "Current", "Change", "Change %", "Status"
+            ]
+        );
+        assert_eq!(
+            table.rows[1],
+            [
+                Value::text("A"),
+                Value::Integer(100),
+                Value::Integer(80),
+                Value::Integer(-20),
+                Value::Decimal(-20.0),
+                Value::text("changed"),
+            ]
+        );
+        let status: Vec<String> = table.rows.iter().map(|r| r[5].to_string()).collect();
+        assert_eq!(status, ["unchanged", "changed", "added", "removed"]);
+        assert_eq!(
+            diff.sections[0].blocks[0],
+            Block::KeyValues(vec![
+                ("Changed".into(), Value::Integer(1)),
+                ("Added".into(), Value::Integer(1)),
+                ("Removed".into(), Value::Integer(1)),
+            ])
+        );
+    }
+
+    #[test]
+    fn items_and_key_values_are_compared_too() {
+        let before = Report::new("R")
+            .section(Section::untitled().items(["A: 10 units", "note"]))
+            .section(Section::new("Totals").key_values([("Revenue", Value::Decimal(0.0))]));
+        let after = Report::new("R")
+            .section(Section::untitled().items(["A: 15 units", "note"]))
+            .section(Section::new("Totals").key_values([("Revenue", Value::Decimal(5.5))]));
+
+        let diff = compare(&before, &after);
+
+        let rows = |section: usize| match &diff.sections[section].blocks[0] {
+            Block::Table(table) => table.rows.clone(),
+            _ => panic!("a comparison is a table"),
+        };
+        assert_eq!(
+            rows(1)[0],
+            [
+                Value::text("A"),
+                Value::Integer(10),
+                Value::Integer(15),
+                Value::Integer(5),
+                Value::Decimal(50.0),
+                Value::text("changed"),
+            ]
+        );
+        assert_eq!(
+            rows(1)[1][1..5],
+            [Value::Empty, Value::Empty, Value::Empty, Value::Empty]
+        );
+        // No percentage from zero
+        assert_eq!(rows(2)[0][3..5], [Value::Decimal(5.5), Value::Empty]);
+        assert_eq!(
+            DiffFormatter::new(before, CsvFormatter::default())
+                .format(&after)
+                .unwrap(),
+            CsvFormatter::default().format(&diff).unwrap()
+        );
+    }
+
+    #[test]
+    fn columns_take_the_kind_of_their_values() {
+        let before = Report::new("R").section(Section::new("Status").key_values([
+            ("State", Value::text("open")),
+            ("Due", Value::Date(Date::new(2025, 3, 1))),
+            ("Units", Value::Integer(i64::MIN)),
+        ]));
+        let after = Report::new("R")
+            .section(Section::new("Status").key_values([
+                ("State", Value::text("closed")),
+                ("Due", Value::Date(Date::new(2025, 3, 1))),
+                ("Units", Value::Integer(i64::MAX)),
+            ]))
+            .section(Section::untitled().items(["A: 10 units", "B: 2.5 units"]));
+
+        let diff = compare(&before, &after);
+
+        let table = |section: usize| match &diff.sections[section].blocks[0] {
+            Block::Table(table) => table.clone(),
+            _ => panic!("a comparison is a table"),
+        };
+        let status = table(1);
+        let kinds: Vec<ColumnType> = status.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ColumnType::Text,
+                ColumnType::Text,
+                ColumnType::Text,
+                ColumnType::Decimal,
+                ColumnType::Decimal,
+                ColumnType::Text
+            ]
+        );
+        assert_eq!(
+            status.rows[0][1..4],
+            [Value::text("open"), Value::text("closed"), Value::Empty]
+        );
+        assert_eq!(status.rows[0][5], Value::text("changed"));
+        assert_eq!(status.rows[1][5], Value::text("unchanged"));
+        // Too large a change for an integer
+        assert_eq!(
+            status.rows[2][3],
+            Value::Decimal(i64::MAX as f64 - i64::MIN as f64)
+        );
+        let items = table(2);
+        assert_eq!(items.columns[1].kind, ColumnType::Decimal);
+        assert_eq!(items.rows[0][2], Value::Decimal(10.0));
+        let text = TextFormatter::default().format(&diff).unwrap();
+        assert_eq!(TextParser.parse(&text).unwrap(), diff);
+    }
+}
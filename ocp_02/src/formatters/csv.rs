@@ -0,0 +1,259 @@
+// src/formatters/csv.rs
+// CSV output (RFC 4180)
+// A report holds several blocks of different shapes, CSV holds one table. So the report is
+// flattened into a "long" table with one record per value, which any CSV tool can filter or pivot:
+//
+//   report,section,heading,block,kind,row,key,type,value
+//   Monthly Sales,1,,1,items,1,,text,Product A: 120 units
+//   Monthly Sales,2,Sales by product,1,table,0,Units,integer,
+//   Monthly Sales,2,Sales by product,1,table,1,Units,integer,120
+//
+// - section/block are 1-based positions, row is 1-based within the block
+// - table columns are declared by row 0 records (key = column name, type = column type)
+// - key is the column name (tables) or the key (key_values), empty for items
+// - type is empty for an empty value
+// - heading is empty for a section without heading, and a quoted "" for an empty heading
+// - a section without blocks, an empty block or a report without sections, still gets one record
+//   with the trailing fields empty, so nothing is lost
+
+use std::io::{self, Write};
+
+use super::ReportFormatter;
+use super::locale::Locale;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report, Value};
+
+pub const HEADER: [&str; 9] = [
+    "report", "section", "heading", "block", "kind", "row", "key", "type", "value",
+];
+
+// The defaults follow RFC 4180 and CsvParser reads them back. Spreadsheets in locales that
+// write decimals with a comma expect ';' between fields and ',' in numbers.
+pub struct CsvFormatter {
+    delimiter: char,
+    locale: Locale,
+}
+
+impl Default for CsvFormatter {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            locale: Locale::default(),
+        }
+    }
+}
+
+impl CsvFormatter {
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    // Fields are separated by the delimiter, records end with CRLF
+    // A field containing the delimiter, a quote, CR or LF (or with surrounding spaces) is quoted
+    // and its quotes are doubled. The field at always_quoted is quoted even when empty.
+    fn write_record(
+        &self,
+        out: &mut dyn Write,
+        fields: &[&str],
+        always_quoted: Option<usize>,
+    ) -> io::Result<()> {
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                write!(out, "{}", self.delimiter)?;
+            }
+            let needs_quotes = field.contains([self.delimiter, '"', '\r', '\n'])
+                || field.starts_with(' ')
+                || field.ends_with(' ')
+                || always_quoted == Some(i);
+            if needs_quotes {
+                write!(out, "\"{}\"", field.replace('"', "\"\""))?;
+            } else {
+                out.write_all(field.as_bytes())?;
+            }
+        }
+        out.write_all(b"\r\n")
+    }
+
+    // Type name and raw text of a value. Decimals keep full precision (no rounding to 2 places).
+    fn value(&self, value: &Value) -> (&'static str, String) {
+        match value {
+            Value::Empty => ("", String::new()),
+            Value::Decimal(x) => (
+                "decimal",
+                self.locale.decimals.localize(&format!("{:?}", x)),
+            ),
+            Value::Date(date) => ("date", self.locale.dates.format(*date)),
+            other => (
+                other.kind().map(|k| k.name()).unwrap_or_default(),
+                other.to_string(),
+            ),
+        }
+    }
+}
+
+// delimiter = ; (or "tab"), and date_style, decimal_locale as for text
+impl Configurable for CsvFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let mut formatter = Self::default().locale(Locale::from_options(options)?);
+        if let Some((value, line)) = options.take_text("delimiter") {
+            let mut chars = value.chars();
+            let delimiter = match (value.as_str(), chars.next(), chars.next()) {
+                ("tab" | "\\t", _, _) => '\t',
+                (_, Some(c), None) if c != '"' && c != '\r' && c != '\n' => c,
+                _ => return Err(options.invalid(line, "delimiter", &value, None)),
+            };
+            formatter = formatter.delimiter(delimiter);
+        }
+        Ok(formatter)
+    }
+}
+
+impl ReportFormatter for CsvFormatter {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["csv"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "text/csv"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        self.write_record(out, &HEADER, None)?;
+        if report.sections.is_empty() {
+            let fields = [&report.title, "", "", "", "", "", "", "", ""];
+            return self.write_record(out, &fields, None);
+        }
+
+        for (s, section) in report.sections.iter().enumerate() {
+            let section_number = (s + 1).to_string();
+            let heading = section.heading.as_deref().unwrap_or("");
+            let empty_heading = (section.heading.as_deref() == Some("")).then_some(2);
+            let record = |out: &mut dyn Write, fields: &[&str]| -> io::Result<()> {
+                let mut all = vec![report.title.as_str(), &section_number, heading];
+                all.extend_from_slice(fields);
+                self.write_record(out, &all, empty_heading)
+            };
+
+            if section.blocks.is_empty() {
+                record(out, &["", "", "", "", "", ""])?;
+            }
+            for (b, block) in section.blocks.iter().enumerate() {
+                let block_number = (b + 1).to_string();
+                match block {
+                    Block::Items(items) => {
+                        if items.is_empty() {
+                            record(out, &[&block_number, "items", "", "", "", ""])?;
+                        }
+                        for (r, item) in items.iter().enumerate() {
+                            let row = (r + 1).to_string();
+                            record(out, &[&block_number, "items", &row, "", "text", item])?;
+                        }
+                    }
+                    Block::Table(table) => {
+                        if table.columns.is_empty() {
+                            record(out, &[&block_number, "table", "", "", "", ""])?;
+                        }
+                        for column in &table.columns {
+                            let kind = column.kind.name();
+                            record(out, &[&block_number, "table", "0", &column.name, kind, ""])?;
+                        }
+                        for (r, cells) in table.rows.iter().enumerate() {
+                            let row = (r + 1).to_string();
+                            for (column, value) in table.columns.iter().zip(cells) {
+                                let (kind, value) = self.value(value);
+                                record(
+                                    out,
+                                    &[&block_number, "table", &row, &column.name, kind, &value],
+                                )?;
+                            }
+                        }
+                    }
+                    Block::KeyValues(pairs) => {
+                        if pairs.is_empty() {
+                            record(out, &[&block_number, "key_values", "", "", "", ""])?;
+                        }
+                        for (r, (key, value)) in pairs.iter().enumerate() {
+                            let row = (r + 1).to_string();
+                            let (kind, value) = self.value(value);
+                            record(out, &[&block_number, "key_values", &row, key, kind, &value])?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    #[test]
+    fn fields_are_quoted_only_when_needed() {
+        let mut out = Vec::new();
+        CsvFormatter::default()
+            .write_record(
+                &mut out,
+                &["plain", "a,b", "say \"hi\"", "two\nlines", " pad", ""],
+                Some(5),
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\" pad\",\"\"\r\n"
+        );
+    }
+
+    #[test]
+    fn one_record_per_item() {
+        let report =
+            Report::new("Sales, 2025").section(Section::new("Top").items(["A: 1", "B: 2"]));
+
+        let csv = String::from_utf8(CsvFormatter::default().format(&report).unwrap()).unwrap();
+
+        assert_eq!(
+            csv,
+            "report,section,heading,block,kind,row,key,type,value\r\n\
+             \"Sales, 2025\",1,Top,1,items,1,,text,A: 1\r\n\
+             \"Sales, 2025\",1,Top,1,items,2,,text,B: 2\r\n"
+        );
+    }
+
+    #[test]
+    fn delimiter_and_decimal_locale_from_options() {
+        let report = Report::new("T")
+            .section(Section::untitled().key_values([("Total; net", Value::Decimal(2.5))]));
+        let mut options = crate::config::parse("[csv]\ndelimiter = ;\ndecimal_locale = de")
+            .unwrap()
+            .remove(0);
+
+        let formatter = CsvFormatter::from_options(&mut options).unwrap();
+
+        let csv = String::from_utf8(formatter.format(&report).unwrap()).unwrap();
+        assert!(csv.ends_with("T;1;;1;key_values;1;\"Total; net\";decimal;2,5\r\n"));
+        let mut tab = crate::config::parse("[csv]\ndelimiter = tab")
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            CsvFormatter::from_options(&mut tab).unwrap().delimiter,
+            '\t'
+        );
+        let mut bad = crate::config::parse("[csv]\ndelimiter = ;;")
+            .unwrap()
+            .remove(0);
+        assert!(CsvFormatter::from_options(&mut bad).is_err());
+    }
+}
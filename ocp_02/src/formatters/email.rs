@@ -0,0 +1,405 @@
+// src/formatters/email.rs
+// A ready-to-send email (RFC 5322 / MIME): the report as plain text and as HTML,
+// in a multipart/alternative body so every mail client shows the best one it can.
+// Other formats (PDF, CSV...) can ride along as base64 attachments; the message then
+// becomes multipart/mixed with the alternative body as its first part.
+// The .eml output can be opened by mail clients or handed to any SMTP tool (sendmail -t...).
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::ReportFormatter;
+use super::html::HtmlFormatter;
+use super::text::TextFormatter;
+use crate::report::{Date, Report};
+
+// "=_" never shows up in base64 or quoted-printable text, so the boundaries can't clash with a body
+const MIXED_BOUNDARY: &str = "=_report_mixed";
+const ALTERNATIVE_BOUNDARY: &str = "=_report_alternative";
+
+pub struct EmailFormatter {
+    from: String,
+    to: Vec<String>,
+    // The report title when not set
+    subject: Option<String>,
+    attachments: Vec<Box<dyn ReportFormatter>>,
+    // Fixed time for reproducible output, the current time otherwise
+    at: Option<SystemTime>,
+}
+
+impl EmailFormatter {
+    // Addresses as they go in the header: "reports@example.com" or "Reports <reports@example.com>"
+    pub fn new(from: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            to: Vec::new(),
+            subject: None,
+            attachments: Vec::new(),
+            at: None,
+        }
+    }
+
+    pub fn to(mut self, address: impl Into<String>) -> Self {
+        self.to.push(address.into());
+        self
+    }
+
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    // The report rendered by this formatter, attached as "<title>.<extension>"
+    pub fn attach(mut self, formatter: impl ReportFormatter + 'static) -> Self {
+        self.attachments.push(Box::new(formatter));
+        self
+    }
+
+    pub fn at(mut self, time: SystemTime) -> Self {
+        self.at = Some(time);
+        self
+    }
+}
+
+impl ReportFormatter for EmailFormatter {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["eml"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "message/rfc822"
+    }
+
+    // Lines end with CRLF, as RFC 5322 wants. The CRLF before a boundary belongs to the boundary.
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let seconds = self
+            .at
+            .unwrap_or_else(SystemTime::now)
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let subject = self.subject.as_deref().unwrap_or(&report.title);
+
+        header(out, "From", &encode_address(&self.from))?;
+        if !self.to.is_empty() {
+            let to: Vec<String> = self.to.iter().map(|a| encode_address(a)).collect();
+            header(out, "To", &to.join(", "))?;
+        }
+        header(out, "Subject", &encode_word(subject))?;
+        header(out, "Date", &rfc5322_date(seconds))?;
+        header(out, "Message-ID", &message_id(&self.from, subject, seconds))?;
+        header(out, "MIME-Version", "1.0")?;
+
+        if self.attachments.is_empty() {
+            return write_alternative(report, out);
+        }
+        header(
+            out,
+            "Content-Type",
+            &format!("multipart/mixed; boundary=\"{}\"", MIXED_BOUNDARY),
+        )?;
+        write!(out, "\r\nThis is a multipart message in MIME format.\r\n")?;
+
+        write!(out, "\r\n--{}\r\n", MIXED_BOUNDARY)?;
+        write_alternative(report, out)?;
+        for attachment in &self.attachments {
+            let file_name = format!(
+                "{}.{}",
+                file_stem(&report.title),
+                attachment.extensions().first().copied().unwrap_or("bin")
+            );
+            write!(out, "\r\n--{}\r\n", MIXED_BOUNDARY)?;
+            header(out, "Content-Type", attachment.mime_type())?;
+            header(out, "Content-Transfer-Encoding", "base64")?;
+            header(
+                out,
+                "Content-Disposition",
+                &format!("attachment; filename=\"{}\"", file_name),
+            )?;
+            out.write_all(b"\r\n")?;
+            write_base64(&attachment.format(report)?, out)?;
+        }
+        write!(out, "\r\n--{}--\r\n", MIXED_BOUNDARY)
+    }
+}
+
+// Content-Type header, blank line and body of the text + HTML alternative
+fn write_alternative(report: &Report, out: &mut dyn Write) -> io::Result<()> {
+    header(
+        out,
+        "Content-Type",
+        &format!(
+            "multipart/alternative; boundary=\"{}\"",
+            ALTERNATIVE_BOUNDARY
+        ),
+    )?;
+    out.write_all(b"\r\n")?;
+    // Least faithful first: clients show the last part they understand
+    let bodies: [(&str, &dyn ReportFormatter); 2] = [
+        ("text/plain", &TextFormatter::default()),
+        ("text/html", &HtmlFormatter::default()),
+    ];
+    for (mime_type, formatter) in bodies {
+        let body = formatter.format(report)?;
+        write!(out, "\r\n--{}\r\n", ALTERNATIVE_BOUNDARY)?;
+        header(
+            out,
+            "Content-Type",
+            &format!("{}; charset=utf-8", mime_type),
+        )?;
+        header(out, "Content-Transfer-Encoding", "quoted-printable")?;
+        out.write_all(b"\r\n")?;
+        write_quoted_printable(&body, out)?;
+    }
+    write!(out, "\r\n--{}--\r\n", ALTERNATIVE_BOUNDARY)
+}
+
+// Line breaks in a value would start a new header: they are flattened to spaces
+fn header(out: &mut dyn Write, name: &str, value: &str) -> io::Result<()> {
+    let value: String = value
+        .chars()
+        .map(|c| if c == '\r' || c == '\n' { ' ' } else { c })
+        .collect();
+    write!(out, "{}: {}\r\n", name, value)
+}
+
+// =========================
+// Encodings
+// =========================
+
+// ASCII text goes as is, anything else as RFC 2047 encoded words of at most 75 characters
+fn encode_word(text: &str) -> String {
+    if text.chars().all(|c| (' '..='~').contains(&c)) {
+        return text.to_string();
+    }
+    // 45 bytes of UTF-8 make 60 base64 characters, plus the 12 of "=?UTF-8?B?" and "?="
+    let mut words = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = (start + 45).min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        words.push(format!(
+            "=?UTF-8?B?{}?=",
+            base64(&text.as_bytes()[start..end])
+        ));
+        start = end;
+    }
+    words.join("\r\n ")
+}
+
+// The display name of "Zoë <zoe@example.com>" is encoded, the address itself stays ASCII
+fn encode_address(address: &str) -> String {
+    match address.rsplit_once('<') {
+        Some((name, rest)) if !name.trim().is_empty() => {
+            format!("{} <{}", encode_word(name.trim()), rest)
+        }
+        _ => address.to_string(),
+    }
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// Base64 in lines of 76 characters
+fn write_base64(bytes: &[u8], out: &mut dyn Write) -> io::Result<()> {
+    for (n, chunk) in bytes.chunks(57).enumerate() {
+        if n > 0 {
+            out.write_all(b"\r\n")?;
+        }
+        out.write_all(base64(chunk).as_bytes())?;
+    }
+    Ok(())
+}
+
+// Quoted-printable (RFC 2045): readable for ASCII text, lines of at most 76 characters
+fn write_quoted_printable(body: &[u8], out: &mut dyn Write) -> io::Result<()> {
+    for (n, line) in body.split(|&b| b == b'\n').enumerate() {
+        if n > 0 {
+            out.write_all(b"\r\n")?;
+        }
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut encoded = String::new();
+        for (i, &byte) in line.iter().enumerate() {
+            let trailing_space = matches!(byte, b' ' | b'\t') && i + 1 == line.len();
+            let piece = match byte {
+                b'!'..=b'<' | b'>'..=b'~' | b' ' | b'\t' if !trailing_space => {
+                    (byte as char).to_string()
+                }
+                _ => format!("={:02X}", byte),
+            };
+            // Soft line break: "=" at the end of a line joins it with the next one
+            if encoded.len() + piece.len() > 75 {
+                write!(out, "{}=\r\n", encoded)?;
+                encoded.clear();
+            }
+            encoded.push_str(&piece);
+        }
+        out.write_all(encoded.as_bytes())?;
+    }
+    Ok(())
+}
+
+// =========================
+// Headers
+// =========================
+
+// "Sat, 01 Feb 2025 09:30:00 +0000"
+fn rfc5322_date(seconds: i64) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (days, seconds) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    let date = Date::from_unix_days(days);
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} +0000",
+        // 1970-01-01 was a Thursday
+        DAYS[days.rem_euclid(7) as usize],
+        date.day,
+        MONTHS[usize::from(date.month) - 1],
+        date.year,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+// Unique enough: the time, the subject and the sender's domain
+fn message_id(from: &str, subject: &str, seconds: i64) -> String {
+    let mut hasher = DefaultHasher::new();
+    (from, subject, seconds).hash(&mut hasher);
+    let address = from
+        .rsplit_once('<')
+        .map_or(from, |(_, address)| address.trim_end_matches('>'));
+    let domain = address
+        .rsplit_once('@')
+        .map_or("localhost", |(_, domain)| domain);
+    format!("<{}.{:016x}@{}>", seconds, hasher.finish(), domain)
+}
+
+// "Monthly Sales" -> "Monthly_Sales": safe in a quoted filename on every system
+fn file_stem(title: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        "report".to_string()
+    } else {
+        stem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::csv::CsvFormatter;
+    use crate::report::Section;
+    use std::time::Duration;
+
+    #[test]
+    fn encodings() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode_word("Ventes été"), "=?UTF-8?B?VmVudGVzIMOpdMOp?=");
+        assert_eq!(
+            encode_address("Zoë <zoe@example.com>"),
+            "=?UTF-8?B?Wm/Dqw==?= <zoe@example.com>"
+        );
+        assert_eq!(encode_address("zoe@example.com"), "zoe@example.com");
+
+        let mut out = Vec::new();
+        let long = format!("{} café \n", "x".repeat(80));
+        write_quoted_printable(long.as_bytes(), &mut out).unwrap();
+        let qp = String::from_utf8(out).unwrap();
+        assert_eq!(qp, format!("{}=\r\nxxxxx caf=C3=A9=20\r\n", "x".repeat(75)));
+        assert_eq!(
+            rfc5322_date(1_738_402_200),
+            "Sat, 01 Feb 2025 09:30:00 +0000"
+        );
+    }
+
+    #[test]
+    fn alternative_bodies_and_attachments() {
+        let report = Report::new("Q1 Sales").section(Section::new("S").items(["x"]));
+        let email = EmailFormatter::new("Reports <reports@example.com>")
+            .to("boss@example.com")
+            .attach(CsvFormatter::default())
+            .at(UNIX_EPOCH + Duration::from_secs(1_738_402_200));
+
+        let message = String::from_utf8(email.format(&report).unwrap()).unwrap();
+
+        assert!(message.starts_with(
+            "From: Reports <reports@example.com>\r\nTo: boss@example.com\r\nSubject: Q1 Sales\r\n"
+        ));
+        assert!(message.contains("@example.com>\r\nMIME-Version: 1.0\r\n"));
+        assert!(message.contains("Content-Type: multipart/mixed; boundary=\"=_report_mixed\""));
+        assert!(message.contains("Content-Type: text/plain; charset=utf-8\r\n"));
+        assert!(message.contains("Content-Type: text/html; charset=utf-8\r\n"));
+        assert!(message.contains("attachment; filename=\"Q1_Sales.csv\"\r\n\r\n"));
+        assert!(message.ends_with("\r\n--=_report_mixed--\r\n"));
+        assert!(!message.replace("\r\n", "").contains('\n'));
+    }
+
+    // A template or plugin that fails
+    struct Failing;
+
+    impl ReportFormatter for Failing {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn mime_type(&self) -> &str {
+            "text/plain"
+        }
+
+        fn write(&self, _: &Report, _: &mut dyn Write) -> io::Result<()> {
+            Err(io::Error::other("plugin exited with code 3"))
+        }
+    }
+
+    #[test]
+    fn a_failing_attachment_fails_the_message() {
+        let email = EmailFormatter::new("reports@example.com").attach(Failing);
+
+        let error = email.format(&Report::new("T")).unwrap_err();
+
+        assert_eq!(error.to_string(), "plugin exited with code 3");
+    }
+}
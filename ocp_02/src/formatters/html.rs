@@ -0,0 +1,252 @@
+// src/formatters/html.rs
+// HTML output
+// A complete, standalone HTML5 document. Every piece of report text is escaped
+// and the look comes from a CSS theme embedded in the <head>.
+
+use std::fs;
+use std::io::{self, Write};
+
+use super::ReportFormatter;
+use super::locale::Locale;
+use super::markup::Escaped;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report, Table, Value};
+
+// The stylesheet embedded in the generated document
+#[derive(Clone, Debug, Default)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+    // No <style> element at all, let the browser (or the host page) decide
+    Unstyled,
+    // Any user supplied CSS
+    Custom(String),
+}
+
+impl Theme {
+    fn css(&self) -> Option<&str> {
+        match self {
+            Theme::Light => Some(LIGHT_CSS),
+            Theme::Dark => Some(DARK_CSS),
+            Theme::Unstyled => None,
+            Theme::Custom(css) => Some(css),
+        }
+    }
+}
+
+const LIGHT_CSS: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 60rem; color: #222; background: #fff; }
+h1 { border-bottom: 2px solid #3366cc; padding-bottom: .25rem; }
+h2 { color: #3366cc; margin-top: 2rem; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: .3rem .6rem; }
+th { background: #eef2fa; text-align: left; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+dt { font-weight: bold; float: left; clear: left; width: 10rem; }
+dd { margin-left: 11rem; }";
+
+const DARK_CSS: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 60rem; color: #ddd; background: #1e1e1e; }
+h1 { border-bottom: 2px solid #6fa8ff; padding-bottom: .25rem; }
+h2 { color: #6fa8ff; margin-top: 2rem; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #444; padding: .3rem .6rem; }
+th { background: #2c2c34; text-align: left; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+dt { font-weight: bold; float: left; clear: left; width: 10rem; }
+dd { margin-left: 11rem; }";
+
+#[derive(Default)]
+pub struct HtmlFormatter {
+    theme: Theme,
+    locale: Locale,
+}
+
+impl HtmlFormatter {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            theme,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+// theme = light | dark | unstyled, or stylesheet = path/to/file.css for a custom theme
+// date_style and decimal_locale as for the text formatter
+impl Configurable for HtmlFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let themes = [
+            ("light", Theme::Light),
+            ("dark", Theme::Dark),
+            ("unstyled", Theme::Unstyled),
+        ];
+        let theme = options.take_choice("theme", &themes)?;
+        let locale = Locale::from_options(options)?;
+        let stylesheet = match options.take_text("stylesheet") {
+            Some((path, line)) => Some(fs::read_to_string(&path).map_err(|e| {
+                ConfigError::new(
+                    line,
+                    format!("[html] cannot read stylesheet {}: {}", path, e),
+                )
+            })?),
+            None => None,
+        };
+        match (theme, stylesheet) {
+            (Some(_), Some(_)) => Err(ConfigError::new(
+                options.line,
+                "[html] set either theme or stylesheet, not both",
+            )),
+            (_, Some(css)) => Ok(Self::new(Theme::Custom(css)).locale(locale)),
+            (theme, None) => Ok(Self::new(theme.unwrap_or_default()).locale(locale)),
+        }
+    }
+}
+
+impl ReportFormatter for HtmlFormatter {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["html", "htm"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "text/html"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        self.write_framed(report, None, None, out)
+    }
+
+    // In <header> and <footer> elements around the report
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let title = Escaped(&report.title);
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html lang=\"en\">")?;
+        writeln!(out, "<head>")?;
+        writeln!(out, "<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>{}</title>", title)?;
+        if let Some(css) = self.theme.css() {
+            // CSS isn't parsed as markup: only a closing tag could break out of <style>
+            writeln!(out, "<style>\n{}\n</style>", css.replace("</", "<\\/"))?;
+        }
+        writeln!(out, "</head>")?;
+        writeln!(out, "<body>")?;
+        if let Some(header) = header {
+            writeln!(out, "<header>{}</header>", Escaped(header))?;
+        }
+
+        writeln!(out, "<h1>{}</h1>", title)?;
+        for section in &report.sections {
+            if let Some(heading) = &section.heading {
+                writeln!(out, "<h2>{}</h2>", Escaped(heading))?;
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        writeln!(out, "<ul>")?;
+                        for item in items {
+                            writeln!(out, "  <li>{}</li>", Escaped(item))?;
+                        }
+                        writeln!(out, "</ul>")?;
+                    }
+                    Block::Table(table) => write_table(table, &self.locale, out)?,
+                    Block::KeyValues(pairs) => write_key_values(pairs, &self.locale, out)?,
+                }
+            }
+        }
+
+        if let Some(footer) = footer {
+            writeln!(out, "<footer>{}</footer>", Escaped(footer))?;
+        }
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")
+    }
+}
+
+fn write_table(table: &Table, locale: &Locale, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "<table>\n  <thead>\n    <tr>")?;
+    for column in &table.columns {
+        write!(out, "<th>{}</th>", Escaped(&column.name))?;
+    }
+    write!(out, "</tr>\n  </thead>\n  <tbody>\n")?;
+    for row in &table.rows {
+        write!(out, "    <tr>")?;
+        for (value, column) in row.iter().zip(&table.columns) {
+            if column.kind.is_numeric() {
+                write!(
+                    out,
+                    "<td class=\"num\">{}</td>",
+                    Escaped(locale.value(value))
+                )?;
+            } else {
+                write!(out, "<td>{}</td>", Escaped(locale.value(value)))?;
+            }
+        }
+        writeln!(out, "</tr>")?;
+    }
+    write!(out, "  </tbody>\n</table>\n")
+}
+
+fn write_key_values(
+    pairs: &[(String, Value)],
+    locale: &Locale,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(out, "<dl>")?;
+    for (key, value) in pairs {
+        writeln!(
+            out,
+            "  <dt>{}</dt><dd>{}</dd>",
+            Escaped(key),
+            Escaped(locale.value(value))
+        )?;
+    }
+    writeln!(out, "</dl>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    #[test]
+    fn report_text_is_escaped() {
+        let report = Report::new("R&D <beta>").section(Section::new("Q&A").items(["<script>"]));
+
+        let html = String::from_utf8(HtmlFormatter::default().format(&report).unwrap()).unwrap();
+
+        assert!(html.contains("<title>R&amp;D &lt;beta&gt;</title>"));
+        assert!(html.contains("<h1>R&amp;D &lt;beta&gt;</h1>"));
+        assert!(html.contains("<h2>Q&amp;A</h2>"));
+        assert!(html.contains("<li>&lt;script&gt;</li>"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn theme_is_embedded_in_head() {
+        let report = Report::new("Monthly Sales");
+
+        let dark =
+            String::from_utf8(HtmlFormatter::new(Theme::Dark).format(&report).unwrap()).unwrap();
+        let bare = String::from_utf8(HtmlFormatter::new(Theme::Unstyled).format(&report).unwrap())
+            .unwrap();
+
+        assert!(dark.starts_with("<!DOCTYPE html>"));
+        assert!(dark.contains("<style>\n") && dark.contains("#1e1e1e"));
+        assert!(!bare.contains("<style>"));
+    }
+}
@@ -0,0 +1,264 @@
+// src/formatters/json.rs
+// JSON output (RFC 8259)
+// Cells keep their type: integers and decimals are numbers, dates are ISO 8601 strings,
+// empty cells are null. A cell of another type than its column's, or a decimal JSON has no
+// number for (NaN, inf), is tagged like a key/value: { "type": "text", "value": "120" }.
+
+use std::io::{self, Write};
+
+use super::{ReportFormatter, take_indent};
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, ColumnType, Report, Value};
+
+pub struct JsonFormatter {
+    // Spaces per nesting level
+    indent: usize,
+}
+
+impl Default for JsonFormatter {
+    fn default() -> Self {
+        Self { indent: 2 }
+    }
+}
+
+impl JsonFormatter {
+    pub fn indent(mut self, spaces: usize) -> Self {
+        self.indent = spaces;
+        self
+    }
+
+    // A new line indented to the nesting level
+    fn line(&self, level: usize) -> String {
+        format!("\n{}", " ".repeat(self.indent * level))
+    }
+
+    fn write_block(&self, block: &Block, out: &mut dyn Write) -> io::Result<()> {
+        let (field, element) = (self.line(5), self.line(6));
+        match block {
+            Block::Items(items) => {
+                let items: Vec<String> = items.iter().map(|i| json_string(i)).collect();
+                write!(
+                    out,
+                    "{{ \"type\": \"items\", \"items\": [{}] }}",
+                    items.join(", ")
+                )
+            }
+            Block::Table(table) => {
+                let columns: Vec<String> = table
+                    .columns
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "{{ \"name\": {}, \"type\": \"{}\" }}",
+                            json_string(&c.name),
+                            c.kind.name()
+                        )
+                    })
+                    .collect();
+                write!(
+                    out,
+                    "{{{field}\"type\": \"table\",{field}\"columns\": [{}],{field}\"rows\": [",
+                    columns.join(", ")
+                )?;
+                for (i, row) in table.rows.iter().enumerate() {
+                    let cells: Vec<String> = row
+                        .iter()
+                        .zip(&table.columns)
+                        .map(|(value, column)| json_cell(value, column.kind))
+                        .collect();
+                    write!(
+                        out,
+                        "{}{}[{}]",
+                        if i == 0 { "" } else { "," },
+                        element,
+                        cells.join(", ")
+                    )?;
+                }
+                if !table.rows.is_empty() {
+                    write!(out, "{}", field)?;
+                }
+                write!(out, "]{}}}", self.line(4))
+            }
+            Block::KeyValues(pairs) => {
+                write!(
+                    out,
+                    "{{{field}\"type\": \"key_values\",{field}\"entries\": ["
+                )?;
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    let kind = match value.kind() {
+                        Some(kind) => format!("\"{}\"", kind.name()),
+                        None => "null".to_string(),
+                    };
+                    write!(
+                        out,
+                        "{}{}{{ \"key\": {}, \"type\": {}, \"value\": {} }}",
+                        if i == 0 { "" } else { "," },
+                        element,
+                        json_string(key),
+                        kind,
+                        json_value(value)
+                    )?;
+                }
+                if !pairs.is_empty() {
+                    write!(out, "{}", field)?;
+                }
+                write!(out, "]{}}}", self.line(4))
+            }
+        }
+    }
+}
+
+// indent = 4
+impl Configurable for JsonFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let mut formatter = Self::default();
+        if let Some(indent) = take_indent(options)? {
+            formatter = formatter.indent(indent);
+        }
+        Ok(formatter)
+    }
+}
+
+impl ReportFormatter for JsonFormatter {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let (top, section, key, block) = (self.line(1), self.line(2), self.line(3), self.line(4));
+        write!(out, "{{{}\"title\": {},", top, json_string(&report.title))?;
+        write!(out, "{}\"sections\": [", top)?;
+        for (i, s) in report.sections.iter().enumerate() {
+            write!(out, "{}{}{{", if i == 0 { "" } else { "," }, section)?;
+            match &s.heading {
+                Some(heading) => write!(out, "{}\"heading\": {},", key, json_string(heading))?,
+                None => write!(out, "{}\"heading\": null,", key)?,
+            }
+            write!(out, "{}\"blocks\": [", key)?;
+            for (j, b) in s.blocks.iter().enumerate() {
+                write!(out, "{}{}", if j == 0 { "" } else { "," }, block)?;
+                self.write_block(b, out)?;
+            }
+            if !s.blocks.is_empty() {
+                write!(out, "{}", key)?;
+            }
+            write!(out, "]{}}}", section)?;
+        }
+        if !report.sections.is_empty() {
+            write!(out, "{}", top)?;
+        }
+        writeln!(out, "]\n}}")
+    }
+}
+
+fn json_value(value: &Value) -> String {
+    match value {
+        Value::Empty => "null".to_string(),
+        Value::Text(s) => json_string(s),
+        Value::Integer(n) => n.to_string(),
+        // Shortest representation that reads back to the same f64
+        Value::Decimal(x) if x.is_finite() => format!("{:?}", x),
+        // JSON has no NaN or Infinity: a string, only valid next to its type
+        Value::Decimal(x) => json_string(&x.to_string()),
+        Value::Date(d) => format!("\"{}\"", d),
+    }
+}
+
+// As is when the column type reads it back, tagged with its own type otherwise
+fn json_cell(value: &Value, column: ColumnType) -> String {
+    match value.kind() {
+        Some(kind) if kind != column || matches!(value, Value::Decimal(x) if !x.is_finite()) => {
+            format!(
+                "{{ \"type\": \"{}\", \"value\": {} }}",
+                kind.name(),
+                json_value(value)
+            )
+        }
+        _ => json_value(value),
+    }
+}
+
+// A double-quoted JSON string: quote, backslash and control characters are escaped
+// Also valid as a YAML double-quoted scalar
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7F}' => {
+                out.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Column, ColumnType, Section, Table};
+
+    #[test]
+    fn strings_are_escaped() {
+        assert_eq!(
+            json_string("say \"hi\"\\\n\u{1}"),
+            "\"say \\\"hi\\\"\\\\\\n\\u0001\""
+        );
+    }
+
+    #[test]
+    fn cells_keep_their_type() {
+        let report = Report::new("T").section(
+            Section::untitled().table(
+                Table::new(vec![
+                    Column::new("n", ColumnType::Integer),
+                    Column::new("x", ColumnType::Decimal),
+                    Column::new("s", ColumnType::Text),
+                ])
+                .row(vec![Value::Integer(3), Value::Decimal(2.5), Value::Empty]),
+            ),
+        );
+
+        let json = String::from_utf8(JsonFormatter::default().format(&report).unwrap()).unwrap();
+
+        assert!(json.contains("[3, 2.5, null]"));
+    }
+
+    #[test]
+    fn cells_of_another_type_are_tagged() {
+        let report = Report::new("T").section(
+            Section::untitled().table(
+                Table::new(vec![
+                    Column::new("d", ColumnType::Date),
+                    Column::new("x", ColumnType::Decimal),
+                ])
+                .row(vec![
+                    Value::text("2025-01-30"),
+                    Value::Decimal(f64::INFINITY),
+                ]),
+            ),
+        );
+
+        let json = String::from_utf8(JsonFormatter::default().format(&report).unwrap()).unwrap();
+
+        assert!(json.contains(
+            "[{ \"type\": \"text\", \"value\": \"2025-01-30\" }, \
+             { \"type\": \"decimal\", \"value\": \"inf\" }]"
+        ));
+    }
+}
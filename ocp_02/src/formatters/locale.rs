@@ -0,0 +1,175 @@
+// src/formatters/locale.rs
+// How the formatters written for people (text, Markdown, HTML, CSV, PDF) show dates and decimals
+// The defaults are the neutral forms the parsers read back: 2025-01-30 and 7749.20
+//
+//   date_style = iso | dmy | mdy | long       2025-01-30, 30/01/2025, 01/30/2025, 30 January 2025
+//   decimal_locale = plain | en | de | fr     7749.20, 7,749.20, 7.749,20, 7 749,20
+
+use crate::config::{ConfigError, Options};
+use crate::report::{Date, Value};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DateStyle {
+    #[default]
+    Iso,
+    DayMonthYear,
+    MonthDayYear,
+    Long,
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+impl DateStyle {
+    pub fn format(self, date: Date) -> String {
+        let Date { year, month, day } = date;
+        match self {
+            DateStyle::Iso => date.to_string(),
+            DateStyle::DayMonthYear => format!("{:02}/{:02}/{:04}", day, month, year),
+            DateStyle::MonthDayYear => format!("{:02}/{:02}/{:04}", month, day, year),
+            DateStyle::Long => format!("{} {} {}", day, MONTHS[usize::from(month) - 1], year),
+        }
+    }
+
+    // Reads date_style alone, for the formatters that leave decimals to their reader
+    pub fn from_options(options: &mut Options) -> Result<Option<Self>, ConfigError> {
+        let styles = [
+            ("iso", DateStyle::Iso),
+            ("dmy", DateStyle::DayMonthYear),
+            ("mdy", DateStyle::MonthDayYear),
+            ("long", DateStyle::Long),
+        ];
+        options.take_choice("date_style", &styles)
+    }
+}
+
+// The decimal separator, and the one between groups of thousands if any
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DecimalLocale {
+    pub separator: char,
+    pub grouping: Option<char>,
+}
+
+impl DecimalLocale {
+    pub const PLAIN: Self = Self::new('.', None);
+    pub const ENGLISH: Self = Self::new('.', Some(','));
+    pub const GERMAN: Self = Self::new(',', Some('.'));
+    // French typography groups with a narrow no-break space
+    pub const FRENCH: Self = Self::new(',', Some('\u{202F}'));
+
+    pub const fn new(separator: char, grouping: Option<char>) -> Self {
+        Self {
+            separator,
+            grouping,
+        }
+    }
+
+    // Rewrites a number written the Rust way ("-7749.2", "1e-7"...)
+    pub fn localize(self, number: &str) -> String {
+        let (sign, digits) = number.split_at(usize::from(number.starts_with('-')));
+        let end = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        let (whole, rest) = digits.split_at(end);
+        let mut out = String::from(sign);
+        for (i, digit) in whole.chars().enumerate() {
+            if let Some(grouping) = self.grouping
+                && i > 0
+                && (whole.len() - i) % 3 == 0
+            {
+                out.push(grouping);
+            }
+            out.push(digit);
+        }
+        match rest.strip_prefix('.') {
+            Some(fraction) => {
+                out.push(self.separator);
+                out.push_str(fraction);
+            }
+            None => out.push_str(rest),
+        }
+        out
+    }
+}
+
+impl Default for DecimalLocale {
+    fn default() -> Self {
+        Self::PLAIN
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Locale {
+    pub dates: DateStyle,
+    pub decimals: DecimalLocale,
+}
+
+impl Locale {
+    // Like the value's Display, with dates and decimals written the local way
+    // Integers are left alone: a year or a product code must not become "2,025"
+    pub fn value(&self, value: &Value) -> String {
+        match value {
+            Value::Decimal(_) => self.decimals.localize(&value.to_string()),
+            Value::Date(date) => self.dates.format(*date),
+            other => other.to_string(),
+        }
+    }
+
+    // Reads date_style and decimal_locale, for the Configurable formatters that take them
+    pub fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let locales = [
+            ("plain", DecimalLocale::PLAIN),
+            ("en", DecimalLocale::ENGLISH),
+            ("de", DecimalLocale::GERMAN),
+            ("fr", DecimalLocale::FRENCH),
+        ];
+        Ok(Self {
+            dates: DateStyle::from_options(options)?.unwrap_or_default(),
+            decimals: options
+                .take_choice("decimal_locale", &locales)?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_and_decimals_the_local_way() {
+        let date = Date::new(2025, 1, 30);
+        assert_eq!(DateStyle::Iso.format(date), "2025-01-30");
+        assert_eq!(DateStyle::DayMonthYear.format(date), "30/01/2025");
+        assert_eq!(DateStyle::MonthDayYear.format(date), "01/30/2025");
+        assert_eq!(DateStyle::Long.format(date), "30 January 2025");
+
+        assert_eq!(DecimalLocale::PLAIN.localize("-1234567.5"), "-1234567.5");
+        assert_eq!(
+            DecimalLocale::ENGLISH.localize("-1234567.5"),
+            "-1,234,567.5"
+        );
+        assert_eq!(DecimalLocale::GERMAN.localize("7749.20"), "7.749,20");
+        assert_eq!(DecimalLocale::FRENCH.localize("749.2"), "749,2");
+        assert_eq!(DecimalLocale::GERMAN.localize("1e-7"), "1e-7");
+
+        let locale = Locale {
+            dates: DateStyle::Long,
+            decimals: DecimalLocale::GERMAN,
+        };
+        assert_eq!(locale.value(&Value::Decimal(7749.2)), "7.749,20");
+        assert_eq!(locale.value(&Value::Integer(2025)), "2025");
+    }
+}
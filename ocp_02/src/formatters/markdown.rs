@@ -0,0 +1,166 @@
+// src/formatters/markdown.rs
+// Markdown output (CommonMark + GitHub tables)
+// Characters with a Markdown meaning are backslash-escaped so report text always shows up verbatim.
+
+use std::io::{self, Write};
+
+use super::ReportFormatter;
+use super::locale::Locale;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report, Table, Value};
+
+#[derive(Default)]
+pub struct MarkdownFormatter {
+    locale: Locale,
+}
+
+impl MarkdownFormatter {
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+// date_style = long, decimal_locale = en
+impl Configurable for MarkdownFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        Ok(Self::default().locale(Locale::from_options(options)?))
+    }
+}
+
+impl ReportFormatter for MarkdownFormatter {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["md", "markdown"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "text/markdown"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "# {}", md_escape(&report.title))?;
+        for section in &report.sections {
+            if let Some(heading) = &section.heading {
+                write!(out, "\n## {}\n", md_escape(heading))?;
+            }
+            for block in &section.blocks {
+                writeln!(out)?;
+                match block {
+                    Block::Items(items) => {
+                        for item in items {
+                            writeln!(out, "- {}", md_escape(item))?;
+                        }
+                    }
+                    Block::Table(table) => write_table(table, &self.locale, out)?,
+                    Block::KeyValues(pairs) => write_key_values(pairs, &self.locale, out)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // A paragraph above the title, and one below a horizontal rule
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        if let Some(header) = header {
+            writeln!(out, "{}\n", md_escape(header))?;
+        }
+        self.write(report, out)?;
+        if let Some(footer) = footer {
+            writeln!(out, "\n---\n\n{}", md_escape(footer))?;
+        }
+        Ok(())
+    }
+}
+
+fn write_table(table: &Table, locale: &Locale, out: &mut dyn Write) -> io::Result<()> {
+    let header: Vec<String> = table.columns.iter().map(|c| md_escape(&c.name)).collect();
+    writeln!(out, "| {} |", header.join(" | "))?;
+    let alignment: Vec<&str> = table
+        .columns
+        .iter()
+        .map(|c| if c.kind.is_numeric() { "---:" } else { "---" })
+        .collect();
+    writeln!(out, "| {} |", alignment.join(" | "))?;
+    for row in &table.rows {
+        let cells: Vec<String> = row.iter().map(|v| md_escape(&locale.value(v))).collect();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+}
+
+fn write_key_values(
+    pairs: &[(String, Value)],
+    locale: &Locale,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    for (key, value) in pairs {
+        writeln!(
+            out,
+            "- **{}:** {}",
+            md_escape(key),
+            md_escape(&locale.value(value))
+        )?;
+    }
+    Ok(())
+}
+
+// Backslash-escape inline markup (emphasis, code, links, HTML, tables, entities)
+// Line breaks would end the list item or table row, they become spaces
+// A leading character that would start a block (heading, list, quote) is escaped too
+fn md_escape(text: &str) -> String {
+    // "1. " or "1) " at the start would open an ordered list ("2398.80" would not)
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    let after = &text[digits..];
+    let ordered_marker = digits > 0
+        && (after.starts_with(['.', ')']))
+        && after[1..].chars().next().is_none_or(char::is_whitespace);
+    let mut out = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '.' | ')' if i == digits && ordered_marker => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '&' | '~' | '!' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '#' | '-' | '+' | '=' if i == 0 => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\r' | '\n' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markup_characters_are_escaped() {
+        assert_eq!(md_escape("Product A: 120 units"), "Product A: 120 units");
+        assert_eq!(md_escape("R&D <beta>"), "R\\&D \\<beta\\>");
+        assert_eq!(md_escape("a|b *c* [d]"), "a\\|b \\*c\\* \\[d\\]");
+        assert_eq!(
+            md_escape("# not a title - really"),
+            "\\# not a title - really"
+        );
+        assert_eq!(md_escape("1. first"), "1\\. first");
+        assert_eq!(md_escape("2398.80"), "2398.80");
+        assert_eq!(md_escape("two\nlines"), "two lines");
+    }
+}
@@ -0,0 +1,72 @@
+// src/formatters/markup.rs
+// Helpers shared by the markup formatters (HTML, XML)
+
+use std::fmt::{self, Display, Write};
+
+// Wraps any Display value so that it is written with &, <, >, " and ' replaced by entities
+// Safe both in text content and in quoted attribute values, for HTML and XML alike
+// Characters XML 1.0 forbids (C0 controls but tab and line ends, U+FFFE, U+FFFF) can't be
+// written even as references: they become U+FFFD, the replacement character
+// Escaping happens while formatting: nothing is allocated, it streams with write!()
+pub struct Escaped<T>(pub T);
+
+impl<T: Display> Display for Escaped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(EscapingWriter(f), "{}", self.0)
+    }
+}
+
+struct EscapingWriter<'a, 'b>(&'a mut fmt::Formatter<'b>);
+
+impl Write for EscapingWriter<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+        while let Some(i) = rest.find(|c| matches!(c, '&' | '<' | '>' | '"' | '\'') || forbidden(c))
+        {
+            self.0.write_str(&rest[..i])?;
+            let c = rest[i..].chars().next().expect("find() stops on a char");
+            self.0.write_str(match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\'' => "&#39;",
+                _ => "\u{FFFD}",
+            })?;
+            rest = &rest[i + c.len_utf8()..];
+        }
+        self.0.write_str(rest)
+    }
+}
+
+// Not a Char of the XML 1.0 grammar (surrogates are not chars in Rust)
+fn forbidden(c: char) -> bool {
+    (c < ' ' && !matches!(c, '\t' | '\n' | '\r')) || matches!(c, '\u{FFFE}' | '\u{FFFF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_characters_become_entities() {
+        assert_eq!(
+            Escaped("R&D <beta> \"quoted\" it's").to_string(),
+            "R&amp;D &lt;beta&gt; &quot;quoted&quot; it&#39;s"
+        );
+        assert_eq!(
+            Escaped("Product A: 120 units").to_string(),
+            "Product A: 120 units"
+        );
+        assert_eq!(Escaped(42).to_string(), "42");
+    }
+
+    #[test]
+    fn characters_xml_forbids_are_replaced() {
+        assert_eq!(
+            Escaped("a\u{1}b\u{1b}c\u{FFFF}é<").to_string(),
+            "a\u{FFFD}b\u{FFFD}c\u{FFFD}é&lt;"
+        );
+        assert_eq!(Escaped("tab\tline\r\n").to_string(), "tab\tline\r\n");
+    }
+}
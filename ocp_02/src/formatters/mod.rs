@@ -0,0 +1,136 @@
+// src/formatters/mod.rs
+// Every output format lives in its own module. Adding one never touches Report.
+
+pub mod csv;
+pub mod email;
+pub mod html;
+pub mod json;
+pub mod locale;
+pub mod markdown;
+mod markup;
+pub mod pdf;
+pub mod svg;
+pub mod template;
+pub mod terminal;
+pub mod text;
+pub mod xlsx;
+pub mod xml;
+pub mod yaml;
+mod zip;
+
+use std::io::{self, Write};
+
+use crate::config::{ConfigError, Options};
+use crate::report::{Report, Section};
+
+// If a type wants to have the ReportFormatter trait it must describe itself and implement .write()
+// .write() receives the whole structured report and renders each block natively
+// Output is streamed as bytes to any sink (file, socket, stdout...): text formats are UTF-8,
+// binary formats (PDF...) are not text at all
+pub trait ReportFormatter: Send + Sync {
+    // Short, unique, lowercase name ("html", "pdf"...) used to look the formatter up
+    fn name(&self) -> &str;
+    // File extensions without the dot, preferred one first
+    fn extensions(&self) -> &[&str];
+    fn mime_type(&self) -> &str;
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()>;
+
+    // The report between a header line and a footer line (see decorators/header_footer.rs)
+    // Formats with a place for them (text, Markdown, HTML, pages...) override this. The others
+    // get them as an untitled section before and after the report's own sections.
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        if header.is_none() && footer.is_none() {
+            return self.write(report, out);
+        }
+        let line = |text: &str| Section::untitled().items([text]);
+        let mut framed = Report::new(report.title.clone());
+        framed.sections.extend(header.map(line));
+        framed.sections.extend(report.sections.iter().cloned());
+        framed.sections.extend(footer.map(line));
+        self.write(&framed, out)
+    }
+
+    // Renders everything in memory
+    // Writing to a Vec never fails, so an error here can only come from the formatter itself
+    // (a template, a plugin, a report it can't show...)
+    fn format(&self, report: &Report) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write(report, &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+// indent = spaces per nesting level, for the formatters that nest (JSON, XML, YAML)
+pub(crate) fn take_indent(options: &mut Options) -> Result<Option<usize>, ConfigError> {
+    let Some((value, line)) = options.take_text("indent") else {
+        return Ok(None);
+    };
+    match value.parse() {
+        Ok(indent @ 1..=8) => Ok(Some(indent)),
+        _ => Err(options.invalid(line, "indent", &value, Some(&["1 to 8"]))),
+    }
+}
+
+// A borrowed or boxed formatter is a formatter too, so it can be wrapped (see decorators/)
+impl<F: ReportFormatter + ?Sized> ReportFormatter for &F {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        (**self).extensions()
+    }
+
+    fn mime_type(&self) -> &str {
+        (**self).mime_type()
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        (**self).write(report, out)
+    }
+
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        (**self).write_framed(report, header, footer, out)
+    }
+}
+
+impl<F: ReportFormatter + ?Sized> ReportFormatter for Box<F> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        (**self).extensions()
+    }
+
+    fn mime_type(&self) -> &str {
+        (**self).mime_type()
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        (**self).write(report, out)
+    }
+
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        (**self).write_framed(report, header, footer, out)
+    }
+}
@@ -0,0 +1,599 @@
+// src/formatters/pdf.rs
+// PDF output
+// A minimal PDF 1.4 writer: A4 or Letter pages, standard base-14 fonts (no embedding),
+// uncompressed content streams. Good enough for any viewer, no dependency needed.
+// Pages are written to the sink as soon as they are full, so memory use is bounded by one page.
+
+use std::io::{self, Write};
+
+use super::ReportFormatter;
+use super::locale::Locale;
+use super::text::table_lines;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report};
+
+const MARGIN: f32 = 56.0;
+const BODY_SIZE: f32 = 11.0;
+const TABLE_SIZE: f32 = 9.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PageSize {
+    #[default]
+    A4,
+    Letter,
+}
+
+impl PageSize {
+    // Width and height in points (1/72 inch)
+    fn points(self) -> (f32, f32) {
+        match self {
+            PageSize::A4 => (595.0, 842.0),
+            PageSize::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PdfFormatter {
+    page_size: PageSize,
+    locale: Locale,
+}
+
+impl PdfFormatter {
+    pub fn page_size(mut self, page_size: PageSize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+// page_size = a4 | letter
+// date_style and decimal_locale as for the text formatter
+impl Configurable for PdfFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let sizes = [("a4", PageSize::A4), ("letter", PageSize::Letter)];
+        Ok(Self::default()
+            .page_size(
+                options
+                    .take_choice("page_size", &sizes)?
+                    .unwrap_or_default(),
+            )
+            .locale(Locale::from_options(options)?))
+    }
+}
+
+impl ReportFormatter for PdfFormatter {
+    fn name(&self) -> &str {
+        "pdf"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pdf"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "application/pdf"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        self.write_framed(report, None, None, out)
+    }
+
+    // Header and footer are repeated on every page, in the margins
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let mut layout = Layout::new(out, self.page_size)?;
+        layout.header = header.map(str::to_string);
+        layout.footer = footer.map(str::to_string);
+
+        layout.paragraph(Font::HelveticaBold, 18.0, MARGIN, &report.title)?;
+        layout.skip(8.0);
+
+        for section in &report.sections {
+            if let Some(heading) = &section.heading {
+                layout.skip(6.0);
+                layout.paragraph(Font::HelveticaBold, 13.0, MARGIN, heading)?;
+                layout.skip(2.0);
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        for item in items {
+                            layout.bullet(item)?;
+                        }
+                    }
+                    Block::Table(table) => {
+                        // Monospace keeps the columns aligned; shrink wide tables to fit the page
+                        let lines = table_lines(table, &self.locale);
+                        let widest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+                        let fit = (layout.width - 2.0 * MARGIN) / (widest.max(1) as f32 * 0.6);
+                        let size = TABLE_SIZE.min(fit);
+                        for (i, line) in lines.iter().enumerate() {
+                            let font = if i == 0 {
+                                Font::CourierBold
+                            } else {
+                                Font::Courier
+                            };
+                            layout.line(font, size, MARGIN, line)?;
+                        }
+                    }
+                    Block::KeyValues(pairs) => {
+                        let key_width = pairs
+                            .iter()
+                            .map(|(k, _)| Font::HelveticaBold.text_width(k, BODY_SIZE))
+                            .fold(0.0, f32::max);
+                        for (key, value) in pairs {
+                            let value = self.locale.value(value);
+                            layout.key_value(key, &value, key_width + 12.0)?;
+                        }
+                    }
+                }
+                layout.skip(4.0);
+            }
+        }
+
+        layout.finish(&report.title)
+    }
+}
+
+// =========================
+// Fonts
+// =========================
+
+#[derive(Clone, Copy, PartialEq)]
+enum Font {
+    Helvetica,
+    HelveticaBold,
+    Courier,
+    CourierBold,
+}
+
+impl Font {
+    const ALL: [Font; 4] = [
+        Font::Helvetica,
+        Font::HelveticaBold,
+        Font::Courier,
+        Font::CourierBold,
+    ];
+
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Helvetica => "F1",
+            Font::HelveticaBold => "F2",
+            Font::Courier => "F3",
+            Font::CourierBold => "F4",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Helvetica => "Helvetica",
+            Font::HelveticaBold => "Helvetica-Bold",
+            Font::Courier => "Courier",
+            Font::CourierBold => "Courier-Bold",
+        }
+    }
+
+    // Glyph width in 1/1000 em, from the Adobe AFM metrics of the base-14 fonts
+    fn glyph_width(self, c: char) -> u16 {
+        match self {
+            Font::Courier | Font::CourierBold => 600,
+            Font::Helvetica => ascii_width(&HELVETICA_WIDTHS, c),
+            Font::HelveticaBold => ascii_width(&HELVETICA_BOLD_WIDTHS, c),
+        }
+    }
+
+    fn text_width(self, text: &str, size: f32) -> f32 {
+        text.chars()
+            .map(|c| self.glyph_width(c) as f32)
+            .sum::<f32>()
+            * size
+            / 1000.0
+    }
+}
+
+fn ascii_width(table: &[u16; 95], c: char) -> u16 {
+    match c {
+        ' '..='~' => table[c as usize - 32],
+        _ => 556,
+    }
+}
+
+#[rustfmt::skip]
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+#[rustfmt::skip]
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+// Base-14 fonts use WinAnsiEncoding (Windows-1252)
+// Characters outside of it are replaced by '?'
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        _ => b'?',
+    }
+}
+
+// A PDF literal string: (...) with \, ( and ) escaped
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        match win_ansi(c) {
+            b @ (b'\\' | b'(' | b')') => out.extend_from_slice(&[b'\\', b]),
+            b => out.push(b),
+        }
+    }
+    out.push(b')');
+    out
+}
+
+// =========================
+// Layout
+// =========================
+
+// Fixed object numbers. Catalog, page tree and info are written last, once the
+// page count is known; fonts come first; then a (page, content) pair per page.
+const CATALOG_ID: usize = 1;
+const PAGES_ID: usize = 2;
+const INFO_ID: usize = 3;
+const FIRST_FONT_ID: usize = 4;
+const FIRST_PAGE_ID: usize = FIRST_FONT_ID + Font::ALL.len();
+
+// Places lines top to bottom and flushes each page to the sink when it is full
+struct Layout<'a> {
+    out: &'a mut dyn Write,
+    written: usize,
+    // offsets[id - 1] = byte offset of object `id`, for the xref table
+    offsets: Vec<usize>,
+    page: Vec<u8>,
+    page_count: usize,
+    // Running lines, in the top and bottom margins of every page
+    header: Option<String>,
+    footer: Option<String>,
+    width: f32,
+    height: f32,
+    y: f32,
+}
+
+impl<'a> Layout<'a> {
+    fn new(out: &'a mut dyn Write, page_size: PageSize) -> io::Result<Self> {
+        let (width, height) = page_size.points();
+        let mut layout = Self {
+            out,
+            written: 0,
+            offsets: vec![0; FIRST_PAGE_ID - 1],
+            page: Vec::new(),
+            page_count: 0,
+            header: None,
+            footer: None,
+            width,
+            height,
+            y: height - MARGIN,
+        };
+        // Header with a binary comment so transfer tools treat the file as binary
+        layout.emit(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n")?;
+        for (i, font) in Font::ALL.iter().enumerate() {
+            let font = format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                font.base_font()
+            );
+            layout.object(FIRST_FONT_ID + i, font.as_bytes())?;
+        }
+        Ok(layout)
+    }
+
+    fn emit(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.out.write_all(bytes)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+
+    fn object(&mut self, id: usize, body: &[u8]) -> io::Result<()> {
+        if self.offsets.len() < id {
+            self.offsets.resize(id, 0);
+        }
+        self.offsets[id - 1] = self.written;
+        self.emit(format!("{} 0 obj\n", id).as_bytes())?;
+        self.emit(body)?;
+        self.emit(b"\nendobj\n")
+    }
+
+    // Write the current page (page object + content stream) and start an empty one
+    fn flush_page(&mut self) -> io::Result<()> {
+        let mut content = std::mem::take(&mut self.page);
+        self.page_count += 1;
+        let page_id = FIRST_PAGE_ID + 2 * (self.page_count - 1);
+
+        // Page number at the bottom, under the footer line if any, the header line at the top
+        let mut margins = vec![(format!("Page {}", self.page_count), MARGIN / 2.0)];
+        if let Some(footer) = &self.footer {
+            margins.push((footer.clone(), MARGIN / 2.0 + 10.0));
+        }
+        if let Some(header) = &self.header {
+            margins.push((header.clone(), self.height - MARGIN / 2.0));
+        }
+        for (text, y) in margins {
+            let x = (self.width - Font::Helvetica.text_width(&text, 8.0)) / 2.0;
+            content.extend_from_slice(format!("BT /F1 8 Tf {:.2} {:.2} Td ", x, y).as_bytes());
+            content.extend_from_slice(&pdf_string(&text));
+            content.extend_from_slice(b" Tj ET\n");
+        }
+
+        let fonts: Vec<String> = Font::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, font)| format!("/{} {} 0 R", font.resource(), FIRST_FONT_ID + i))
+            .collect();
+        let page = format!(
+            "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << {} >> >> /Contents {} 0 R >>",
+            PAGES_ID,
+            self.width,
+            self.height,
+            fonts.join(" "),
+            page_id + 1
+        );
+        self.object(page_id, page.as_bytes())?;
+
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend_from_slice(&content);
+        stream.extend_from_slice(b"\nendstream");
+        self.object(page_id + 1, &stream)?;
+
+        self.y = self.height - MARGIN;
+        Ok(())
+    }
+
+    fn skip(&mut self, points: f32) {
+        self.y -= points;
+    }
+
+    // Move down one line, breaking the page if the line doesn't fit
+    fn advance(&mut self, size: f32) -> io::Result<f32> {
+        let leading = size * 1.3;
+        if self.y - leading < MARGIN {
+            self.flush_page()?;
+        }
+        self.y -= leading;
+        Ok(self.y)
+    }
+
+    fn show(&mut self, font: Font, size: f32, x: f32, y: f32, text: &str) {
+        self.page.extend_from_slice(
+            format!("BT /{} {} Tf {:.2} {:.2} Td ", font.resource(), size, x, y).as_bytes(),
+        );
+        self.page.extend_from_slice(&pdf_string(text));
+        self.page.extend_from_slice(b" Tj ET\n");
+    }
+
+    fn line(&mut self, font: Font, size: f32, x: f32, text: &str) -> io::Result<()> {
+        let y = self.advance(size)?;
+        self.show(font, size, x, y, text);
+        Ok(())
+    }
+
+    fn paragraph(&mut self, font: Font, size: f32, x: f32, text: &str) -> io::Result<()> {
+        for line in wrap(text, font, size, self.width - MARGIN - x) {
+            self.line(font, size, x, &line)?;
+        }
+        Ok(())
+    }
+
+    fn bullet(&mut self, text: &str) -> io::Result<()> {
+        let indent = MARGIN + 14.0;
+        for (i, line) in wrap(
+            text,
+            Font::Helvetica,
+            BODY_SIZE,
+            self.width - MARGIN - indent,
+        )
+        .iter()
+        .enumerate()
+        {
+            let y = self.advance(BODY_SIZE)?;
+            if i == 0 {
+                self.show(Font::Helvetica, BODY_SIZE, MARGIN + 4.0, y, "•");
+            }
+            self.show(Font::Helvetica, BODY_SIZE, indent, y, line);
+        }
+        Ok(())
+    }
+
+    fn key_value(&mut self, key: &str, value: &str, value_offset: f32) -> io::Result<()> {
+        let x = MARGIN + value_offset;
+        for (i, line) in wrap(value, Font::Helvetica, BODY_SIZE, self.width - MARGIN - x)
+            .iter()
+            .enumerate()
+        {
+            let y = self.advance(BODY_SIZE)?;
+            if i == 0 {
+                self.show(Font::HelveticaBold, BODY_SIZE, MARGIN, y, key);
+            }
+            self.show(Font::Helvetica, BODY_SIZE, x, y, line);
+        }
+        Ok(())
+    }
+
+    // Flush the last page, then write the document structure and the xref table
+    fn finish(mut self, title: &str) -> io::Result<()> {
+        self.flush_page()?;
+
+        let kids: Vec<String> = (0..self.page_count)
+            .map(|i| format!("{} 0 R", FIRST_PAGE_ID + 2 * i))
+            .collect();
+        let pages = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            self.page_count
+        );
+        self.object(PAGES_ID, pages.as_bytes())?;
+
+        let mut info = b"<< /Title ".to_vec();
+        info.extend_from_slice(&pdf_string(title));
+        info.extend_from_slice(b" /Producer (ex_02_ocp) >>");
+        self.object(INFO_ID, &info)?;
+
+        let catalog = format!("<< /Type /Catalog /Pages {} 0 R >>", PAGES_ID);
+        self.object(CATALOG_ID, catalog.as_bytes())?;
+
+        let xref = self.written;
+        let size = self.offsets.len() + 1;
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", size);
+        for offset in &self.offsets {
+            table.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        table.push_str(&format!(
+            "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+            size, CATALOG_ID, INFO_ID, xref
+        ));
+        self.emit(table.as_bytes())?;
+        self.out.flush()
+    }
+}
+
+// Greedy word wrap using the font metrics
+// A single word wider than the line is kept whole rather than split
+fn wrap(text: &str, font: Font, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+        if font.text_width(&candidate, size) > max_width && !current.is_empty() {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        } else {
+            current = candidate;
+        }
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).position(|w| w == needle)
+    }
+
+    #[test]
+    fn xref_offsets_point_at_objects() {
+        let report = Report::new("Monthly Sales").section(Section::untitled().items(["A (1)"]));
+        let pdf = PdfFormatter::default().format(&report).unwrap();
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        let xref = find(&pdf, b"\nxref\n").unwrap() + 1;
+        let startxref = find(&pdf, b"startxref\n").unwrap() + b"startxref\n".len();
+        let declared: usize = String::from_utf8_lossy(&pdf[startxref..])
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(declared, xref);
+
+        let table = String::from_utf8_lossy(&pdf[xref..]).to_string();
+        let entries: Vec<&str> = table
+            .lines()
+            .skip(3)
+            .take_while(|l| l.ends_with(" n "))
+            .collect();
+        assert!(!entries.is_empty());
+        for (i, entry) in entries.iter().enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn long_reports_break_pages() {
+        let items: Vec<String> = (0..200).map(|i| format!("Product {}", i)).collect();
+        let report = Report::new("Inventory").section(Section::untitled().items(items));
+        let pdf =
+            String::from_utf8_lossy(&PdfFormatter::default().format(&report).unwrap()).to_string();
+
+        assert!(!pdf.contains("/Count 1 "));
+        assert!(pdf.contains("(Page 2)"));
+    }
+
+    #[test]
+    fn page_size_and_locale_from_options() {
+        let report = Report::new("T").section(
+            Section::untitled().key_values([("Total", crate::report::Value::Decimal(7749.2))]),
+        );
+        let mut options = crate::config::parse("[pdf]\npage_size = letter\ndecimal_locale = de")
+            .unwrap()
+            .remove(0);
+
+        let formatter = PdfFormatter::from_options(&mut options).unwrap();
+
+        let pdf = String::from_utf8_lossy(&formatter.format(&report).unwrap()).to_string();
+        assert!(pdf.contains("/MediaBox [0 0 612 792]"));
+        assert!(pdf.contains("(7.749,20)"));
+    }
+
+    #[test]
+    fn special_characters_are_escaped() {
+        assert_eq!(pdf_string("a(b)\\c"), b"(a\\(b\\)\\\\c)".to_vec());
+        assert_eq!(pdf_string("€ → ok"), b"(\x80 ? ok)".to_vec());
+    }
+}
@@ -0,0 +1,399 @@
+// src/formatters/svg.rs
+// SVG chart output
+// A picture of the numbers in the report: the first block holding numeric data becomes a bar
+// or line chart, with axes, labels and a legend, in a standalone SVG file.
+// - items like "Product A: 120 units" give one series ("units"), labelled "Product A"...
+// - a table gives one series per numeric column, labelled by its first non-numeric column
+// A report without numbers still gives a valid SVG, saying so.
+
+use std::io::{self, Write};
+
+use super::ReportFormatter;
+use super::markup::Escaped;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report, Table, Value, parse_item};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ChartKind {
+    #[default]
+    Bar,
+    Line,
+}
+
+#[derive(Default)]
+pub struct SvgChartFormatter {
+    kind: ChartKind,
+}
+
+impl SvgChartFormatter {
+    pub fn new(kind: ChartKind) -> Self {
+        Self { kind }
+    }
+}
+
+// chart = bar | line
+impl Configurable for SvgChartFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let kinds = [("bar", ChartKind::Bar), ("line", ChartKind::Line)];
+        Ok(Self::new(
+            options.take_choice("chart", &kinds)?.unwrap_or_default(),
+        ))
+    }
+}
+
+// Layout, in SVG user units (pixels)
+const WIDTH: f64 = 720.0;
+const HEIGHT: f64 = 420.0;
+const LEFT: f64 = 72.0;
+const RIGHT: f64 = 24.0;
+const TOP: f64 = 72.0;
+const BOTTOM: f64 = 72.0;
+const COLORS: [&str; 6] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948",
+];
+
+impl ReportFormatter for SvgChartFormatter {
+    fn name(&self) -> &str {
+        "svg"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "image/svg+xml"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"{t}\" \
+             font-family=\"system-ui, sans-serif\" font-size=\"12\">",
+            w = WIDTH,
+            h = HEIGHT,
+            t = Escaped(&report.title)
+        )?;
+        writeln!(out, "  <title>{}</title>", Escaped(&report.title))?;
+        writeln!(
+            out,
+            "  <rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\n  \
+             <text x=\"{}\" y=\"28\" text-anchor=\"middle\" font-size=\"18\" font-weight=\"bold\">{}</text>",
+            WIDTH / 2.0,
+            Escaped(&report.title)
+        )?;
+
+        match chart_data(report) {
+            Some(chart) => self.write_chart(&chart, out)?,
+            None => writeln!(
+                out,
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#666\">No numeric data</text>",
+                WIDTH / 2.0,
+                HEIGHT / 2.0
+            )?,
+        }
+        writeln!(out, "</svg>")
+    }
+}
+
+// Labels along the x axis, and one or more series of values (None: no value for that label)
+#[derive(Debug, PartialEq)]
+struct Chart {
+    labels: Vec<String>,
+    series: Vec<(String, Vec<Option<f64>>)>,
+}
+
+// The first block that holds numbers
+fn chart_data(report: &Report) -> Option<Chart> {
+    report
+        .sections
+        .iter()
+        .flat_map(|section| &section.blocks)
+        .find_map(|block| match block {
+            Block::Items(items) => items_chart(items),
+            Block::Table(table) => table_chart(table),
+            Block::KeyValues(_) => None,
+        })
+}
+
+fn items_chart(items: &[String]) -> Option<Chart> {
+    let parsed: Vec<_> = items.iter().filter_map(|i| parse_item(i)).collect();
+    let unit = parsed.first()?.2;
+    Some(Chart {
+        labels: parsed
+            .iter()
+            .map(|(label, _, _)| label.to_string())
+            .collect(),
+        series: vec![(
+            if unit.is_empty() { "value" } else { unit }.to_string(),
+            parsed.iter().map(|&(_, value, _)| Some(value)).collect(),
+        )],
+    })
+}
+
+fn table_chart(table: &Table) -> Option<Chart> {
+    let label_column = table.columns.iter().position(|c| !c.kind.is_numeric());
+    let series: Vec<(String, Vec<Option<f64>>)> = table
+        .columns
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.kind.is_numeric())
+        .map(|(i, column)| {
+            let values = table
+                .rows
+                .iter()
+                .map(|row| match row[i] {
+                    Value::Integer(n) => Some(n as f64),
+                    Value::Decimal(x) if x.is_finite() => Some(x),
+                    _ => None,
+                })
+                .collect();
+            (column.name.clone(), values)
+        })
+        .collect();
+    if series.is_empty() || table.rows.is_empty() {
+        return None;
+    }
+    let labels = table
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(r, row)| match label_column {
+            Some(c) => row[c].to_string(),
+            None => (r + 1).to_string(),
+        })
+        .collect();
+    Some(Chart { labels, series })
+}
+
+// Round tick values covering [min, max]: steps of 1, 2 or 5 times a power of ten
+fn ticks(min: f64, max: f64) -> (Vec<f64>, usize) {
+    let (min, max) = (min.min(0.0), max.max(0.0));
+    let range = if max > min { max - min } else { 1.0 };
+    let raw = range / 5.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&s| s >= raw)
+        .unwrap_or(10.0 * magnitude);
+    let decimals = if step < 1.0 {
+        (-step.log10().floor()) as usize
+    } else {
+        0
+    };
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    ((first..=last).map(|i| i as f64 * step).collect(), decimals)
+}
+
+impl SvgChartFormatter {
+    fn write_chart(&self, chart: &Chart, out: &mut dyn Write) -> io::Result<()> {
+        let values = chart.series.iter().flat_map(|(_, v)| v.iter().flatten());
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+        let (ticks, decimals) = ticks(min, max);
+        let (low, high) = (ticks[0], ticks[ticks.len() - 1]);
+        let plot_width = WIDTH - LEFT - RIGHT;
+        let plot_height = HEIGHT - TOP - BOTTOM;
+        let y = |v: f64| TOP + plot_height * (high - v) / (high - low);
+        let slot = plot_width / chart.labels.len() as f64;
+        let center = |i: usize| LEFT + slot * (i as f64 + 0.5);
+
+        // Legend, under the title
+        let mut x = LEFT;
+        for (s, (name, _)) in chart.series.iter().enumerate() {
+            writeln!(
+                out,
+                "  <rect x=\"{:.1}\" y=\"42\" width=\"12\" height=\"12\" fill=\"{}\"/>\
+                 <text x=\"{:.1}\" y=\"52\">{}</text>",
+                x,
+                COLORS[s % COLORS.len()],
+                x + 16.0,
+                Escaped(name)
+            )?;
+            x += 32.0 + 7.0 * name.chars().count() as f64;
+        }
+
+        // Grid and y axis
+        for &tick in &ticks {
+            writeln!(
+                out,
+                "  <line x1=\"{l:.1}\" y1=\"{y:.1}\" x2=\"{r:.1}\" y2=\"{y:.1}\" stroke=\"#e5e5e5\"/>\
+                 <text x=\"{tx:.1}\" y=\"{ty:.1}\" text-anchor=\"end\" fill=\"#444\">{v:.d$}</text>",
+                l = LEFT,
+                r = WIDTH - RIGHT,
+                y = y(tick),
+                tx = LEFT - 8.0,
+                ty = y(tick) + 4.0,
+                v = tick,
+                d = decimals
+            )?;
+        }
+        writeln!(
+            out,
+            "  <line x1=\"{l:.1}\" y1=\"{t:.1}\" x2=\"{l:.1}\" y2=\"{b:.1}\" stroke=\"#333\"/>\
+             <line x1=\"{l:.1}\" y1=\"{z:.1}\" x2=\"{r:.1}\" y2=\"{z:.1}\" stroke=\"#333\"/>",
+            l = LEFT,
+            r = WIDTH - RIGHT,
+            t = TOP,
+            b = HEIGHT - BOTTOM,
+            z = y(0.0)
+        )?;
+
+        // X labels, slanted when they would overlap
+        let slanted = chart
+            .labels
+            .iter()
+            .any(|l| 7.0 * l.chars().count() as f64 > slot);
+        for (i, label) in chart.labels.iter().enumerate() {
+            let (lx, ly) = (center(i), HEIGHT - BOTTOM + 18.0);
+            if slanted {
+                writeln!(
+                    out,
+                    "  <text x=\"{lx:.1}\" y=\"{ly:.1}\" text-anchor=\"end\" \
+                     transform=\"rotate(-30 {lx:.1} {ly:.1})\">{}</text>",
+                    Escaped(label)
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "  <text x=\"{lx:.1}\" y=\"{ly:.1}\" text-anchor=\"middle\">{}</text>",
+                    Escaped(label)
+                )?;
+            }
+        }
+
+        // Data
+        let zero = y(0.0);
+        for (s, (name, values)) in chart.series.iter().enumerate() {
+            let color = COLORS[s % COLORS.len()];
+            match self.kind {
+                ChartKind::Bar => {
+                    let bar = slot * 0.8 / chart.series.len() as f64;
+                    for (i, value) in values.iter().enumerate() {
+                        let Some(value) = *value else { continue };
+                        let bx = center(i) - slot * 0.4 + bar * s as f64;
+                        let (top, bottom) = (y(value).min(zero), y(value).max(zero));
+                        writeln!(
+                            out,
+                            "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\">\
+                             <title>{}: {}</title></rect>",
+                            bx,
+                            top,
+                            bar,
+                            bottom - top,
+                            color,
+                            Escaped(&chart.labels[i]),
+                            Escaped(format!("{} {}", value, name))
+                        )?;
+                    }
+                }
+                ChartKind::Line => {
+                    // A missing value breaks the line
+                    let points: Vec<Option<(f64, f64)>> = values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| v.map(|v| (center(i), y(v))))
+                        .collect();
+                    for run in points.split(Option::is_none).filter(|r| r.len() > 1) {
+                        let path: Vec<String> = run
+                            .iter()
+                            .flatten()
+                            .map(|(px, py)| format!("{:.1},{:.1}", px, py))
+                            .collect();
+                        writeln!(
+                            out,
+                            "  <polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+                            path.join(" "),
+                            color
+                        )?;
+                    }
+                    for (i, point) in points.iter().enumerate() {
+                        let Some((px, py)) = point else { continue };
+                        writeln!(
+                            out,
+                            "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3.5\" fill=\"{}\">\
+                             <title>{}: {}</title></circle>",
+                            px,
+                            py,
+                            color,
+                            Escaped(&chart.labels[i]),
+                            Escaped(format!("{} {}", values[i].unwrap_or_default(), name))
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Column, ColumnType, Section};
+
+    #[test]
+    fn series_come_from_items_or_tables() {
+        let items = Report::new("T").section(Section::untitled().items([
+            "Product A: 120 units",
+            "no number here",
+            "Product B: 1,098.5 units",
+        ]));
+        assert_eq!(
+            chart_data(&items),
+            Some(Chart {
+                labels: vec!["Product A".to_string(), "Product B".to_string()],
+                series: vec![("units".to_string(), vec![Some(120.0), Some(1098.5)])],
+            })
+        );
+
+        let table = Report::new("T").section(
+            Section::untitled().table(
+                Table::new(vec![
+                    Column::new("Month", ColumnType::Text),
+                    Column::new("Units", ColumnType::Integer),
+                ])
+                .row(vec![Value::text("Jan"), Value::Integer(3)])
+                .row(vec![Value::text("Feb"), Value::Empty]),
+            ),
+        );
+        assert_eq!(
+            chart_data(&table).unwrap().series,
+            vec![("Units".to_string(), vec![Some(3.0), None])]
+        );
+    }
+
+    #[test]
+    fn ticks_are_round_numbers() {
+        assert_eq!(ticks(98.0, 143.0), (vec![0.0, 50.0, 100.0, 150.0], 0));
+        assert_eq!(
+            ticks(-3.0, 7.0).0,
+            vec![-4.0, -2.0, 0.0, 2.0, 4.0, 6.0, 8.0]
+        );
+        assert_eq!(ticks(0.0, 0.5).1, 1);
+    }
+
+    #[test]
+    fn one_shape_per_value() {
+        let report = Report::new("A & B").section(Section::untitled().items(["x: 1", "y: 2"]));
+
+        let bars =
+            String::from_utf8(SvgChartFormatter::default().format(&report).unwrap()).unwrap();
+        let line = String::from_utf8(
+            SvgChartFormatter::new(ChartKind::Line)
+                .format(&report)
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert!(bars.contains("<title>A &amp; B</title>"));
+        assert_eq!(bars.matches("<title>x: 1 value</title></rect>").count(), 1);
+        assert_eq!(line.matches("<circle").count(), 2);
+        assert_eq!(line.matches("<polyline").count(), 1);
+    }
+}
@@ -0,0 +1,751 @@
+// src/formatters/template.rs
+// Template driven output
+// The layout comes from a text file written by anyone, no Rust and no recompilation needed.
+//
+// Syntax
+//   {{ title }}                       value of a variable
+//   {{ item | upper }}                filters: upper, lower, escape (HTML/XML entities), length
+//   {% for item in items %}...{% endfor %}
+//   {% if section.heading %}...{% else %}...{% endif %}    also: {% if not x %}
+//   {# a comment #}
+// Inside a loop, loop.index (1-based), loop.first and loop.last are available.
+// A line holding only a {% ... %} or {# ... #} tag disappears entirely from the output.
+//
+// Variables
+//   title, items (every item of the report), sections
+//   section.heading, section.items, section.tables, section.entries
+//   table.columns (name, type, numeric), table.rows (each row is a list of cells)
+//   entry.key, entry.value, entry.type
+// Empty strings and empty lists are false in conditions. Unknown variables, loops over what
+// isn't a list and values that can't be printed are errors, found when the template is parsed.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use super::ReportFormatter;
+use super::markup::Escaped;
+use crate::report::{Block, Report};
+
+#[derive(Debug)]
+pub struct TemplateError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "template line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+// Output types a template may declare through its file name ("invoice.html.tpl")
+const OUTPUT_TYPES: [(&str, &str); 7] = [
+    ("txt", "text/plain"),
+    ("html", "text/html"),
+    ("md", "text/markdown"),
+    ("xml", "application/xml"),
+    ("csv", "text/csv"),
+    ("json", "application/json"),
+    ("yaml", "application/yaml"),
+];
+
+pub struct TemplateFormatter {
+    name: String,
+    extensions: [&'static str; 1],
+    mime_type: &'static str,
+    nodes: Vec<Node>,
+}
+
+impl TemplateFormatter {
+    // "templates/monthly.md.tpl" gives a formatter named "monthly" producing .md (text/markdown)
+    // Without an inner extension ("monthly.tpl") the output is plain text
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TemplateError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| TemplateError {
+            line: 0,
+            message: format!("cannot read {}: {}", path.display(), e),
+        })?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut parts = file_name.split('.');
+        let name = parts.next().unwrap_or_default().to_string();
+        let output = parts.next().filter(|_| parts.next().is_some());
+        Self::parse(name, output.unwrap_or("txt"), &source)
+    }
+
+    pub fn parse(
+        name: impl Into<String>,
+        extension: &str,
+        source: &str,
+    ) -> Result<Self, TemplateError> {
+        let (extension, mime_type) = OUTPUT_TYPES
+            .iter()
+            .copied()
+            .find(|(ext, _)| ext.eq_ignore_ascii_case(extension))
+            .unwrap_or(OUTPUT_TYPES[0]);
+        let tokens = tokenize(source)?;
+        let mut tokens = tokens.into_iter();
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+        if let Some((tag, line)) = end {
+            return Err(TemplateError {
+                line,
+                message: format!("unexpected {{% {} %}}", tag),
+            });
+        }
+        check(&nodes, &mut Vec::new())?;
+        Ok(Self {
+            name: name.into(),
+            extensions: [extension],
+            mime_type,
+            nodes,
+        })
+    }
+}
+
+impl ReportFormatter for TemplateFormatter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+
+    fn mime_type(&self) -> &str {
+        self.mime_type
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let root = Data::from_report(report);
+        let mut scopes = Vec::new();
+        render(&self.nodes, &root, &mut scopes, out)
+    }
+}
+
+// =========================
+// Parsing
+// =========================
+
+enum Token {
+    Text(String),
+    Expr(String, usize),
+    Stmt(String, usize),
+}
+
+// Split the source into text and tags, dropping comments and standalone tag lines
+fn tokenize(source: &str) -> Result<Vec<Token>, TemplateError> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = source;
+    let line_of = |rest: &str| source[..source.len() - rest.len()].matches('\n').count() + 1;
+
+    while let Some(start) = rest.find('{') {
+        let (open, close) = match rest[start..].get(..2) {
+            Some("{{") => ("{{", "}}"),
+            Some("{%") => ("{%", "%}"),
+            Some("{#") => ("{#", "#}"),
+            _ => {
+                text.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+                continue;
+            }
+        };
+        let line = line_of(&rest[start..]);
+        text.push_str(&rest[..start]);
+        let body_start = start + open.len();
+        let Some(len) = rest[body_start..].find(close) else {
+            return Err(TemplateError {
+                line,
+                message: format!("{} is never closed by {}", open, close),
+            });
+        };
+        let body = rest[body_start..body_start + len].trim().to_string();
+        rest = &rest[body_start + len + close.len()..];
+
+        if open != "{{" {
+            // Standalone tag: only whitespace around it on its line, remove the whole line
+            let line_start = text.rfind('\n').map_or(0, |i| i + 1);
+            let before_is_blank = text[line_start..].trim().is_empty()
+                && (line_start > 0 || tokens.is_empty() || ends_line(&tokens));
+            let line_end = rest.find('\n');
+            let after = &rest[..line_end.unwrap_or(rest.len())];
+            if before_is_blank && after.trim().is_empty() {
+                text.truncate(line_start);
+                rest = &rest[line_end.map_or(rest.len(), |i| i + 1)..];
+            }
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+        match open {
+            "{{" => tokens.push(Token::Expr(body, line)),
+            "{%" => tokens.push(Token::Stmt(body, line)),
+            _ => {}
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+// True when the output so far ends at the start of a line (so a tag there can be standalone)
+fn ends_line(tokens: &[Token]) -> bool {
+    matches!(tokens.last(), Some(Token::Stmt(..)))
+        || matches!(tokens.last(), Some(Token::Text(t)) if t.ends_with('\n'))
+}
+
+enum Node {
+    Text(String),
+    Value {
+        path: Vec<String>,
+        filters: Vec<Filter>,
+        line: usize,
+    },
+    For {
+        var: String,
+        path: Vec<String>,
+        body: Vec<Node>,
+        line: usize,
+    },
+    If {
+        path: Vec<String>,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+        line: usize,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Filter {
+    Upper,
+    Lower,
+    Escape,
+    Length,
+}
+
+type Tokens = std::vec::IntoIter<Token>;
+// Closing tag (endfor, else, endif) that ended a block, with its line
+type Closing = Option<(String, usize)>;
+
+// Parse until the end of input or a closing tag, which is returned
+fn parse_nodes(tokens: &mut Tokens) -> Result<(Vec<Node>, Closing), TemplateError> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Expr(expr, line) => {
+                let mut parts = expr.split('|').map(str::trim);
+                let path = parse_path(parts.next().unwrap_or_default(), line)?;
+                let filters = parts
+                    .map(|name| match name {
+                        "upper" => Ok(Filter::Upper),
+                        "lower" => Ok(Filter::Lower),
+                        "escape" => Ok(Filter::Escape),
+                        "length" => Ok(Filter::Length),
+                        other => Err(TemplateError {
+                            line,
+                            message: format!("unknown filter \"{}\"", other),
+                        }),
+                    })
+                    .collect::<Result<_, _>>()?;
+                nodes.push(Node::Value {
+                    path,
+                    filters,
+                    line,
+                });
+            }
+            Token::Stmt(stmt, line) => {
+                let words: Vec<&str> = stmt.split_whitespace().collect();
+                match words.as_slice() {
+                    ["for", var, "in", path] => {
+                        let (body, end) = parse_nodes(tokens)?;
+                        expect_end(end, "endfor", line)?;
+                        nodes.push(Node::For {
+                            var: var.to_string(),
+                            path: parse_path(path, line)?,
+                            body,
+                            line,
+                        });
+                    }
+                    ["if", path] | ["if", "not", path] => {
+                        let negate = words.len() == 3;
+                        let (then, end) = parse_nodes(tokens)?;
+                        let otherwise = match end {
+                            Some((tag, _)) if tag == "else" => {
+                                let (otherwise, end) = parse_nodes(tokens)?;
+                                expect_end(end, "endif", line)?;
+                                otherwise
+                            }
+                            end => {
+                                expect_end(end, "endif", line)?;
+                                Vec::new()
+                            }
+                        };
+                        nodes.push(Node::If {
+                            path: parse_path(path, line)?,
+                            negate,
+                            then,
+                            otherwise,
+                            line,
+                        });
+                    }
+                    ["endfor"] | ["else"] | ["endif"] => {
+                        return Ok((nodes, Some((words[0].to_string(), line))));
+                    }
+                    _ => {
+                        return Err(TemplateError {
+                            line,
+                            message: format!("unknown statement {{% {} %}}", stmt),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Ok((nodes, None))
+}
+
+fn expect_end(end: Closing, expected: &str, line: usize) -> Result<(), TemplateError> {
+    match end {
+        Some((tag, _)) if tag == expected => Ok(()),
+        Some((tag, tag_line)) => Err(TemplateError {
+            line: tag_line,
+            message: format!("expected {{% {} %}}, found {{% {} %}}", expected, tag),
+        }),
+        None => Err(TemplateError {
+            line,
+            message: format!("missing {{% {} %}}", expected),
+        }),
+    }
+}
+
+fn parse_path(path: &str, line: usize) -> Result<Vec<String>, TemplateError> {
+    let parts: Vec<String> = path.split('.').map(str::to_string).collect();
+    let valid =
+        |p: &String| !p.is_empty() && p.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if parts.iter().all(valid) {
+        Ok(parts)
+    } else {
+        Err(TemplateError {
+            line,
+            message: format!("invalid variable name \"{}\"", path),
+        })
+    }
+}
+
+// =========================
+// Checking
+// =========================
+
+// The shape of what the template sees (see Data::from_report), to check paths before rendering
+enum Shape {
+    Text,
+    Bool,
+    List(&'static Shape),
+    Object(&'static [(&'static str, Shape)]),
+}
+
+const COLUMN: Shape = Shape::Object(&[
+    ("name", Shape::Text),
+    ("type", Shape::Text),
+    ("numeric", Shape::Bool),
+]);
+const TABLE: Shape = Shape::Object(&[
+    ("columns", Shape::List(&COLUMN)),
+    ("rows", Shape::List(&Shape::List(&Shape::Text))),
+]);
+const ENTRY: Shape = Shape::Object(&[
+    ("key", Shape::Text),
+    ("value", Shape::Text),
+    ("type", Shape::Text),
+]);
+const SECTION: Shape = Shape::Object(&[
+    ("heading", Shape::Text),
+    ("items", Shape::List(&Shape::Text)),
+    ("tables", Shape::List(&TABLE)),
+    ("entries", Shape::List(&ENTRY)),
+]);
+const REPORT: Shape = Shape::Object(&[
+    ("title", Shape::Text),
+    ("items", Shape::List(&Shape::Text)),
+    ("sections", Shape::List(&SECTION)),
+]);
+const LOOP: Shape = Shape::Object(&[
+    ("index", Shape::Text),
+    ("first", Shape::Bool),
+    ("last", Shape::Bool),
+]);
+
+// Loop variables and their shape, innermost last
+type ShapeScopes<'a> = Vec<(&'a str, &'static Shape)>;
+
+fn check<'a>(nodes: &'a [Node], scopes: &mut ShapeScopes<'a>) -> Result<(), TemplateError> {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Value {
+                path,
+                filters,
+                line,
+            } => match shape_of(path, scopes, *line)? {
+                Shape::Text | Shape::Bool => {}
+                Shape::List(_) if matches!(filters.first(), Some(Filter::Length)) => {}
+                _ => {
+                    return Err(TemplateError {
+                        line: *line,
+                        message: format!("\"{}\" is not a printable value", path.join(".")),
+                    });
+                }
+            },
+            Node::For {
+                var,
+                path,
+                body,
+                line,
+            } => {
+                let Shape::List(element) = shape_of(path, scopes, *line)? else {
+                    return Err(TemplateError {
+                        line: *line,
+                        message: format!("\"{}\" is not a list", path.join(".")),
+                    });
+                };
+                scopes.push(("loop", &LOOP));
+                scopes.push((var, element));
+                let result = check(body, scopes);
+                scopes.truncate(scopes.len() - 2);
+                result?;
+            }
+            Node::If {
+                path,
+                then,
+                otherwise,
+                line,
+                ..
+            } => {
+                shape_of(path, scopes, *line)?;
+                check(then, scopes)?;
+                check(otherwise, scopes)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Same resolution as lookup(), on shapes
+fn shape_of(
+    path: &[String],
+    scopes: &ShapeScopes<'_>,
+    line: usize,
+) -> Result<&'static Shape, TemplateError> {
+    let field = |shape: &'static Shape, name: &str| match shape {
+        Shape::Object(fields) => fields.iter().find(|(k, _)| *k == name).map(|(_, v)| v),
+        _ => None,
+    };
+    let first = path[0].as_str();
+    let mut current = scopes
+        .iter()
+        .rev()
+        .find(|(name, _)| *name == first)
+        .map(|&(_, shape)| shape)
+        .or_else(|| field(&REPORT, first));
+    for part in &path[1..] {
+        current = current.and_then(|shape| field(shape, part));
+    }
+    current.ok_or_else(|| TemplateError {
+        line,
+        message: format!("unknown variable \"{}\"", path.join(".")),
+    })
+}
+
+// =========================
+// Rendering
+// =========================
+
+// What the template sees of the report
+#[derive(Clone)]
+enum Data {
+    Text(String),
+    Bool(bool),
+    List(Vec<Data>),
+    Object(Vec<(&'static str, Data)>),
+}
+
+impl Data {
+    fn text(s: impl Into<String>) -> Self {
+        Data::Text(s.into())
+    }
+
+    fn from_report(report: &Report) -> Self {
+        let mut all_items = Vec::new();
+        let mut sections = Vec::new();
+        for section in &report.sections {
+            let mut items = Vec::new();
+            let mut tables = Vec::new();
+            let mut entries = Vec::new();
+            for block in &section.blocks {
+                match block {
+                    Block::Items(list) => {
+                        items.extend(list.iter().map(Data::text));
+                        all_items.extend(list.iter().map(Data::text));
+                    }
+                    Block::Table(table) => {
+                        let columns = table
+                            .columns
+                            .iter()
+                            .map(|c| {
+                                Data::Object(vec![
+                                    ("name", Data::text(&c.name)),
+                                    ("type", Data::text(c.kind.name())),
+                                    ("numeric", Data::Bool(c.kind.is_numeric())),
+                                ])
+                            })
+                            .collect();
+                        let rows = table
+                            .rows
+                            .iter()
+                            .map(|row| {
+                                Data::List(row.iter().map(|v| Data::text(v.to_string())).collect())
+                            })
+                            .collect();
+                        tables.push(Data::Object(vec![
+                            ("columns", Data::List(columns)),
+                            ("rows", Data::List(rows)),
+                        ]));
+                    }
+                    Block::KeyValues(pairs) => {
+                        entries.extend(pairs.iter().map(|(key, value)| {
+                            Data::Object(vec![
+                                ("key", Data::text(key)),
+                                ("value", Data::text(value.to_string())),
+                                (
+                                    "type",
+                                    Data::text(value.kind().map(|k| k.name()).unwrap_or("")),
+                                ),
+                            ])
+                        }));
+                    }
+                }
+            }
+            sections.push(Data::Object(vec![
+                (
+                    "heading",
+                    Data::text(section.heading.clone().unwrap_or_default()),
+                ),
+                ("items", Data::List(items)),
+                ("tables", Data::List(tables)),
+                ("entries", Data::List(entries)),
+            ]));
+        }
+        Data::Object(vec![
+            ("title", Data::text(&report.title)),
+            ("items", Data::List(all_items)),
+            ("sections", Data::List(sections)),
+        ])
+    }
+
+    fn is_truthy(&self) -> bool {
+        match self {
+            Data::Text(s) => !s.is_empty(),
+            Data::Bool(b) => *b,
+            Data::List(l) => !l.is_empty(),
+            Data::Object(_) => true,
+        }
+    }
+
+    fn field(&self, name: &str) -> Option<&Data> {
+        match self {
+            Data::Object(fields) => fields.iter().find(|(k, _)| *k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+// Loop variables, innermost last
+type Scopes<'a> = Vec<Binding<'a>>;
+
+#[derive(Clone, Copy)]
+enum Binding<'a> {
+    Var(&'a str, &'a Data),
+    Loop { index: usize, len: usize },
+}
+
+// Borrowed from the report data, except loop.* which is computed on the fly
+fn lookup<'a>(
+    path: &[String],
+    root: &'a Data,
+    scopes: &Scopes<'a>,
+    line: usize,
+) -> io::Result<Cow<'a, Data>> {
+    let unknown = || render_error(line, format!("unknown variable \"{}\"", path.join(".")));
+    let first = path[0].as_str();
+    let binding = scopes.iter().rev().find(|b| match b {
+        Binding::Var(name, _) => *name == first,
+        Binding::Loop { .. } => first == "loop",
+    });
+    let mut current = match binding {
+        Some(Binding::Var(_, data)) => Cow::Borrowed(*data),
+        Some(Binding::Loop { index, len }) => Cow::Owned(Data::Object(vec![
+            ("index", Data::text((index + 1).to_string())),
+            ("first", Data::Bool(*index == 0)),
+            ("last", Data::Bool(index + 1 == *len)),
+        ])),
+        None => Cow::Borrowed(root.field(first).ok_or_else(unknown)?),
+    };
+    for part in &path[1..] {
+        current = match current {
+            Cow::Borrowed(data) => Cow::Borrowed(data.field(part).ok_or_else(unknown)?),
+            Cow::Owned(data) => Cow::Owned(data.field(part).ok_or_else(unknown)?.clone()),
+        };
+    }
+    Ok(current)
+}
+
+fn render_error(line: usize, message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, TemplateError { line, message })
+}
+
+fn render<'a>(
+    nodes: &'a [Node],
+    root: &'a Data,
+    scopes: &mut Scopes<'a>,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.write_all(text.as_bytes())?,
+            Node::Value {
+                path,
+                filters,
+                line,
+            } => {
+                let value = lookup(path, root, scopes, *line)?;
+                let mut text = match value.as_ref() {
+                    Data::Text(s) => s.clone(),
+                    Data::Bool(b) => b.to_string(),
+                    Data::List(l) if matches!(filters.first(), Some(Filter::Length)) => {
+                        l.len().to_string()
+                    }
+                    _ => {
+                        return Err(render_error(
+                            *line,
+                            format!("\"{}\" is not a printable value", path.join(".")),
+                        ));
+                    }
+                };
+                for filter in filters {
+                    text = match filter {
+                        Filter::Upper => text.to_uppercase(),
+                        Filter::Lower => text.to_lowercase(),
+                        Filter::Escape => Escaped(&text).to_string(),
+                        Filter::Length if matches!(value.as_ref(), Data::List(_)) => text,
+                        Filter::Length => text.chars().count().to_string(),
+                    };
+                }
+                out.write_all(text.as_bytes())?;
+            }
+            Node::For {
+                var,
+                path,
+                body,
+                line,
+            } => {
+                let Cow::Borrowed(Data::List(list)) = lookup(path, root, scopes, *line)? else {
+                    return Err(render_error(
+                        *line,
+                        format!("\"{}\" is not a list", path.join(".")),
+                    ));
+                };
+                for (i, element) in list.iter().enumerate() {
+                    scopes.push(Binding::Loop {
+                        index: i,
+                        len: list.len(),
+                    });
+                    scopes.push(Binding::Var(var, element));
+                    let result = render(body, root, scopes, out);
+                    scopes.truncate(scopes.len() - 2);
+                    result?;
+                }
+            }
+            Node::If {
+                path,
+                negate,
+                then,
+                otherwise,
+                line,
+            } => {
+                let condition = lookup(path, root, scopes, *line)?.is_truthy() != *negate;
+                render(if condition { then } else { otherwise }, root, scopes, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    fn render_str(template: &str, report: &Report) -> String {
+        let formatter = TemplateFormatter::parse("test", "txt", template).unwrap();
+        String::from_utf8(formatter.format(report).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn loops_conditionals_and_filters() {
+        let report = Report::new("R&D")
+            .section(Section::untitled().items(["a", "b"]))
+            .section(Section::new("Extra").items(["c"]));
+        let template = "\
+{{ title | escape }} ({{ items | length }})
+{% for section in sections %}
+{% if section.heading %}
+## {{ section.heading | upper }}
+{% endif %}
+{% for item in section.items %}
+{{ loop.index }}. {{ item }}{% if not loop.last %},{% endif %}
+{% endfor %}
+{% endfor %}
+";
+
+        assert_eq!(
+            render_str(template, &report),
+            "R&amp;D (3)\n1. a,\n2. b\n## EXTRA\n1. c\n"
+        );
+    }
+
+    #[test]
+    fn errors_carry_the_line_number() {
+        let unclosed = TemplateFormatter::parse("t", "txt", "x\n{% for i in items %}\n{{ i }}");
+        assert_eq!(unclosed.err().unwrap().line, 2);
+
+        let errors = [
+            ("ok\n{{ nope }}", 2),
+            (
+                "{% for s in sections %}\n{{ s.heading }}\n{{ s.titel }}\n{% endfor %}",
+                3,
+            ),
+            ("{% for s in sections %}{% endfor %}\n{{ s.heading }}", 2),
+            ("{% if loop.first %}{% endif %}", 1),
+            ("\n{{ sections }}", 2),
+            ("\n\n{% for c in title %}{% endfor %}", 3),
+        ];
+        for (template, line) in errors {
+            let err = TemplateFormatter::parse("t", "txt", template)
+                .err()
+                .unwrap();
+            assert_eq!(err.line, line, "{}", template);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+// src/formatters/terminal.rs
+// Rich terminal output: a highlighted title, colored headings, box-drawn tables sized to the
+// terminal and items wrapped to its width
+// detect() looks at stdout: when it isn't a terminal (a pipe, a file) the output is the same
+// as TextFormatter's, and NO_COLOR (https://no-color.org) or TERM=dumb turn the colors off.
+
+use std::io::{self, IsTerminal, Write};
+
+use super::ReportFormatter;
+use super::text::TextFormatter;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report, Table, Value};
+
+// When the terminal doesn't tell its size
+const DEFAULT_WIDTH: usize = 80;
+// Narrowest a column gets when a table has to shrink, "…" included
+const MIN_COLUMN_WIDTH: usize = 3;
+
+pub struct TerminalFormatter {
+    width: usize,
+    colors: bool,
+    // Not a terminal: plain text, like TextFormatter
+    plain: bool,
+}
+
+impl TerminalFormatter {
+    // Boxes and wrapping at this width, with colors
+    pub fn new(width: usize) -> Self {
+        Self {
+            width: width.max(20),
+            colors: true,
+            plain: false,
+        }
+    }
+
+    pub fn colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    // Settings for whatever stdout is connected to
+    pub fn detect() -> Self {
+        if !io::stdout().is_terminal() {
+            return Self {
+                width: DEFAULT_WIDTH,
+                colors: false,
+                plain: true,
+            };
+        }
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let dumb = std::env::var("TERM").is_ok_and(|term| term == "dumb");
+        Self::new(terminal_width().unwrap_or(DEFAULT_WIDTH)).colors(!no_color && !dumb)
+    }
+
+    // SGR escape sequence around the text, or the bare text without colors
+    fn paint(&self, codes: &str, text: &str) -> String {
+        if self.colors {
+            format!("\x1b[{}m{}\x1b[0m", codes, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+// width = columns, colors = true | false, each overriding what detect() finds
+// Redirected output stays plain text whatever the options say
+impl Configurable for TerminalFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let mut formatter = Self::detect();
+        if let Some(width) = options.take::<usize>("width")? {
+            formatter.width = width.max(20);
+        }
+        if let Some(colors) = options.take("colors")? {
+            formatter.colors = colors && !formatter.plain;
+        }
+        Ok(formatter)
+    }
+}
+
+impl ReportFormatter for TerminalFormatter {
+    fn name(&self) -> &str {
+        "terminal"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ans"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "text/plain"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        if self.plain {
+            return TextFormatter::default().write(report, out);
+        }
+
+        // Title: bold white on blue across the whole width
+        let title = format!(" {}", truncate(&report.title, self.width - 1));
+        let title = format!("{:<width$}", title, width = self.width);
+        writeln!(out, "{}", self.paint("1;97;44", &title))?;
+
+        for section in &report.sections {
+            writeln!(out)?;
+            if let Some(heading) = &section.heading {
+                writeln!(
+                    out,
+                    "{}",
+                    self.paint("1;36", &truncate(heading, self.width))
+                )?;
+                writeln!(out, "{}", self.paint("2", &"─".repeat(self.width)))?;
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        for item in items {
+                            let lines = wrap(item, self.width - 2);
+                            for (i, line) in lines.iter().enumerate() {
+                                let bullet = if i == 0 {
+                                    self.paint("36", "•")
+                                } else {
+                                    " ".into()
+                                };
+                                writeln!(out, "{} {}", bullet, line)?;
+                            }
+                        }
+                    }
+                    Block::Table(table) => self.write_table(table, out)?,
+                    Block::KeyValues(pairs) => self.write_key_values(pairs, out)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Dimmed lines above the title and below the last section
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        if self.plain {
+            return TextFormatter::default().write_framed(report, header, footer, out);
+        }
+        if let Some(header) = header {
+            writeln!(out, "{}", self.paint("2", &truncate(header, self.width)))?;
+        }
+        self.write(report, out)?;
+        if let Some(footer) = footer {
+            writeln!(out, "\n{}", self.paint("2", &truncate(footer, self.width)))?;
+        }
+        Ok(())
+    }
+}
+
+impl TerminalFormatter {
+    fn write_table(&self, table: &Table, out: &mut dyn Write) -> io::Result<()> {
+        let cells: Vec<Vec<String>> = table
+            .rows
+            .iter()
+            .map(|row| row.iter().map(ToString::to_string).collect())
+            .collect();
+        let mut widths: Vec<usize> = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                cells
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([column.name.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        if widths.is_empty() {
+            return Ok(());
+        }
+
+        // Borders take 3 characters per column, plus one. Shrink the widest column until it fits.
+        let available = self.width.saturating_sub(3 * widths.len() + 1);
+        while widths.iter().sum::<usize>() > available {
+            let (widest, &width) = widths
+                .iter()
+                .enumerate()
+                .max_by_key(|&(i, w)| (w, std::cmp::Reverse(i)))
+                .expect("at least one column");
+            if width <= MIN_COLUMN_WIDTH {
+                break;
+            }
+            widths[widest] -= 1;
+        }
+
+        let rule = |left: &str, middle: &str, right: &str| -> String {
+            let segments: Vec<String> = widths.iter().map(|&w| "─".repeat(w + 2)).collect();
+            self.paint("2", &format!("{}{}{}", left, segments.join(middle), right))
+        };
+        let bar = self.paint("2", "│");
+        let line = |values: &[&str], header: bool| -> String {
+            let padded: Vec<String> = values
+                .iter()
+                .zip(&table.columns)
+                .zip(&widths)
+                .map(|((value, column), &width)| {
+                    let value = truncate(value, width);
+                    let padded = if column.kind.is_numeric() {
+                        format!("{:>width$}", value)
+                    } else {
+                        format!("{:<width$}", value)
+                    };
+                    if header {
+                        self.paint("1", &padded)
+                    } else {
+                        padded
+                    }
+                })
+                .collect();
+            format!("{} {} {}", bar, padded.join(&format!(" {} ", bar)), bar)
+        };
+
+        writeln!(out, "{}", rule("┌", "┬", "┐"))?;
+        let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        writeln!(out, "{}", line(&names, true))?;
+        writeln!(out, "{}", rule("├", "┼", "┤"))?;
+        for row in &cells {
+            let values: Vec<&str> = row.iter().map(String::as_str).collect();
+            writeln!(out, "{}", line(&values, false))?;
+        }
+        writeln!(out, "{}", rule("└", "┴", "┘"))
+    }
+
+    // Keys bold and aligned, long values wrapped under themselves
+    fn write_key_values(&self, pairs: &[(String, Value)], out: &mut dyn Write) -> io::Result<()> {
+        let key_width = pairs
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(self.width / 2);
+        let value_width = self.width.saturating_sub(key_width + 3).max(1);
+        for (key, value) in pairs {
+            let key = format!("{:<key_width$}", truncate(key, key_width));
+            for (i, line) in wrap(&value.to_string(), value_width).iter().enumerate() {
+                if i == 0 {
+                    writeln!(out, "{} : {}", self.paint("1", &key), line)?;
+                } else {
+                    writeln!(out, "{:key_width$}   {}", "", line)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Cut to `width` characters, the last one becoming "…"
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+// Lines of at most `width` characters, broken between words when possible
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        let used = line.chars().count();
+        if used > 0 && used + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        // A word longer than a line is split
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.extend(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// Columns of the terminal on stdout, $COLUMNS first so users can override it
+fn terminal_width() -> Option<usize> {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .or_else(sys::width)
+        .filter(|&width| width > 0)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod sys {
+    use std::ffi::{c_int, c_ulong};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct WinSize {
+        rows: u16,
+        columns: u16,
+        x_pixels: u16,
+        y_pixels: u16,
+    }
+
+    #[cfg(target_os = "linux")]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(target_os = "macos")]
+    const TIOCGWINSZ: c_ulong = 0x4008_7468;
+    const STDOUT: c_int = 1;
+
+    unsafe extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    pub fn width() -> Option<usize> {
+        let mut size = WinSize::default();
+        // Safety: TIOCGWINSZ fills a winsize struct, which WinSize mirrors
+        let result = unsafe { ioctl(STDOUT, TIOCGWINSZ, &mut size as *mut WinSize) };
+        (result == 0).then_some(usize::from(size.columns))
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Coord {
+        x: i16,
+        y: i16,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct SmallRect {
+        left: i16,
+        top: i16,
+        right: i16,
+        bottom: i16,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct ScreenBufferInfo {
+        size: Coord,
+        cursor_position: Coord,
+        attributes: u16,
+        window: SmallRect,
+        maximum_window_size: Coord,
+    }
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetStdHandle(std_handle: u32) -> *mut c_void;
+        fn GetConsoleScreenBufferInfo(console: *mut c_void, info: *mut ScreenBufferInfo) -> i32;
+    }
+
+    pub fn width() -> Option<usize> {
+        let mut info = ScreenBufferInfo::default();
+        // Safety: the handle is only passed back to the console API, info is a valid out pointer
+        let ok = unsafe { GetConsoleScreenBufferInfo(GetStdHandle(STD_OUTPUT_HANDLE), &mut info) };
+        if ok == 0 {
+            return None;
+        }
+        usize::try_from(info.window.right - info.window.left + 1).ok()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod sys {
+    pub fn width() -> Option<usize> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Column, ColumnType, Section};
+
+    fn report() -> Report {
+        Report::new("Sales").section(
+            Section::new("By product")
+                .items(["A fairly long item that needs wrapping"])
+                .table(
+                    Table::new(vec![
+                        Column::new("Product", ColumnType::Text),
+                        Column::new("Units", ColumnType::Integer),
+                    ])
+                    .row(vec![
+                        Value::text("Product with a long name"),
+                        Value::Integer(120),
+                    ]),
+                ),
+        )
+    }
+
+    #[test]
+    fn boxes_fit_the_width() {
+        let text = String::from_utf8(
+            TerminalFormatter::new(28)
+                .colors(false)
+                .format(&report())
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            text,
+            concat!(
+                " Sales                      \n",
+                "\n",
+                "By product\n",
+                "────────────────────────────\n",
+                "• A fairly long item that\n",
+                "  needs wrapping\n",
+                "┌──────────────────┬───────┐\n",
+                "│ Product          │ Units │\n",
+                "├──────────────────┼───────┤\n",
+                "│ Product with a … │   120 │\n",
+                "└──────────────────┴───────┘\n",
+            )
+        );
+    }
+
+    #[test]
+    fn colors_and_plain_fallback() {
+        let colored =
+            String::from_utf8(TerminalFormatter::new(40).format(&report()).unwrap()).unwrap();
+        assert!(colored.starts_with("\x1b[1;97;44m Sales"));
+        assert!(colored.contains("\x1b[1;36mBy product\x1b[0m"));
+
+        let plain = TerminalFormatter {
+            width: 40,
+            colors: false,
+            plain: true,
+        };
+        assert_eq!(
+            plain.format(&report()).unwrap(),
+            TextFormatter::default().format(&report()).unwrap()
+        );
+        assert_eq!(wrap("abcdefgh ij", 3), ["abc", "def", "gh", "ij"]);
+        assert_eq!(wrap("", 10), [""]);
+    }
+}
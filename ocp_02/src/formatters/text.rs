@@ -0,0 +1,350 @@
+// src/formatters/text.rs
+// Plain text output
+// TextParser reads it back (see parsers/text.rs), so nothing about the report is left out:
+// - the dashed rule under a table header ends each column with its type:
+//   nothing for text, '#' for integers, '.' for decimals, '/' for dates
+//       Product    Units   Revenue  Last order
+//       ---------  ----#  -------.  ---------/
+// - a cell or key/value reads as the number or date it looks like; a text that would read as
+//   something else ("120", "", " padded", two lines...) is quoted, with \" \\ \n \r escapes,
+//   and so is any title, heading, item or key that wouldn't otherwise read back the same
+// - a blank line separates two blocks that would otherwise read as one
+// - "(no items)" and "(no entries)" stand for empty blocks, "---" for a section without
+//   heading that isn't the first one, or that is empty
+// What text can't show at all is an error: a table without columns, a row without any value,
+// a value of another type than its column when it would read back as the column's type.
+// With another date style or decimal locale (see locale.rs) the output is for people only.
+
+use std::io::{self, Write};
+
+use super::ReportFormatter;
+use super::locale::Locale;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::parsers::text::{cell_value, read_value};
+use crate::report::{Block, ColumnType, Report, Table, Value};
+
+pub(crate) const NO_ITEMS: &str = "(no items)";
+pub(crate) const NO_ENTRIES: &str = "(no entries)";
+
+#[derive(Default)]
+pub struct TextFormatter {
+    locale: Locale,
+}
+
+impl TextFormatter {
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+// date_style = long, decimal_locale = en
+impl Configurable for TextFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        Ok(Self::default().locale(Locale::from_options(options)?))
+    }
+}
+
+impl ReportFormatter for TextFormatter {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt", "text"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "text/plain"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        self.check(report)?;
+        write!(out, "=== {} ===", line_text(&report.title))?;
+        for (s, section) in report.sections.iter().enumerate() {
+            match &section.heading {
+                Some(heading) => write!(out, "\n\n--- {} ---", line_text(heading))?,
+                // Else it would read as part of the section before, or not at all
+                None if s > 0 || section.blocks.is_empty() => write!(out, "\n\n---")?,
+                None => {}
+            }
+            for (i, block) in section.blocks.iter().enumerate() {
+                if i > 0 && needs_separator(&section.blocks[i - 1], block) {
+                    writeln!(out)?;
+                }
+                match block {
+                    Block::Items(items) if items.is_empty() => write!(out, "\n{}", NO_ITEMS)?,
+                    Block::Items(items) => {
+                        for item in items {
+                            write!(out, "\n- {}", item_text(item))?;
+                        }
+                    }
+                    Block::Table(table) => {
+                        let names = table.columns.iter().map(|c| name_text(&c.name)).collect();
+                        let cells = table
+                            .rows
+                            .iter()
+                            .map(|row| {
+                                row.iter()
+                                    .zip(&table.columns)
+                                    .map(|(value, column)| {
+                                        cell_text(column.kind, value, &self.locale)
+                                    })
+                                    .collect()
+                            })
+                            .collect();
+                        let mut lines = lay_out(table, names, cells);
+                        lines[1] = typed_rule(&lines[1], table);
+                        for line in lines {
+                            write!(out, "\n{}", line)?;
+                        }
+                    }
+                    Block::KeyValues(pairs) if pairs.is_empty() => write!(out, "\n{}", NO_ENTRIES)?,
+                    Block::KeyValues(pairs) => write_key_values(pairs, &self.locale, out)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Plain lines above the title and below the last section
+    fn write_framed(
+        &self,
+        report: &Report,
+        header: Option<&str>,
+        footer: Option<&str>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        self.check(report)?;
+        if let Some(header) = header {
+            write!(out, "{}\n\n", header)?;
+        }
+        self.write(report, out)?;
+        if let Some(footer) = footer {
+            write!(out, "\n\n{}", footer)?;
+        }
+        Ok(())
+    }
+}
+
+impl TextFormatter {
+    // Refuses what can't be written so that it reads back the same
+    // Values are only checked in the default locale: the others don't read back anyway
+    fn check(&self, report: &Report) -> io::Result<()> {
+        let typed = self.locale == Locale::default();
+        let fail = |message: String| Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+        for block in report.sections.iter().flat_map(|s| &s.blocks) {
+            match block {
+                Block::Table(table) if table.columns.is_empty() => {
+                    return fail("text can't show a table without columns".to_string());
+                }
+                Block::Table(table) => {
+                    for row in &table.rows {
+                        if row.iter().all(|v| *v == Value::Empty) {
+                            return fail("text can't show a table row without values".to_string());
+                        }
+                        for (value, column) in row.iter().zip(&table.columns) {
+                            if typed
+                                && !matches!(value, Value::Text(_))
+                                && cell_value(column.kind, &value.to_string()) != *value
+                            {
+                                return fail(format!(
+                                    "text can't tell {} \"{}\" from a value of its {} column",
+                                    value.kind().map(|k| k.name()).unwrap_or_default(),
+                                    value,
+                                    column.kind.name()
+                                ));
+                            }
+                        }
+                    }
+                }
+                Block::KeyValues(pairs) => {
+                    for (key, value) in pairs {
+                        if typed
+                            && !matches!(value, Value::Text(_))
+                            && read_value(&value.to_string()) != *value
+                        {
+                            return fail(format!("text can't show \"{}\" of {}", value, key));
+                        }
+                    }
+                }
+                Block::Items(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// Only blocks of the same kind, or anything but items or a table after a table, run together
+fn needs_separator(previous: &Block, block: &Block) -> bool {
+    match (previous, block) {
+        (Block::Table(_), Block::Table(_)) => false,
+        (Block::Table(_), Block::Items(items)) => items.is_empty(),
+        (Block::Table(_), _) => true,
+        (Block::Items(a), Block::Items(b)) => !a.is_empty() && !b.is_empty(),
+        (Block::KeyValues(a), Block::KeyValues(b)) => !a.is_empty() && !b.is_empty(),
+        _ => false,
+    }
+}
+
+// Double-quoted, with \\ \" \n and \r escaped (see parsers/text.rs unquote())
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("\"{}\"", escaped)
+}
+
+fn breaks_line(text: &str) -> bool {
+    text.contains(['\n', '\r'])
+}
+
+// A title or heading: the whole line is the text
+fn line_text(text: &str) -> String {
+    if text.starts_with('"') || breaks_line(text) {
+        quote(text)
+    } else {
+        text.to_string()
+    }
+}
+
+// Made of dashes and the like, it would read as a table rule
+fn rule_like(text: &str) -> bool {
+    text.chars()
+        .all(|c| matches!(c, '-' | '#' | '.' | '/' | ' '))
+}
+
+fn item_text(item: &str) -> String {
+    if rule_like(item) || item.starts_with('"') || breaks_line(item) {
+        quote(item)
+    } else {
+        item.to_string()
+    }
+}
+
+// Cells are trimmed when read, and "- " would end the table
+fn cell_text(kind: ColumnType, value: &Value, locale: &Locale) -> String {
+    match value {
+        Value::Text(text)
+            if text.trim() != text
+                || text.starts_with("- ")
+                || rule_like(text)
+                || breaks_line(text)
+                || cell_value(kind, text) != *value =>
+        {
+            quote(text)
+        }
+        value => locale.value(value),
+    }
+}
+
+fn name_text(name: &str) -> String {
+    cell_text(ColumnType::Text, &Value::text(name), &Locale::default())
+}
+
+// Keys end at the first " : " and are trimmed; "-" would start an item or a heading
+fn key_text(key: &str) -> String {
+    if key.starts_with(['"', '-'])
+        || key.contains(" : ")
+        || key.ends_with(" :")
+        || key.trim_end() != key
+        || breaks_line(key)
+    {
+        quote(key)
+    } else {
+        key.to_string()
+    }
+}
+
+// Columns are padded to their widest cell, numbers are right-aligned
+// Shared with other formatters that lay tables out in a monospace font
+pub(crate) fn table_lines(table: &Table, locale: &Locale) -> Vec<String> {
+    let names = table.columns.iter().map(|c| c.name.clone()).collect();
+    let cells = table
+        .rows
+        .iter()
+        .map(|row| row.iter().map(|value| locale.value(value)).collect())
+        .collect();
+    lay_out(table, names, cells)
+}
+
+fn lay_out(table: &Table, names: Vec<String>, cells: Vec<Vec<String>>) -> Vec<String> {
+    let widths: Vec<usize> = (0..table.columns.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(names[i].chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |values: &mut dyn Iterator<Item = &str>| -> String {
+        let padded: Vec<String> = values
+            .zip(&table.columns)
+            .zip(&widths)
+            .map(|((value, column), &width)| {
+                if column.kind.is_numeric() {
+                    format!("{:>width$}", value)
+                } else {
+                    format!("{:<width$}", value)
+                }
+            })
+            .collect();
+        padded.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![line(&mut names.iter().map(String::as_str))];
+    let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+    lines.push(rule.join("  "));
+    for row in &cells {
+        lines.push(line(&mut row.iter().map(String::as_str)));
+    }
+    lines
+}
+
+// The last dash of each column tells its type
+fn typed_rule(rule: &str, table: &Table) -> String {
+    let columns: Vec<String> = rule
+        .split("  ")
+        .zip(&table.columns)
+        .map(|(dashes, column)| {
+            let tag = match column.kind {
+                ColumnType::Text => '-',
+                ColumnType::Integer => '#',
+                ColumnType::Decimal => '.',
+                ColumnType::Date => '/',
+            };
+            format!("{}{}", dashes.get(1..).unwrap_or_default(), tag)
+        })
+        .collect();
+    columns.join("  ")
+}
+
+// As it reads back: a text that would read as a number, a date or nothing is quoted
+fn key_value_text(value: &Value, locale: &Locale) -> String {
+    match value {
+        Value::Text(text) if read_value(text) != *value || breaks_line(text) => quote(text),
+        value => locale.value(value),
+    }
+}
+
+fn write_key_values(
+    pairs: &[(String, Value)],
+    locale: &Locale,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let keys: Vec<String> = pairs.iter().map(|(key, _)| key_text(key)).collect();
+    let width = keys
+        .iter()
+        .map(|key| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (key, (_, value)) in keys.iter().zip(pairs) {
+        write!(out, "\n{:<width$} : {}", key, key_value_text(value, locale))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,461 @@
+// src/formatters/xlsx.rs
+// Spreadsheet output: an Office Open XML workbook (.xlsx) that Excel, LibreOffice and
+// Google Sheets open natively
+// The report is laid out on one sheet, top to bottom like the text output. Numbers are stored
+// as numbers and dates as date serials, so formulas (=SUM(B4:B6)...) work on the result.
+// Texts go to the shared string table; column headers get a bold, shaded style.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use super::ReportFormatter;
+use super::locale::DateStyle;
+use super::markup::Escaped;
+use super::zip::ZipWriter;
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Date, Report, Value};
+
+// Dates show in the reader's short date format unless a date style is set. Numbers are left
+// for the spreadsheet to show the local way.
+#[derive(Default)]
+pub struct XlsxFormatter {
+    sheet_name: Option<String>,
+    dates: Option<DateStyle>,
+}
+
+impl XlsxFormatter {
+    // The report title otherwise
+    pub fn sheet_name(mut self, name: impl Into<String>) -> Self {
+        self.sheet_name = Some(name.into());
+        self
+    }
+
+    pub fn date_style(mut self, style: DateStyle) -> Self {
+        self.dates = Some(style);
+        self
+    }
+}
+
+// sheet_name = Sales, date_style as for the text formatter
+impl Configurable for XlsxFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        Ok(Self {
+            sheet_name: options.take_text("sheet_name").map(|(name, _)| name),
+            dates: DateStyle::from_options(options)?,
+        })
+    }
+}
+
+impl ReportFormatter for XlsxFormatter {
+    fn name(&self) -> &str {
+        "xlsx"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["xlsx"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let sheet = Sheet::from_report(report);
+
+        let mut zip = ZipWriter::new(out);
+        zip.add("[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+        zip.add("_rels/.rels", ROOT_RELATIONSHIPS.as_bytes())?;
+        let sheet_name = self.sheet_name.as_deref().unwrap_or(&report.title);
+        zip.add("xl/workbook.xml", workbook(sheet_name).as_bytes())?;
+        zip.add(
+            "xl/_rels/workbook.xml.rels",
+            WORKBOOK_RELATIONSHIPS.as_bytes(),
+        )?;
+        zip.add("xl/styles.xml", styles(self.dates).as_bytes())?;
+        zip.add("xl/worksheets/sheet1.xml", sheet.xml().as_bytes())?;
+        zip.add("xl/sharedStrings.xml", sheet.shared_strings().as_bytes())?;
+        zip.finish()
+    }
+}
+
+// =========================
+// Worksheet
+// =========================
+
+// Indexes into the cellXfs list of STYLES
+const STYLE_TITLE: u8 = 1;
+const STYLE_HEADING: u8 = 2;
+const STYLE_COLUMN_HEADER: u8 = 3;
+const STYLE_DECIMAL: u8 = 4;
+const STYLE_DATE: u8 = 5;
+const STYLE_KEY: u8 = 6;
+
+struct Sheet {
+    // <row> elements, built as the report is walked
+    rows: String,
+    row_count: usize,
+    // Widest content of each column, in characters
+    widths: Vec<usize>,
+    strings: Vec<String>,
+    string_index: HashMap<String, usize>,
+    string_refs: usize,
+}
+
+impl Sheet {
+    fn from_report(report: &Report) -> Self {
+        let mut sheet = Sheet {
+            rows: String::new(),
+            row_count: 0,
+            widths: Vec::new(),
+            strings: Vec::new(),
+            string_index: HashMap::new(),
+            string_refs: 0,
+        };
+
+        // The title spans the page: it doesn't count for the column width
+        sheet.row(&[(Value::text(&report.title), STYLE_TITLE)], false);
+        for section in &report.sections {
+            sheet.row_count += 1;
+            if let Some(heading) = &section.heading {
+                sheet.row(&[(Value::text(heading), STYLE_HEADING)], false);
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        for item in items {
+                            sheet.row(&[(Value::text(item), 0)], true);
+                        }
+                    }
+                    Block::Table(table) => {
+                        let header: Vec<_> = table
+                            .columns
+                            .iter()
+                            .map(|c| (Value::text(&c.name), STYLE_COLUMN_HEADER))
+                            .collect();
+                        sheet.row(&header, true);
+                        for row in &table.rows {
+                            let cells: Vec<_> = row.iter().map(|v| (v.clone(), 0)).collect();
+                            sheet.row(&cells, true);
+                        }
+                    }
+                    Block::KeyValues(pairs) => {
+                        for (key, value) in pairs {
+                            sheet.row(&[(Value::text(key), STYLE_KEY), (value.clone(), 0)], true);
+                        }
+                    }
+                }
+            }
+        }
+        sheet
+    }
+
+    // One row of cells from column A. Empty values leave their cell out.
+    fn row(&mut self, cells: &[(Value, u8)], measured: bool) {
+        self.row_count += 1;
+        let r = self.row_count;
+        let _ = write!(self.rows, "<row r=\"{}\">", r);
+        for (i, (value, style)) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column_name(i), r);
+            let shown = value.to_string().chars().count();
+            if measured {
+                if self.widths.len() <= i {
+                    self.widths.resize(i + 1, 0);
+                }
+                self.widths[i] = self.widths[i].max(shown);
+            }
+            let (kind, style, content) = match value {
+                Value::Empty => continue,
+                Value::Integer(n) => (None, *style, n.to_string()),
+                Value::Decimal(x) if x.is_finite() => {
+                    let style = if *style == 0 { STYLE_DECIMAL } else { *style };
+                    (None, style, x.to_string())
+                }
+                Value::Date(date) => (None, STYLE_DATE, serial(*date).to_string()),
+                // Text, and infinities or NaN that a number cell can't hold
+                other => {
+                    let index = self.string(&other.to_string());
+                    (Some("s"), *style, index.to_string())
+                }
+            };
+            let _ = write!(self.rows, "<c r=\"{}\"", reference);
+            if let Some(kind) = kind {
+                let _ = write!(self.rows, " t=\"{}\"", kind);
+            }
+            if style != 0 {
+                let _ = write!(self.rows, " s=\"{}\"", style);
+            }
+            let _ = write!(self.rows, "><v>{}</v></c>", content);
+        }
+        self.rows.push_str("</row>");
+    }
+
+    // Index in the shared string table, each distinct text stored once
+    fn string(&mut self, text: &str) -> usize {
+        self.string_refs += 1;
+        if let Some(&index) = self.string_index.get(text) {
+            return index;
+        }
+        let index = self.strings.len();
+        self.strings.push(text.to_string());
+        self.string_index.insert(text.to_string(), index);
+        index
+    }
+
+    fn xml(&self) -> String {
+        let mut xml = String::from(XML_DECLARATION);
+        xml.push_str(&format!("<worksheet xmlns=\"{}\">", MAIN_NAMESPACE));
+        if !self.widths.is_empty() {
+            xml.push_str("<cols>");
+            for (i, width) in self.widths.iter().enumerate() {
+                let _ = write!(
+                    xml,
+                    "<col min=\"{n}\" max=\"{n}\" width=\"{}\" customWidth=\"1\"/>",
+                    (width + 2).clamp(8, 60),
+                    n = i + 1
+                );
+            }
+            xml.push_str("</cols>");
+        }
+        let _ = write!(xml, "<sheetData>{}</sheetData></worksheet>", self.rows);
+        xml
+    }
+
+    fn shared_strings(&self) -> String {
+        let mut xml = String::from(XML_DECLARATION);
+        let _ = write!(
+            xml,
+            "<sst xmlns=\"{}\" count=\"{}\" uniqueCount=\"{}\">",
+            MAIN_NAMESPACE,
+            self.string_refs,
+            self.strings.len()
+        );
+        for text in &self.strings {
+            // Control characters are not allowed in XML 1.0, even escaped
+            let text: String = text
+                .chars()
+                .filter(|&c| c >= ' ' || matches!(c, '\t' | '\n' | '\r'))
+                .collect();
+            let _ = write!(
+                xml,
+                "<si><t xml:space=\"preserve\">{}</t></si>",
+                Escaped(text)
+            );
+        }
+        xml.push_str("</sst>");
+        xml
+    }
+}
+
+// 0 -> "A", 25 -> "Z", 26 -> "AA"...
+fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        name.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.iter().rev().map(|&b| b as char).collect()
+}
+
+// Spreadsheet dates count days from 1899-12-30
+fn serial(date: Date) -> i64 {
+    date.unix_days() + 25_569
+}
+
+// =========================
+// Package parts
+// =========================
+
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+const MAIN_NAMESPACE: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+
+const CONTENT_TYPES: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    "<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">",
+    "<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>",
+    "<Default Extension=\"xml\" ContentType=\"application/xml\"/>",
+    "<Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>",
+    "<Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>",
+    "<Override PartName=\"/xl/styles.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml\"/>",
+    "<Override PartName=\"/xl/sharedStrings.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sharedStrings+xml\"/>",
+    "</Types>"
+);
+
+const ROOT_RELATIONSHIPS: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    "<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>",
+    "</Relationships>"
+);
+
+const WORKBOOK_RELATIONSHIPS: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+    "<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+    "<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet1.xml\"/>",
+    "<Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles\" Target=\"styles.xml\"/>",
+    "<Relationship Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/sharedStrings\" Target=\"sharedStrings.xml\"/>",
+    "</Relationships>"
+);
+
+// Fonts: regular, bold, large bold. Number formats 4 and 14 are built in: "#,##0.00" and a short date.
+// A date style is a custom number format, 164 being the first id left for those.
+fn styles(dates: Option<DateStyle>) -> String {
+    let (formats, date_format) = match dates {
+        Some(style) => (
+            format!(
+                "<numFmts count=\"1\"><numFmt numFmtId=\"164\" formatCode=\"{}\"/></numFmts>",
+                date_format(style)
+            ),
+            164,
+        ),
+        None => (String::new(), 14),
+    };
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n",
+            "<styleSheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">",
+            "{formats}",
+            "<fonts count=\"3\">",
+            "<font><sz val=\"11\"/><name val=\"Calibri\"/></font>",
+            "<font><b/><sz val=\"11\"/><name val=\"Calibri\"/></font>",
+            "<font><b/><sz val=\"14\"/><name val=\"Calibri\"/></font>",
+            "</fonts>",
+            "<fills count=\"3\">",
+            "<fill><patternFill patternType=\"none\"/></fill>",
+            "<fill><patternFill patternType=\"gray125\"/></fill>",
+            "<fill><patternFill patternType=\"solid\"><fgColor rgb=\"FFDDE5F4\"/><bgColor indexed=\"64\"/></patternFill></fill>",
+            "</fills>",
+            "<borders count=\"2\">",
+            "<border><left/><right/><top/><bottom/><diagonal/></border>",
+            "<border><left/><right/><top/><bottom style=\"thin\"><color auto=\"1\"/></bottom><diagonal/></border>",
+            "</borders>",
+            "<cellStyleXfs count=\"1\"><xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\"/></cellStyleXfs>",
+            "<cellXfs count=\"7\">",
+            // 0: default
+            "<xf numFmtId=\"0\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\"/>",
+            // 1: title
+            "<xf numFmtId=\"0\" fontId=\"2\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/>",
+            // 2: section heading
+            "<xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/>",
+            // 3: column header
+            "<xf numFmtId=\"0\" fontId=\"1\" fillId=\"2\" borderId=\"1\" xfId=\"0\" applyFont=\"1\" applyFill=\"1\" applyBorder=\"1\"/>",
+            // 4: decimal
+            "<xf numFmtId=\"4\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>",
+            // 5: date
+            "<xf numFmtId=\"{date_format}\" fontId=\"0\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyNumberFormat=\"1\"/>",
+            // 6: key of a key/value pair
+            "<xf numFmtId=\"0\" fontId=\"1\" fillId=\"0\" borderId=\"0\" xfId=\"0\" applyFont=\"1\"/>",
+            "</cellXfs>",
+            "<cellStyles count=\"1\"><cellStyle name=\"Normal\" xfId=\"0\" builtinId=\"0\"/></cellStyles>",
+            "</styleSheet>"
+        ),
+        formats = formats,
+        date_format = date_format
+    )
+}
+
+// '/' is the reader's date separator in a format code: escaped, it stays a slash
+fn date_format(style: DateStyle) -> &'static str {
+    match style {
+        DateStyle::Iso => "yyyy-mm-dd",
+        DateStyle::DayMonthYear => "dd\\/mm\\/yyyy",
+        DateStyle::MonthDayYear => "mm\\/dd\\/yyyy",
+        DateStyle::Long => "d mmmm yyyy",
+    }
+}
+
+// The sheet name is cut to 31 characters, none of []:*?/\
+fn workbook(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\') && *c >= ' ')
+        .take(31)
+        .collect();
+    let name = name.trim().trim_matches('\'');
+    let name = if name.is_empty() { "Report" } else { name };
+    format!(
+        "{}<workbook xmlns=\"{}\" \
+         xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+         <sheets><sheet name=\"{}\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>",
+        XML_DECLARATION,
+        MAIN_NAMESPACE,
+        Escaped(name)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Column, ColumnType, Section, Table};
+
+    #[test]
+    fn numbers_stay_numbers() {
+        let report = Report::new("Sales").section(
+            Section::new("By product").table(
+                Table::new(vec![
+                    Column::new("Product", ColumnType::Text),
+                    Column::new("Units", ColumnType::Integer),
+                    Column::new("Revenue", ColumnType::Decimal),
+                    Column::new("Day", ColumnType::Date),
+                ])
+                .row(vec![
+                    Value::text("A & B"),
+                    Value::Integer(120),
+                    Value::Decimal(2_398.8),
+                    Value::Date(Date::new(2025, 1, 30)),
+                ])
+                .row(vec![
+                    Value::text("Sales"),
+                    Value::Empty,
+                    Value::Decimal(f64::NAN),
+                    Value::Empty,
+                ]),
+            ),
+        );
+
+        let sheet = Sheet::from_report(&report);
+
+        let rows = &sheet.rows;
+        assert!(rows.contains("<row r=\"3\"><c r=\"A3\" t=\"s\" s=\"2\"><v>1</v></c></row>"));
+        assert!(rows.contains("<c r=\"A4\" t=\"s\" s=\"3\"><v>2</v></c>"));
+        assert!(rows.contains(concat!(
+            "<row r=\"5\"><c r=\"A5\" t=\"s\"><v>6</v></c><c r=\"B5\"><v>120</v></c>",
+            "<c r=\"C5\" s=\"4\"><v>2398.8</v></c><c r=\"D5\" s=\"5\"><v>45687</v></c></row>"
+        )));
+        // "Sales" is shared with the title, NaN can only be text
+        assert!(rows.contains(
+            "<row r=\"6\"><c r=\"A6\" t=\"s\"><v>0</v></c><c r=\"C6\" t=\"s\"><v>7</v></c></row>"
+        ));
+        let strings = sheet.shared_strings();
+        assert!(strings.contains("count=\"9\" uniqueCount=\"8\""));
+        assert!(strings.contains("<si><t xml:space=\"preserve\">A &amp; B</t></si>"));
+    }
+
+    #[test]
+    fn sheet_name_and_date_style_from_options() {
+        let mut options = crate::config::parse("[xlsx]\nsheet_name = Q1\ndate_style = dmy")
+            .unwrap()
+            .remove(0);
+
+        let formatter = XlsxFormatter::from_options(&mut options).unwrap();
+
+        assert_eq!(formatter.sheet_name.as_deref(), Some("Q1"));
+        let custom = styles(formatter.dates);
+        assert!(custom.contains("<numFmt numFmtId=\"164\" formatCode=\"dd\\/mm\\/yyyy\"/>"));
+        assert!(custom.contains("<xf numFmtId=\"164\" fontId=\"0\""));
+        assert!(styles(None).contains("<xf numFmtId=\"14\" fontId=\"0\""));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+        assert!(workbook("Q1: Sales [draft]").contains("sheet name=\"Q1 Sales draft\""));
+        assert_eq!(serial(Date::new(1900, 3, 1)), 61);
+    }
+}
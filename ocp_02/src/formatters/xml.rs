@@ -0,0 +1,271 @@
+// src/formatters/xml.rs
+// XML output - extension without modification
+// Documents are namespaced and validate against the published schema (ocp_02/schema/report.xsd)
+// Nothing is lost: decimals keep full precision and a cell carries its own type whenever the
+// column type alone wouldn't tell (empty text, a value of another type), so XmlParser reads it back
+
+use std::io::{self, Write};
+
+use super::markup::Escaped;
+use super::{ReportFormatter, take_indent};
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report, Table, Value};
+
+pub struct XmlFormatter {
+    // Spaces per nesting level
+    indent: usize,
+}
+
+impl Default for XmlFormatter {
+    fn default() -> Self {
+        Self { indent: 2 }
+    }
+}
+
+impl XmlFormatter {
+    pub const NAMESPACE: &str = "urn:ex-02-ocp:report:1";
+    // The XSD, so callers can publish or validate against it without reading the repository
+    pub const SCHEMA: &str = include_str!("../../schema/report.xsd");
+
+    pub fn indent(mut self, spaces: usize) -> Self {
+        self.indent = spaces;
+        self
+    }
+
+    fn pad(&self, level: usize) -> String {
+        " ".repeat(self.indent * level)
+    }
+
+    fn write_table(&self, table: &Table, out: &mut dyn Write) -> io::Result<()> {
+        let (table_pad, row_pad) = (self.pad(2), self.pad(3));
+        writeln!(out, "{}<table>", table_pad)?;
+        writeln!(out, "{}<columns>", row_pad)?;
+        for column in &table.columns {
+            writeln!(
+                out,
+                "{}<column name=\"{}\" type=\"{}\"/>",
+                self.pad(4),
+                Escaped(&column.name),
+                column.kind.name()
+            )?;
+        }
+        writeln!(out, "{}</columns>", row_pad)?;
+        for row in &table.rows {
+            write!(out, "{}<row>", row_pad)?;
+            for (column, value) in table.columns.iter().zip(row) {
+                match value.kind() {
+                    None => write!(out, "<cell/>")?,
+                    Some(kind) if kind == column.kind && !is_empty_text(value) => {
+                        write!(out, "<cell>{}</cell>", Escaped(raw(value)))?
+                    }
+                    Some(kind) => write!(
+                        out,
+                        "<cell type=\"{}\">{}</cell>",
+                        kind.name(),
+                        Escaped(raw(value))
+                    )?,
+                }
+            }
+            writeln!(out, "</row>")?;
+        }
+        writeln!(out, "{}</table>", table_pad)
+    }
+
+    fn write_key_values(&self, pairs: &[(String, Value)], out: &mut dyn Write) -> io::Result<()> {
+        let (entries_pad, entry_pad) = (self.pad(2), self.pad(3));
+        writeln!(out, "{}<entries>", entries_pad)?;
+        for (key, value) in pairs {
+            match value.kind() {
+                Some(kind) => writeln!(
+                    out,
+                    "{}<entry key=\"{}\" type=\"{}\">{}</entry>",
+                    entry_pad,
+                    Escaped(key),
+                    kind.name(),
+                    Escaped(raw(value))
+                )?,
+                None => writeln!(out, "{}<entry key=\"{}\"/>", entry_pad, Escaped(key))?,
+            }
+        }
+        writeln!(out, "{}</entries>", entries_pad)
+    }
+}
+
+// indent = 4
+impl Configurable for XmlFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let mut formatter = Self::default();
+        if let Some(indent) = take_indent(options)? {
+            formatter = formatter.indent(indent);
+        }
+        Ok(formatter)
+    }
+}
+
+impl ReportFormatter for XmlFormatter {
+    fn name(&self) -> &str {
+        "xml"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["xml"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "application/xml"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(
+            out,
+            "<report xmlns=\"{ns}\" \
+             xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+             xsi:schemaLocation=\"{ns} report.xsd\">",
+            ns = Self::NAMESPACE
+        )?;
+        let (section_pad, block_pad, item_pad) = (self.pad(1), self.pad(2), self.pad(3));
+        writeln!(
+            out,
+            "{}<title>{}</title>",
+            section_pad,
+            Escaped(&report.title)
+        )?;
+
+        for section in &report.sections {
+            match &section.heading {
+                Some(heading) => writeln!(
+                    out,
+                    "{}<section heading=\"{}\">",
+                    section_pad,
+                    Escaped(heading)
+                )?,
+                None => writeln!(out, "{}<section>", section_pad)?,
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        writeln!(out, "{}<items>", block_pad)?;
+                        for item in items {
+                            writeln!(out, "{}<item>{}</item>", item_pad, Escaped(item))?;
+                        }
+                        writeln!(out, "{}</items>", block_pad)?;
+                    }
+                    Block::Table(table) => self.write_table(table, out)?,
+                    Block::KeyValues(pairs) => self.write_key_values(pairs, out)?,
+                }
+            }
+            writeln!(out, "{}</section>", section_pad)?;
+        }
+
+        writeln!(out, "</report>")
+    }
+}
+
+// Full precision for decimals, so they read back to the same f64
+fn raw(value: &Value) -> String {
+    match value {
+        Value::Decimal(x) => format!("{:?}", x),
+        other => other.to_string(),
+    }
+}
+
+// An empty <cell/> is an empty value, empty text must say it is text
+fn is_empty_text(value: &Value) -> bool {
+    matches!(value, Value::Text(s) if s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Column, ColumnType, Date, Section, Table, Value};
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn declaration_namespace_and_escaping() {
+        let report = Report::new("R&D <beta>").section(Section::new("\"Q\"").items(["a < b"]));
+
+        let xml = String::from_utf8(XmlFormatter::default().format(&report).unwrap()).unwrap();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<report xmlns=\""));
+        assert!(xml.contains("<title>R&amp;D &lt;beta&gt;</title>"));
+        assert!(xml.contains("<section heading=\"&quot;Q&quot;\">"));
+        assert!(xml.contains("<item>a &lt; b</item>"));
+        assert!(XmlFormatter::SCHEMA.contains(XmlFormatter::NAMESPACE));
+    }
+
+    #[test]
+    fn output_conforms_to_the_schema() {
+        let report = Report::new("Q1 \u{1}")
+            .section(Section::untitled().items(["a", "b\u{7}"]))
+            .section(
+                Section::new("Sales")
+                    .table(
+                        Table::new(vec![
+                            Column::new("Product", ColumnType::Text),
+                            Column::new("Units", ColumnType::Integer),
+                            Column::new("Last order", ColumnType::Date),
+                        ])
+                        .row(vec![
+                            Value::text("A"),
+                            Value::Integer(120),
+                            Value::Date(Date::new(2025, 1, 30)),
+                        ])
+                        .row(vec![
+                            Value::text(""),
+                            Value::Decimal(1.5),
+                            Value::Empty,
+                        ]),
+                    )
+                    .key_values([("Total", Value::Integer(218)), ("Note", Value::Empty)]),
+            );
+
+        let xml = String::from_utf8(XmlFormatter::default().format(&report).unwrap()).unwrap();
+
+        // Every element and attribute name, but the namespace ones, is declared in the schema
+        let declared = |name: &str| XmlFormatter::SCHEMA.contains(&format!("name=\"{}\"", name));
+        for tag in xml.split('<').skip(1).map(|t| &t[..t.find('>').unwrap()]) {
+            if tag.starts_with(['?', '/']) {
+                continue;
+            }
+            let (element, attributes) = tag
+                .split_once(' ')
+                .unwrap_or((tag.trim_end_matches('/'), ""));
+            assert!(declared(element), "<{}> is not in the schema", element);
+            for attribute in attributes
+                .split('"')
+                .step_by(2)
+                .filter_map(|a| a.trim().strip_suffix('='))
+            {
+                if !attribute.starts_with("xmlns") && !attribute.starts_with("xsi:") {
+                    assert!(declared(attribute), "@{} is not in the schema", attribute);
+                }
+            }
+        }
+        assert!(!xml.contains(|c: char| c < ' ' && c != '\n'));
+
+        // Full validation, where libxml2 is installed
+        let schema = concat!(env!("CARGO_MANIFEST_DIR"), "/schema/report.xsd");
+        let Ok(mut xmllint) = Command::new("xmllint")
+            .args(["--noout", "--schema", schema, "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+        else {
+            return;
+        };
+        xmllint
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(xml.as_bytes())
+            .unwrap();
+        let output = xmllint.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -0,0 +1,212 @@
+// src/formatters/yaml.rs
+// YAML output (block style, YAML 1.2)
+// Strings stay plain when that is unambiguous, otherwise they are double-quoted and escaped.
+// Dates are quoted too: YAML 1.1 readers would otherwise turn them into timestamps.
+
+use std::io::{self, Write};
+
+use super::json::json_string;
+use super::{ReportFormatter, take_indent};
+use crate::config::{ConfigError, Configurable, Options};
+use crate::report::{Block, Report, Value};
+
+pub struct YamlFormatter {
+    // Spaces a nested sequence or mapping is indented by, at least 1
+    indent: usize,
+}
+
+impl Default for YamlFormatter {
+    fn default() -> Self {
+        Self { indent: 2 }
+    }
+}
+
+impl YamlFormatter {
+    pub fn indent(mut self, spaces: usize) -> Self {
+        assert!(spaces > 0, "YAML nests by indentation");
+        self.indent = spaces;
+        self
+    }
+}
+
+// indent = 4
+impl Configurable for YamlFormatter {
+    fn from_options(options: &mut Options) -> Result<Self, ConfigError> {
+        let mut formatter = Self::default();
+        if let Some(indent) = take_indent(options)? {
+            formatter = formatter.indent(indent);
+        }
+        Ok(formatter)
+    }
+}
+
+impl ReportFormatter for YamlFormatter {
+    fn name(&self) -> &str {
+        "yaml"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["yaml", "yml"]
+    }
+
+    fn mime_type(&self) -> &str {
+        "application/yaml"
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "title: {}", yaml_string(&report.title))?;
+        if report.sections.is_empty() {
+            return writeln!(out, "sections: []");
+        }
+        // A sequence is indented under its key, the keys of a "- " entry line up after the dash
+        let pad = |width: usize| " ".repeat(width);
+        let section = self.indent;
+        let block = section + 2 + self.indent;
+        let inner = block + 2 + self.indent;
+        let (section, block, inner, nested) = (
+            pad(section),
+            pad(block),
+            pad(inner),
+            pad(inner + self.indent),
+        );
+        writeln!(out, "sections:")?;
+        for s in &report.sections {
+            match &s.heading {
+                Some(heading) => writeln!(out, "{}- heading: {}", section, yaml_string(heading))?,
+                None => writeln!(out, "{}- heading: null", section)?,
+            }
+            if s.blocks.is_empty() {
+                writeln!(out, "{}  blocks: []", section)?;
+                continue;
+            }
+            writeln!(out, "{}  blocks:", section)?;
+            for b in &s.blocks {
+                match b {
+                    Block::Items(items) => {
+                        if items.is_empty() {
+                            writeln!(out, "{}- items: []", block)?;
+                        } else {
+                            writeln!(out, "{}- items:", block)?;
+                        }
+                        for item in items {
+                            writeln!(out, "{}- {}", inner, yaml_string(item))?;
+                        }
+                    }
+                    Block::Table(table) => {
+                        writeln!(out, "{}- table:", block)?;
+                        writeln!(out, "{}columns:", inner)?;
+                        for column in &table.columns {
+                            writeln!(
+                                out,
+                                "{}- {{ name: {}, type: {} }}",
+                                nested,
+                                yaml_string(&column.name),
+                                column.kind.name()
+                            )?;
+                        }
+                        if table.rows.is_empty() {
+                            writeln!(out, "{}rows: []", inner)?;
+                        } else {
+                            writeln!(out, "{}rows:", inner)?;
+                        }
+                        for row in &table.rows {
+                            let cells: Vec<String> = row.iter().map(yaml_value).collect();
+                            writeln!(out, "{}- [{}]", nested, cells.join(", "))?;
+                        }
+                    }
+                    Block::KeyValues(pairs) => {
+                        if pairs.is_empty() {
+                            writeln!(out, "{}- key_values: []", block)?;
+                        } else {
+                            writeln!(out, "{}- key_values:", block)?;
+                        }
+                        for (key, value) in pairs {
+                            let kind = value.kind().map(|k| k.name()).unwrap_or("null");
+                            writeln!(
+                                out,
+                                "{}- {{ key: {}, type: {}, value: {} }}",
+                                inner,
+                                yaml_string(key),
+                                kind,
+                                yaml_value(value)
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn yaml_value(value: &Value) -> String {
+    match value {
+        Value::Empty => "null".to_string(),
+        Value::Text(s) => yaml_string(s),
+        Value::Integer(n) => n.to_string(),
+        Value::Decimal(x) if x.is_nan() => ".nan".to_string(),
+        Value::Decimal(x) if x.is_infinite() => if *x > 0.0 { ".inf" } else { "-.inf" }.to_string(),
+        Value::Decimal(x) => format!("{:?}", x),
+        Value::Date(d) => format!("\"{}\"", d),
+    }
+}
+
+// Plain scalar when it can't be mistaken for anything else, double-quoted otherwise
+// Every string here may end up inside a flow collection ([...] or {...}), so flow
+// indicators force quoting as well
+fn yaml_string(s: &str) -> String {
+    if is_plain_safe(s) {
+        s.to_string()
+    } else {
+        json_string(s)
+    }
+}
+
+fn is_plain_safe(s: &str) -> bool {
+    let Some(first) = s.chars().next() else {
+        return false;
+    };
+    let reserved = [
+        "null", "Null", "NULL", "~", "true", "True", "TRUE", "false", "False", "FALSE", "yes",
+        "Yes", "YES", "no", "No", "NO", "on", "On", "ON", "off", "Off", "OFF",
+    ];
+    !"-?:,[]{}#&*!|>'\"%@`".contains(first)
+        && !first.is_whitespace()
+        && !s.ends_with(char::is_whitespace)
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !s.contains([',', '[', ']', '{', '}'])
+        && !s.chars().any(char::is_control)
+        && !reserved.contains(&s)
+        && !looks_numeric(s)
+}
+
+// Anything a YAML reader could take for a number (including .5, 1e3, 0x1F, .inf...)
+fn looks_numeric(s: &str) -> bool {
+    let body = s.trim_start_matches(['+', '-']);
+    body.starts_with(|c: char| c.is_ascii_digit())
+        || (body.starts_with('.') && body[1..].starts_with(|c: char| c.is_ascii_digit()))
+        || matches!(body.to_ascii_lowercase().as_str(), ".inf" | ".nan")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ambiguous_strings_are_quoted() {
+        assert_eq!(yaml_string("Monthly Sales"), "Monthly Sales");
+        assert_eq!(
+            yaml_string("Product A: 120 units"),
+            "\"Product A: 120 units\""
+        );
+        assert_eq!(yaml_string("yes"), "\"yes\"");
+        assert_eq!(yaml_string("2025"), "\"2025\"");
+        assert_eq!(yaml_string("-.5"), "\"-.5\"");
+        assert_eq!(yaml_string("- item"), "\"- item\"");
+        assert_eq!(yaml_string("a, b"), "\"a, b\"");
+        assert_eq!(yaml_string(""), "\"\"");
+        assert_eq!(yaml_string("two\nlines"), "\"two\\nlines\"");
+    }
+}
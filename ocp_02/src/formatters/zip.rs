@@ -0,0 +1,150 @@
+// src/formatters/zip.rs
+// A minimal zip writer for the container formats (XLSX...)
+// Entries are stored, not compressed: every reader accepts it and no dependency is needed.
+// Each entry goes to the sink as soon as it is added; the central directory follows on finish().
+
+use std::io::{self, Write};
+
+pub struct ZipWriter<'a> {
+    out: &'a mut dyn Write,
+    // Bytes written so far: the offset of the next local header
+    offset: u32,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+// 1980-01-01 00:00, the earliest DOS date: the output doesn't depend on the clock
+const DOS_TIME: u16 = 0;
+const DOS_DATE: u16 = 1 << 5 | 1;
+
+impl<'a> ZipWriter<'a> {
+    pub fn new(out: &'a mut dyn Write) -> Self {
+        Self {
+            out,
+            offset: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "zip entry over 4 GiB");
+        let size = u32::try_from(data.len()).map_err(|_| too_large())?;
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32(data),
+            size,
+            offset: self.offset,
+        };
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend(0x04034b50u32.to_le_bytes());
+        // Version needed (2.0), flags, method (stored)
+        header.extend([20u16, 0, 0].iter().flat_map(|n| n.to_le_bytes()));
+        header.extend(DOS_TIME.to_le_bytes());
+        header.extend(DOS_DATE.to_le_bytes());
+        header.extend(entry.crc.to_le_bytes());
+        // Compressed and uncompressed sizes are the same
+        header.extend(size.to_le_bytes());
+        header.extend(size.to_le_bytes());
+        header.extend((name.len() as u16).to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend(name.as_bytes());
+
+        self.out.write_all(&header)?;
+        self.out.write_all(data)?;
+        self.offset = (header.len() as u32)
+            .checked_add(size)
+            .and_then(|n| n.checked_add(self.offset))
+            .ok_or_else(too_large)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    // Central directory and end record: without them the archive can't be read
+    pub fn finish(self) -> io::Result<()> {
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend(0x02014b50u32.to_le_bytes());
+            // Made by and needed: version 2.0; flags, method (stored)
+            directory.extend([20u16, 20, 0, 0].iter().flat_map(|n| n.to_le_bytes()));
+            directory.extend(DOS_TIME.to_le_bytes());
+            directory.extend(DOS_DATE.to_le_bytes());
+            directory.extend(entry.crc.to_le_bytes());
+            directory.extend(entry.size.to_le_bytes());
+            directory.extend(entry.size.to_le_bytes());
+            directory.extend((entry.name.len() as u16).to_le_bytes());
+            // Extra field and comment lengths, disk number, internal and external attributes
+            directory.extend([0u16; 4].iter().flat_map(|n| n.to_le_bytes()));
+            directory.extend(0u32.to_le_bytes());
+            directory.extend(entry.offset.to_le_bytes());
+            directory.extend(entry.name.as_bytes());
+        }
+
+        let (count, size) = (self.entries.len() as u16, directory.len() as u32);
+        directory.extend(0x06054b50u32.to_le_bytes());
+        // This disk, the disk with the directory, entries on this disk, entries in total
+        directory.extend([0u16, 0, count, count].iter().flat_map(|n| n.to_le_bytes()));
+        directory.extend(size.to_le_bytes());
+        directory.extend(self.offset.to_le_bytes());
+        directory.extend(0u16.to_le_bytes());
+        self.out.write_all(&directory)
+    }
+}
+
+// CRC-32 (IEEE 802.3), as zip wants it
+fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    0xEDB8_8320 ^ (crc >> 1)
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+    !data.iter().fold(!0u32, |crc, &byte| {
+        TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_entries_and_directory() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+
+        let mut out = Vec::new();
+        let mut zip = ZipWriter::new(&mut out);
+        zip.add("a.txt", b"hello").unwrap();
+        zip.add("dir/b.txt", b"").unwrap();
+        zip.finish().unwrap();
+
+        // Two local headers with their data, two directory records, the end record
+        let locals = 30 + 5 + 5 + 30 + 9;
+        assert_eq!(out.len(), locals + 46 + 5 + 46 + 9 + 22);
+        assert_eq!(&out[30..40], b"a.txthello");
+        let end = &out[out.len() - 22..];
+        assert_eq!(end[..4], 0x06054b50u32.to_le_bytes());
+        assert_eq!(end[10..12], 2u16.to_le_bytes());
+        assert_eq!(end[16..20], (locals as u32).to_le_bytes());
+    }
+}
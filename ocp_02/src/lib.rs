@@ -0,0 +1,15 @@
+// src/lib.rs
+// The reporting library shared by the demo (main.rs) and the report command line tool (bin/report.rs)
+// See main.rs for a tour of the modules
+
+pub mod batch;
+pub mod config;
+pub mod decorators;
+pub mod diff;
+pub mod formatters;
+pub mod parsers;
+pub mod plugin;
+pub mod query;
+pub mod registry;
+pub mod report;
+pub mod schedule;
@@ -1,106 +1,507 @@
 // cargo run -p ex_02_ocp
+// cargo build -p ex_02_ocp_rst_plugin first to see a plugin formatter in action
 
 // =========================
 // Dynamic Traits Based Solution - Reporter
 // =========================
 
-// =========================
-// Abstractions
-// =========================
+// The report (report.rs) doesn't know about specific formats
+// Each formatter lives in its own module under formatters/
+// Adding a format means adding a file, never modifying Report
 
-// The report doesn't know about specific formats
-pub struct Report {
-    title: String,
-    data: Vec<String>,
-}
+// The registry (registry.rs) lets callers pick a formatter at runtime,
+// by name, file extension or MIME type, without naming a concrete type
+// Formats can even come from outside the program: a template file (templates/) is loaded
+// and registered at runtime like any built-in formatter
+// ...or from a shared library (plugin/): third parties ship formats without touching this build
 
-// However, the report has a .generate() method which calls the .format() method of the formatter
-// The call will be resolved at runtime (via a vtable)
-impl Report {
-    pub fn generate(&self, formatter: &dyn ReportFormatter) -> String {
-        formatter.format(&self.title, &self.data)
-    }
-}
+// Parsers (parsers/) read text, XML, JSON and CSV back into a Report,
+// so a report produced elsewhere can be re-rendered in any format
 
-// If a type wants to have the ReportFormatter trait it must implement the .format() method
-pub trait ReportFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String;
-}
+// Decorators (decorators/) wrap any formatter to add headers, footers, a table of contents
+// or page numbers, without touching the formatter they wrap
+
+// Queries (query.rs) filter, sort and group the report data before formatting,
+// so every view of the data works with every formatter
+// A diff (diff.rs) compares two periods of a report, and is rendered by any formatter as well
+
+// Formatters with settings (CSV delimiter, HTML theme...) read typed options from a
+// config file (config.rs), so a new setting never needs a new formatter type
+// The batch renderer (batch.rs) renders many reports in many formats on all cores
+// The scheduler (schedule/) renders reports from named data sources on cron-like schedules
+
+use ex_02_ocp::{
+    batch, config, decorators, diff, formatters, parsers, plugin, query, registry, report, schedule,
+};
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use batch::BatchRenderer;
+use decorators::contents::TableOfContents;
+use decorators::header_footer::HeaderFooter;
+use decorators::pagination::Paginated;
+use diff::DiffFormatter;
+use formatters::csv::CsvFormatter;
+use formatters::email::EmailFormatter;
+use formatters::html::{HtmlFormatter, Theme};
+use formatters::pdf::PdfFormatter;
+use formatters::svg::{ChartKind, SvgChartFormatter};
+use formatters::template::TemplateFormatter;
+use formatters::text::TextFormatter;
+use formatters::xml::XmlFormatter;
+use parsers::ReportParser;
+use parsers::csv::CsvParser;
+use parsers::json::JsonParser;
+use parsers::text::TextParser;
+use parsers::xml::XmlParser;
+use query::{Order, Query};
+use registry::FormatterRegistry;
+use report::{Column, ColumnType, Date, Report, Section, Table, Value};
+use schedule::cron::DateTime;
+use schedule::history::RunHistory;
+use schedule::{CatchUp, Job, Scheduler};
 
 // =========================
-// Concrete formatters
+// Usage
 // =========================
 
-// Plain text output (same behavior as before)
-pub struct TextFormatter;
+fn main() {
+    let report = Report::new("Monthly Sales")
+        .section(Section::untitled().items([
+            "Product A: 120 units",
+            "Product B: 98 units",
+            "Product C: 143 units",
+        ]))
+        .section(
+            Section::new("Sales by product").table(
+                Table::new(vec![
+                    Column::new("Product", ColumnType::Text),
+                    Column::new("Units", ColumnType::Integer),
+                    Column::new("Revenue", ColumnType::Decimal),
+                    Column::new("Last order", ColumnType::Date),
+                ])
+                .row(vec![
+                    Value::text("Product A"),
+                    Value::Integer(120),
+                    Value::Decimal(2_398.80),
+                    Value::Date(Date::new(2025, 1, 30)),
+                ])
+                .row(vec![
+                    Value::text("Product B"),
+                    Value::Integer(98),
+                    Value::Decimal(4_890.20),
+                    Value::Date(Date::new(2025, 1, 28)),
+                ])
+                .row(vec![
+                    Value::text("Product C"),
+                    Value::Integer(143),
+                    Value::Decimal(1_429.99),
+                    Value::Empty,
+                ]),
+            ),
+        )
+        .section(Section::new("Summary").key_values([
+            ("Period", Value::text("January 2025")),
+            ("Total units", Value::Integer(361)),
+            ("Total revenue", Value::Decimal(8_718.99)),
+            ("Generated on", Value::Date(Date::new(2025, 2, 1))),
+        ]));
+
+    let mut registry = FormatterRegistry::with_builtin_formatters();
 
-impl ReportFormatter for TextFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String {
-        let mut output = format!("=== {} ===", title);
-        for item in data {
-            output.push_str(&format!("\n- {}", item));
+    let template = concat!(env!("CARGO_MANIFEST_DIR"), "/templates/digest.md.tpl");
+    match TemplateFormatter::from_file(template) {
+        Ok(digest) => {
+            if let Err(e) = registry.register(Box::new(digest)) {
+                eprintln!("could not register {}: {}", template, e);
+            }
         }
-        output
+        Err(e) => eprintln!("could not load {}: {}", template, e),
     }
-}
 
-// HTML output (same structure as initial example)
-pub struct HtmlFormatter;
+    // Plugins live in $REPORT_PLUGIN_DIR, or next to the executable (where cargo puts cdylibs)
+    let plugin_dir = std::env::var_os("REPORT_PLUGIN_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_exe().ok()?.parent().map(PathBuf::from));
+    if let Some(dir) = plugin_dir {
+        match plugin::load_plugins(&dir, &mut registry) {
+            Ok(outcomes) => {
+                for (path, outcome) in outcomes {
+                    match outcome {
+                        Ok(name) => println!("plugin \"{}\" loaded from {}", name, path.display()),
+                        Err(e) => eprintln!("skipped plugin {}: {}", path.display(), e),
+                    }
+                }
+            }
+            Err(e) => eprintln!("could not read plugin directory {}: {}", dir.display(), e),
+        }
+    }
+
+    println!("--- AVAILABLE FORMATS ---");
+    for formatter in registry.formats() {
+        println!(
+            "{:<6} {:<18} .{}",
+            formatter.name(),
+            formatter.mime_type(),
+            formatter.extensions().join(", .")
+        );
+    }
 
-impl ReportFormatter for HtmlFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String {
-        let mut output = format!("<h1>{}</h1>\n<ul>\n", title);
-        for item in data {
-            output.push_str(&format!("  <li>{}</li>\n", item));
+    // Text formats are UTF-8, they can be printed
+    let as_text = |bytes: Vec<u8>| String::from_utf8_lossy(&bytes).into_owned();
+    for query in [
+        "text",
+        "text/html",
+        "json",
+        "export.csv",
+        "application/yaml",
+        "md",
+        "digest",
+        "rst",
+        "terminal",
+    ] {
+        let Some(formatter) = registry.lookup(query) else {
+            eprintln!("no formatter for \"{}\"", query);
+            continue;
+        };
+        println!("\n--- {} ({}) ---", formatter.name().to_uppercase(), query);
+        // Templates and plugins can fail on a report their author didn't foresee
+        match report.generate(formatter) {
+            Ok(output) => println!("{}", as_text(output).trim_end()),
+            Err(e) => eprintln!("{} could not render the report: {}", formatter.name(), e),
         }
-        output.push_str("</ul>");
-        output
     }
-}
 
-// Fake PDF output (same spirit as before)
-pub struct PdfFormatter;
+    // Decorated: same text formatter, printed on 16-line pages with a contents list, a header and a footer
+    // Outermost, HeaderFooter lets the pages repeat its lines
+    let printable = HeaderFooter::new(Paginated::new(
+        TableOfContents::new(TextFormatter::default()).min_sections(2),
+        16,
+    ))
+    .header("{title}, internal")
+    .footer("Printed {timestamp}");
+    println!("\n--- TEXT, DECORATED ---");
+    match report.generate(&printable) {
+        Ok(output) => print!("{}", as_text(output).replace('\x0c', "\n")),
+        Err(e) => eprintln!("could not render the decorated text: {}", e),
+    }
 
-impl ReportFormatter for PdfFormatter {
-    fn format(&self, title: &str, _data: &[String]) -> String {
-        format!("PDF: {} [binary data]", title)
+    // Another view of the same data, rendered by an unchanged formatter
+    let best_sellers = Query::new()
+        .filter(
+            "Units",
+            |units| matches!(units, Value::Integer(n) if *n >= 100),
+        )
+        .sort_by("Revenue", Order::Descending)
+        .limit(2)
+        .subtotal("Units")
+        .subtotal("Revenue");
+    println!("\n--- MARKDOWN, BEST SELLERS ---");
+    match (report.query(&best_sellers), registry.lookup("md")) {
+        (Ok(view), Some(markdown)) => match view.generate(markdown) {
+            Ok(output) => print!("{}", as_text(output)),
+            Err(e) => eprintln!("could not render the best sellers: {}", e),
+        },
+        (Err(e), _) => eprintln!("invalid query: {}", e),
+        (_, None) => eprintln!("no markdown formatter"),
     }
-}
 
-// New XML output - extension without modification
-pub struct XmlFormatter;
+    let orders = Report::new("January orders").section(
+        Section::new("Orders").table(
+            Table::new(vec![
+                Column::new("Region", ColumnType::Text),
+                Column::new("Product", ColumnType::Text),
+                Column::new("Units", ColumnType::Integer),
+            ])
+            .row(vec![
+                Value::text("North"),
+                Value::text("Product A"),
+                Value::Integer(70),
+            ])
+            .row(vec![
+                Value::text("South"),
+                Value::text("Product C"),
+                Value::Integer(143),
+            ])
+            .row(vec![
+                Value::text("North"),
+                Value::text("Product B"),
+                Value::Integer(98),
+            ])
+            .row(vec![
+                Value::text("South"),
+                Value::text("Product A"),
+                Value::Integer(50),
+            ]),
+        ),
+    );
+    let by_region = Query::new()
+        .sort_by("Product", Order::Ascending)
+        .group_by("Region")
+        .subtotal("Units");
+    println!("\n--- TEXT, BY REGION ---");
+    match orders.query(&by_region) {
+        Ok(view) => match view.generate(&TextFormatter::default()) {
+            Ok(output) => println!("{}", as_text(output)),
+            Err(e) => eprintln!("could not render the regions: {}", e),
+        },
+        Err(e) => eprintln!("invalid query: {}", e),
+    }
 
-impl ReportFormatter for XmlFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String {
-        let mut output = String::from("<report>\n");
-        output.push_str(&format!("  <title>{}</title>\n", title));
-        output.push_str("  <items>\n");
+    // Last month's figures, compared with this month's by any formatter
+    let december = Report::new("Monthly Sales")
+        .section(Section::untitled().items([
+            "Product A: 105 units",
+            "Product B: 98 units",
+            "Product D: 40 units",
+        ]))
+        .section(
+            Section::new("Sales by product").table(
+                Table::new(vec![
+                    Column::new("Product", ColumnType::Text),
+                    Column::new("Units", ColumnType::Integer),
+                    Column::new("Revenue", ColumnType::Decimal),
+                ])
+                .row(vec![
+                    Value::text("Product A"),
+                    Value::Integer(105),
+                    Value::Decimal(2_099.00),
+                ])
+                .row(vec![
+                    Value::text("Product B"),
+                    Value::Integer(98),
+                    Value::Decimal(4_890.20),
+                ])
+                .row(vec![
+                    Value::text("Product D"),
+                    Value::Integer(40),
+                    Value::Decimal(760.00),
+                ]),
+            ),
+        )
+        .section(Section::new("Summary").key_values([
+            ("Period", Value::text("December 2024")),
+            ("Total units", Value::Integer(243)),
+            ("Total revenue", Value::Decimal(7_749.20)),
+            ("Generated on", Value::Date(Date::new(2025, 1, 2))),
+        ]));
+    println!("\n--- TEXT, CHANGES SINCE DECEMBER ---");
+    match report.generate(&DiffFormatter::new(december, TextFormatter::default())) {
+        Ok(output) => println!("{}", as_text(output)),
+        Err(e) => eprintln!("could not render the changes: {}", e),
+    }
 
-        for item in data {
-            output.push_str(&format!("    <item>{}</item>\n", item));
+    // Back from each format: parse(format(report)) gives the report again
+    println!("\n--- ROUND TRIPS ---");
+    let parsers: [&dyn ReportParser; 4] = [&TextParser, &XmlParser, &JsonParser, &CsvParser];
+    for parser in parsers {
+        let Some(formatter) = registry.by_name(parser.name()) else {
+            continue;
+        };
+        let output = match report.generate(formatter) {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("could not render {}: {}", parser.name(), e);
+                continue;
+            }
+        };
+        match parser.parse(&output) {
+            Ok(parsed) if parsed == report => println!("{:<5} identical", parser.name()),
+            Ok(_) => eprintln!("{} read back a different report", parser.name()),
+            Err(e) => eprintln!("could not read {} back: {}", parser.name(), e),
         }
+    }
 
-        output.push_str("  </items>\n</report>");
-        output
+    // The HTML look is chosen when the formatter is built
+    let themes = [
+        ("light", Theme::Light),
+        ("dark", Theme::Dark),
+        ("unstyled", Theme::Unstyled),
+        (
+            "serif",
+            Theme::Custom("body { font-family: Georgia, serif; }".to_string()),
+        ),
+    ];
+    for (name, theme) in themes {
+        let path = std::env::temp_dir().join(format!("monthly_sales.{}.html", name));
+        let written = report
+            .generate(&HtmlFormatter::new(theme))
+            .and_then(|html| std::fs::write(&path, html));
+        if let Err(e) = written {
+            eprintln!("could not write {}: {}", path.display(), e);
+        }
     }
-}
 
-// =========================
-// Usage
-// =========================
+    // Charts too: the numbers of the report as bars or as a line
+    for (name, kind) in [("bar", ChartKind::Bar), ("line", ChartKind::Line)] {
+        let path = std::env::temp_dir().join(format!("monthly_sales.{}.svg", name));
+        let chart = SvgChartFormatter::new(kind);
+        match report
+            .generate(&chart)
+            .and_then(|svg| std::fs::write(&path, svg))
+        {
+            Ok(()) => println!("\n{} chart written to {}", name, path.display()),
+            Err(e) => eprintln!("could not write {}: {}", path.display(), e),
+        }
+    }
 
-fn main() {
-    let report = Report {
-        title: "Monthly Sales".to_string(),
-        data: vec![
-            "Product A: 120 units".to_string(),
-            "Product B: 98 units".to_string(),
-            "Product C: 143 units".to_string(),
-        ],
-    };
+    // ...or streamed straight to stdout
+    if let Some(xml) = registry.lookup("report.xml") {
+        println!("\n--- XML (report.xml) ---");
+        let mut stdout = std::io::stdout().lock();
+        if let Err(e) = report.generate_to(xml, &mut stdout) {
+            eprintln!("could not write XML: {}", e);
+        }
+    }
+
+    // The document and its published schema side by side, ready for
+    // xmllint --noout --schema report.xsd monthly_sales.xml
+    let dir = std::env::temp_dir();
+    let written = std::fs::write(dir.join("report.xsd"), XmlFormatter::SCHEMA).and_then(|()| {
+        report
+            .generate(&XmlFormatter::default())
+            .and_then(|xml| std::fs::write(dir.join("monthly_sales.xml"), xml))
+    });
+    match written {
+        Ok(()) => println!("\nXML and schema written to {}", dir.display()),
+        Err(e) => eprintln!("could not write XML files: {}", e),
+    }
+
+    // PDF is binary, it is streamed to a file page by page
+    if let Some(pdf) = registry.lookup("application/pdf") {
+        let path = std::env::temp_dir().join("monthly_sales.pdf");
+        let written = File::create(&path).and_then(|file| {
+            let mut file = BufWriter::new(file);
+            report.generate_to(pdf, &mut file)
+        });
+        match written {
+            Ok(()) => println!(
+                "\n--- PDF (application/pdf) ---\nwritten to {}",
+                path.display()
+            ),
+            Err(e) => eprintln!("could not write {}: {}", path.display(), e),
+        }
+    }
+
+    // A workbook for the spreadsheet crowd: numbers stay numbers, formulas work on them
+    if let Some(xlsx) = registry.lookup("xlsx") {
+        let path = std::env::temp_dir().join("monthly_sales.xlsx");
+        match report
+            .generate(xlsx)
+            .and_then(|workbook| std::fs::write(&path, workbook))
+        {
+            Ok(()) => println!(
+                "\n--- XLSX ({}) ---\nwritten to {}",
+                xlsx.mime_type(),
+                path.display()
+            ),
+            Err(e) => eprintln!("could not write {}: {}", path.display(), e),
+        }
+    }
+
+    // Text and HTML bodies in one message, PDF and CSV attached: ready for sendmail -t
+    let email = EmailFormatter::new("Reports <reports@example.com>")
+        .to("Sales managers <sales-managers@example.com>")
+        .subject("Monthly Sales, January 2025")
+        .attach(PdfFormatter::default())
+        .attach(CsvFormatter::default());
+    let path = std::env::temp_dir().join("monthly_sales.eml");
+    match report
+        .generate(&email)
+        .and_then(|message| std::fs::write(&path, message))
+    {
+        Ok(()) => println!(
+            "\n--- EMAIL (message/rfc822) ---\nwritten to {}",
+            path.display()
+        ),
+        Err(e) => eprintln!("could not write {}: {}", path.display(), e),
+    }
+
+    // Same formats, other settings: report.conf turns the CSV into what a European Excel expects
+    println!("\n--- CSV, CONFIGURED (report.conf) ---");
+    let configured = config::read(concat!(env!("CARGO_MANIFEST_DIR"), "/report.conf"))
+        .and_then(FormatterRegistry::from_config);
+    match configured {
+        Ok(registry) => {
+            if let Some(csv) = registry.by_name("csv") {
+                match report.generate(csv) {
+                    Ok(output) => println!("{}", as_text(output).trim_end()),
+                    Err(e) => eprintln!("could not render the configured CSV: {}", e),
+                }
+            }
+        }
+        Err(e) => eprintln!("report.conf: {}", e),
+    }
+
+    // The nightly run: every report in the usual formats, four jobs at a time
+    let nightly = std::env::temp_dir().join("nightly_reports");
+    let batch = ["text", "html", "pdf", "xml"]
+        .into_iter()
+        .filter_map(|name| registry.by_name(name))
+        .fold(
+            BatchRenderer::new(&nightly).threads(4),
+            BatchRenderer::format,
+        );
+    let summary = batch.render(&[report, orders]);
+    println!(
+        "\n--- BATCH ---\n{} files written to {}",
+        summary.written.len(),
+        nightly.display()
+    );
+    for failure in &summary.failures {
+        eprintln!("failed: {}", failure);
+    }
 
-    println!("\n--- TEXT ---\n{}", report.generate(&TextFormatter));
-    println!("\n--- HTML ---\n{}", report.generate(&HtmlFormatter));
-    println!("\n--- PDF ---\n{}", report.generate(&PdfFormatter));
-    println!("\n--- XML ---\n{}", report.generate(&XmlFormatter));
+    // Every morning at 06:00, whatever the data source says then. The history file remembers
+    // the runs, so after a 2-day outage the missed mornings are rendered too.
+    let scheduled = std::env::temp_dir().join("scheduled_reports");
+    let _ = std::fs::remove_dir_all(&scheduled);
+    let history = match RunHistory::open(scheduled.join("history.tsv")) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("could not read the run history: {}", e);
+            return;
+        }
+    };
+    let mut scheduler = Scheduler::new(
+        FormatterRegistry::with_builtin_formatters(),
+        &scheduled,
+        history,
+    );
+    scheduler.register_source("sales", |at: DateTime| -> Result<Report, Box<dyn Error>> {
+        Ok(Report::new(format!("Sales on {}", at.date))
+            .section(Section::untitled().key_values([("As of", Value::Date(at.date))])))
+    });
+    let jobs = [
+        Job::new("daily-sales", "0 6 * * *", "sales", "html").map(|job| job.catch_up(CatchUp::All)),
+        Job::new("office-hours", "*/30 8-18 * * mon-fri", "sales", "text")
+            .map(|job| job.catch_up(CatchUp::Skip)),
+    ];
+    for job in jobs {
+        let added = job
+            .map_err(|e| e.to_string())
+            .and_then(|job| scheduler.add_job(job).map_err(|e| e.to_string()));
+        if let Err(e) = added {
+            eprintln!("job not scheduled: {}", e);
+        }
+    }
+    println!("\n--- SCHEDULE ---");
+    for now in ["2025-02-03 06:00", "2025-02-03 08:30", "2025-02-05 08:31"] {
+        let now: DateTime = now.parse().expect("valid time");
+        match scheduler.tick(now) {
+            Ok(records) => {
+                for record in records {
+                    println!("{}", record);
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    println!(
+        "{} runs of daily-sales recorded",
+        scheduler.history().runs("daily-sales").count()
+    );
 }
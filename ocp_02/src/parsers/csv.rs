@@ -0,0 +1,351 @@
+// src/parsers/csv.rs
+// Reads the long CSV layout written by CsvFormatter::default() (see formatters/csv.rs)
+// Records are grouped back by section and block numbers, row 0 records declare table columns.
+// A quoted empty heading is an empty heading, an unquoted one no heading.
+// parse_table() reads any other CSV file, a header and rows (a spreadsheet export...), as one table.
+
+use super::{ParseError, ReportParser, column_type, typed_value, utf8};
+use crate::formatters::csv::HEADER;
+use crate::report::{Block, Column, ColumnType, Report, Section, Table, Value};
+
+pub struct CsvParser;
+
+impl ReportParser for CsvParser {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Report, ParseError> {
+        let records = read_records(utf8(input)?)?;
+        let mut records = records.into_iter();
+        match records.next() {
+            Some(record) if record.fields == HEADER => {}
+            _ => return Err(ParseError::new(1, "missing or unexpected header")),
+        }
+
+        let mut report: Option<Report> = None;
+        for Record {
+            line,
+            fields,
+            quoted,
+        } in records
+        {
+            let err = |message: &str| ParseError::new(line, message);
+            let [
+                title,
+                section,
+                heading,
+                block,
+                kind,
+                row,
+                key,
+                kind_name,
+                value,
+            ] = <[String; 9]>::try_from(fields)
+                .map_err(|f| err(&format!("expected 9 fields, found {}", f.len())))?;
+
+            let report = report.get_or_insert_with(|| Report::new(title.clone()));
+            if title != report.title {
+                return Err(err("all records must belong to the same report"));
+            }
+            if section.is_empty() {
+                continue;
+            }
+
+            // Sections and blocks are numbered in order: the next number opens a new one
+            let section_number = position(&section).ok_or_else(|| err("invalid section"))?;
+            if section_number == report.sections.len() + 1 {
+                report.sections.push(if heading.is_empty() && !quoted[2] {
+                    Section::untitled()
+                } else {
+                    Section::new(heading)
+                });
+            } else if section_number != report.sections.len() {
+                return Err(err("sections must be numbered in order"));
+            }
+            let section = report
+                .sections
+                .last_mut()
+                .expect("a section was just pushed");
+            if block.is_empty() {
+                continue;
+            }
+
+            let block_number = position(&block).ok_or_else(|| err("invalid block"))?;
+            if block_number == section.blocks.len() + 1 {
+                section.blocks.push(match kind.as_str() {
+                    "items" => Block::Items(Vec::new()),
+                    "table" => Block::Table(Table::new(Vec::new())),
+                    "key_values" => Block::KeyValues(Vec::new()),
+                    _ => return Err(err(&format!("unknown block kind \"{}\"", kind))),
+                });
+            } else if block_number != section.blocks.len() {
+                return Err(err("blocks must be numbered in order"));
+            }
+            if row.is_empty() {
+                continue;
+            }
+
+            let block = section.blocks.last_mut().expect("a block was just pushed");
+            if let Block::Table(table) = block
+                && row == "0"
+            {
+                let kind = column_type(&kind_name).ok_or_else(|| err("column without type"))?;
+                if !table.rows.is_empty() {
+                    return Err(err("columns must be declared before the rows"));
+                }
+                table.columns.push(Column::new(key, kind));
+                continue;
+            }
+
+            let value = if kind_name.is_empty() {
+                Value::Empty
+            } else {
+                let kind = column_type(&kind_name)
+                    .ok_or_else(|| err(&format!("unknown type \"{}\"", kind_name)))?;
+                typed_value(kind, &value)
+                    .ok_or_else(|| err(&format!("invalid {} \"{}\"", kind_name, value)))?
+            };
+            match block {
+                Block::Items(items) => match value {
+                    Value::Text(text) => items.push(text),
+                    _ => return Err(err("items are text")),
+                },
+                Block::KeyValues(pairs) => pairs.push((key, value)),
+                Block::Table(table) => {
+                    let row_number = position(&row).ok_or_else(|| err("invalid row"))?;
+                    if row_number == table.rows.len() + 1 {
+                        table.rows.push(Vec::with_capacity(table.columns.len()));
+                    } else if row_number != table.rows.len() {
+                        return Err(err("rows must be numbered in order"));
+                    }
+                    let cells = table.rows.last_mut().expect("a row was just pushed");
+                    match table.columns.get(cells.len()) {
+                        Some(column) if column.name == key => cells.push(value),
+                        _ => return Err(err(&format!("unexpected column \"{}\"", key))),
+                    }
+                }
+            }
+        }
+
+        let report = report.ok_or_else(|| ParseError::new(2, "no records"))?;
+        for section in &report.sections {
+            for block in &section.blocks {
+                if let Block::Table(table) = block
+                    && table.rows.iter().any(|r| r.len() != table.columns.len())
+                {
+                    return Err(ParseError::new(0, "a table row is missing cells"));
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+// The first record names the columns. A column is typed by what all its non-empty cells
+// have in common: integer, then decimal, then date (YYYY-MM-DD), text otherwise.
+pub fn parse_table(title: &str, input: &[u8]) -> Result<Report, ParseError> {
+    let mut records = read_records(utf8(input)?)?.into_iter();
+    let names = records
+        .next()
+        .ok_or_else(|| ParseError::new(1, "missing header"))?
+        .fields;
+    let mut rows = Vec::new();
+    for Record { line, fields, .. } in records {
+        // A blank line, often the last one
+        if fields.len() == 1 && fields[0].is_empty() {
+            continue;
+        }
+        if fields.len() != names.len() {
+            let message = format!("expected {} fields, found {}", names.len(), fields.len());
+            return Err(ParseError::new(line, message));
+        }
+        rows.push(fields);
+    }
+
+    let kinds: Vec<ColumnType> = (0..names.len())
+        .map(|i| {
+            let cells = || rows.iter().map(|r| r[i].as_str()).filter(|c| !c.is_empty());
+            [ColumnType::Integer, ColumnType::Decimal, ColumnType::Date]
+                .into_iter()
+                .find(|&kind| cells().all(|cell| typed_value(kind, cell).is_some()))
+                .filter(|_| cells().next().is_some())
+                .unwrap_or(ColumnType::Text)
+        })
+        .collect();
+    let columns = names
+        .into_iter()
+        .zip(&kinds)
+        .map(|(name, &kind)| Column::new(name, kind))
+        .collect();
+    let table = rows.into_iter().fold(Table::new(columns), |table, row| {
+        let cells = row
+            .iter()
+            .zip(&kinds)
+            .map(|(cell, &kind)| match cell.as_str() {
+                "" => Value::Empty,
+                cell => typed_value(kind, cell).expect("the column type fits every cell"),
+            })
+            .collect();
+        table.row(cells)
+    });
+    Ok(Report::new(title).section(Section::untitled().table(table)))
+}
+
+// 1-based position
+fn position(field: &str) -> Option<usize> {
+    field.parse().ok().filter(|&n| n > 0)
+}
+
+// A record with the line it starts on, and which of its fields were quoted
+struct Record {
+    line: usize,
+    fields: Vec<String>,
+    quoted: Vec<bool>,
+}
+
+// RFC 4180 records. Accepts CRLF or LF line ends.
+fn read_records(input: &str) -> Result<Vec<Record>, ParseError> {
+    let mut records = Vec::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        let mut quoted = Vec::new();
+        let mut field = String::new();
+        let mut is_quoted = false;
+        loop {
+            match chars.next() {
+                Some('"') if field.is_empty() && !is_quoted => {
+                    is_quoted = true;
+                    loop {
+                        match chars.next() {
+                            Some('"') if chars.peek() == Some(&'"') => {
+                                chars.next();
+                                field.push('"');
+                            }
+                            Some('"') => break,
+                            Some(c) => {
+                                line += usize::from(c == '\n');
+                                field.push(c);
+                            }
+                            None => {
+                                return Err(ParseError::new(start, "unterminated quoted field"));
+                            }
+                        }
+                    }
+                }
+                Some(',') => {
+                    fields.push(std::mem::take(&mut field));
+                    quoted.push(std::mem::take(&mut is_quoted));
+                }
+                Some('\r') if chars.peek() == Some(&'\n') => {}
+                Some('\n') | None => {
+                    line += 1;
+                    fields.push(field);
+                    quoted.push(is_quoted);
+                    break;
+                }
+                Some(c) => field.push(c),
+            }
+        }
+        records.push(Record {
+            line: start,
+            fields,
+            quoted,
+        });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::ReportFormatter;
+    use crate::formatters::csv::CsvFormatter;
+    use crate::report::{ColumnType, Date};
+
+    #[test]
+    fn round_trip() {
+        let report = Report::new("Sales, \"2025\"")
+            .section(Section::untitled().items(["a,b", "two\nlines", ""]))
+            .section(Section::new("Empty"))
+            .section(Section::new("").items(["x"]))
+            .section(Section::untitled())
+            .section(
+                Section::new("Table")
+                    .table(
+                        Table::new(vec![
+                            Column::new("n", ColumnType::Integer),
+                            Column::new("x", ColumnType::Decimal),
+                            Column::new("d", ColumnType::Date),
+                        ])
+                        .row(vec![
+                            Value::Integer(-3),
+                            Value::Decimal(0.1 + 0.2),
+                            Value::Date(Date::new(2025, 1, 30)),
+                        ])
+                        .row(vec![
+                            Value::Empty,
+                            Value::text("n/a"),
+                            Value::Empty,
+                        ]),
+                    )
+                    .table(Table::new(vec![Column::new("none", ColumnType::Text)]))
+                    .key_values([("k", Value::text("")), ("e", Value::Empty)]),
+            );
+
+        let csv = CsvFormatter::default().format(&report).unwrap();
+
+        assert_eq!(CsvParser.parse(&csv).unwrap(), report);
+        assert_eq!(
+            CsvParser
+                .parse(&CsvFormatter::default().format(&Report::new("T")).unwrap())
+                .unwrap(),
+            Report::new("T")
+        );
+    }
+
+    #[test]
+    fn errors_point_to_the_record() {
+        let csv = "report,section,heading,block,kind,row,key,type,value\r\n\
+                   T,1,,1,table,1,n,integer,x\r\n";
+
+        let err = CsvParser.parse(csv.as_bytes()).unwrap_err();
+
+        assert_eq!(err.line, 2);
+
+        let february = "report,section,heading,block,kind,row,key,type,value\r\n\
+                        T,1,,1,table,1,d,date,2025-02-29\r\n";
+        assert_eq!(CsvParser.parse(february.as_bytes()).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn plain_tables_get_typed_columns() {
+        let csv = "Product,Units,Revenue,Last order,Note\n\
+                   A,120,2398.80,2025-01-30,\n\
+                   \"B, large\",98,4890,,2nd\n";
+
+        let report = parse_table("Sales", csv.as_bytes()).unwrap();
+
+        let Block::Table(table) = &report.sections[0].blocks[0] else {
+            panic!("expected a table");
+        };
+        let kinds: Vec<_> = table.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ColumnType::Text,
+                ColumnType::Integer,
+                ColumnType::Decimal,
+                ColumnType::Date,
+                ColumnType::Text
+            ]
+        );
+        assert_eq!(table.rows[1][0], Value::text("B, large"));
+        assert_eq!(table.rows[1][2], Value::Decimal(4890.0));
+        assert_eq!(table.rows[1][3], Value::Empty);
+        assert_eq!(parse_table("T", b"a,b\n1\n").unwrap_err().line, 2);
+    }
+}
@@ -0,0 +1,415 @@
+// src/parsers/json.rs
+// Reads the document written by JsonFormatter (see formatters/json.rs)
+// Any valid JSON with that shape is accepted, whatever its layout.
+// Table cells have no type of their own: strings are read with the column type (text or date),
+// numbers with a dot or an exponent are decimals, the others integers. A cell tagged with its
+// type ({ "type": ..., "value": ... }) is read like a key/value.
+
+use super::{ParseError, ReportParser, column_type, parse_date, typed_value, utf8};
+use crate::report::{Block, Column, ColumnType, Report, Section, Table, Value};
+
+pub struct JsonParser;
+
+impl ReportParser for JsonParser {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Report, ParseError> {
+        let mut reader = Reader {
+            chars: utf8(input)?.chars().peekable(),
+            line: 1,
+        };
+        let document = reader.document()?;
+        // The document is valid JSON from here, shape errors can't point to a line
+        let err = |message: &str| ParseError::new(0, message);
+
+        let title = document.get("title").and_then(Json::as_str);
+        let mut report = Report::new(title.ok_or_else(|| err("missing title"))?);
+        for section in document
+            .get("sections")
+            .and_then(Json::as_array)
+            .ok_or_else(|| err("missing sections"))?
+        {
+            let mut parsed = match section.get("heading") {
+                Some(Json::String(heading)) => Section::new(heading.as_str()),
+                Some(Json::Null) | None => Section::untitled(),
+                Some(_) => return Err(err("heading must be a string or null")),
+            };
+            let blocks = section
+                .get("blocks")
+                .and_then(Json::as_array)
+                .ok_or_else(|| err("missing blocks"))?;
+            for block in blocks {
+                parsed.blocks.push(parse_block(block).map_err(|m| err(&m))?);
+            }
+            report.sections.push(parsed);
+        }
+        Ok(report)
+    }
+}
+
+fn parse_block(block: &Json) -> Result<Block, String> {
+    match block.get("type").and_then(Json::as_str) {
+        Some("items") => {
+            let items = block
+                .get("items")
+                .and_then(Json::as_array)
+                .ok_or("missing items")?;
+            let items = items
+                .iter()
+                .map(|i| i.as_str().map(str::to_string).ok_or("items are strings"))
+                .collect::<Result<_, _>>()?;
+            Ok(Block::Items(items))
+        }
+        Some("table") => {
+            let mut table = Table::new(Vec::new());
+            for column in block
+                .get("columns")
+                .and_then(Json::as_array)
+                .ok_or("missing columns")?
+            {
+                let name = column
+                    .get("name")
+                    .and_then(Json::as_str)
+                    .ok_or("column without name")?;
+                let kind = column
+                    .get("type")
+                    .and_then(Json::as_str)
+                    .and_then(column_type);
+                table
+                    .columns
+                    .push(Column::new(name, kind.ok_or("column without valid type")?));
+            }
+            for row in block
+                .get("rows")
+                .and_then(Json::as_array)
+                .ok_or("missing rows")?
+            {
+                let cells = row.as_array().ok_or("rows are arrays")?;
+                if cells.len() != table.columns.len() {
+                    return Err("a table row must have one cell per column".to_string());
+                }
+                let cells = table
+                    .columns
+                    .iter()
+                    .zip(cells)
+                    .map(|(column, cell)| cell_value(column.kind, cell))
+                    .collect::<Result<_, _>>()?;
+                table.rows.push(cells);
+            }
+            Ok(Block::Table(table))
+        }
+        Some("key_values") => {
+            let mut pairs = Vec::new();
+            for entry in block
+                .get("entries")
+                .and_then(Json::as_array)
+                .ok_or("missing entries")?
+            {
+                let key = entry
+                    .get("key")
+                    .and_then(Json::as_str)
+                    .ok_or("entry without key")?;
+                let value = tagged_value(entry).map_err(|m| format!("{} for \"{}\"", m, key))?;
+                pairs.push((key.to_string(), value));
+            }
+            Ok(Block::KeyValues(pairs))
+        }
+        _ => Err("unknown block type".to_string()),
+    }
+}
+
+// { "type": ..., "value": ... }, the value a string or a number written in that type
+fn tagged_value(tagged: &Json) -> Result<Value, String> {
+    match (tagged.get("type"), tagged.get("value")) {
+        (Some(Json::Null) | None, _) | (_, Some(Json::Null)) => Ok(Value::Empty),
+        (Some(Json::String(kind)), Some(Json::String(text) | Json::Number(text))) => {
+            let kind = column_type(kind).ok_or_else(|| format!("unknown type \"{}\"", kind))?;
+            typed_value(kind, text).ok_or_else(|| format!("invalid {} \"{}\"", kind.name(), text))
+        }
+        _ => Err("invalid value".to_string()),
+    }
+}
+
+fn cell_value(kind: ColumnType, cell: &Json) -> Result<Value, String> {
+    match cell {
+        Json::Null => Ok(Value::Empty),
+        Json::Object(_) => tagged_value(cell),
+        Json::String(s) if kind == ColumnType::Date => {
+            Ok(parse_date(s).map_or_else(|| Value::text(s.as_str()), Value::Date))
+        }
+        Json::String(s) => Ok(Value::text(s.as_str())),
+        Json::Number(n) if n.contains(['.', 'e', 'E']) => n
+            .parse()
+            .map(Value::Decimal)
+            .map_err(|_| format!("invalid number {}", n)),
+        Json::Number(n) => n
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| format!("invalid integer {}", n)),
+        _ => Err("cells are strings, numbers or null".to_string()),
+    }
+}
+
+// =========================
+// A small JSON reader (RFC 8259)
+// =========================
+
+enum Json {
+    Null,
+    // true or false: valid JSON, never part of a report
+    Bool,
+    // Kept as written, so it can be read as an integer or a decimal without loss
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl Reader<'_> {
+    fn document(&mut self) -> Result<Json, ParseError> {
+        let value = self.value()?;
+        self.skip_whitespace();
+        match self.chars.next() {
+            None => Ok(value),
+            Some(c) => Err(self.error(&format!("unexpected '{}' after the document", c))),
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                '\n' => self.line += 1,
+                ' ' | '\t' | '\r' => {}
+                _ => break,
+            }
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(self.error(&format!("expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(&format!("expected '{}', found the end", expected))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, ParseError> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('{') => {
+                self.chars.next();
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.chars.peek() == Some(&'}') {
+                    self.chars.next();
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.expect('"')?;
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.chars.next() {
+                        Some(',') => self.skip_whitespace(),
+                        Some('}') => return Ok(Json::Object(fields)),
+                        _ => return Err(self.error("expected ',' or '}'")),
+                    }
+                }
+            }
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.chars.peek() == Some(&']') {
+                    self.chars.next();
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.chars.next() {
+                        Some(',') => {}
+                        Some(']') => return Ok(Json::Array(items)),
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                }
+            }
+            Some('"') => {
+                self.chars.next();
+                self.string().map(Json::String)
+            }
+            Some('-' | '0'..='9') => {
+                let mut number = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E') {
+                        break;
+                    }
+                    number.push(c);
+                    self.chars.next();
+                }
+                match number.parse::<f64>() {
+                    Ok(_) => Ok(Json::Number(number)),
+                    Err(_) => Err(self.error(&format!("invalid number {}", number))),
+                }
+            }
+            Some(_) => {
+                let mut word = String::new();
+                while let Some(&c) = self.chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                    word.push(c);
+                    self.chars.next();
+                }
+                match word.as_str() {
+                    "null" => Ok(Json::Null),
+                    "true" | "false" => Ok(Json::Bool),
+                    _ => Err(self.error("expected a value")),
+                }
+            }
+            None => Err(self.error("unexpected end of document")),
+        }
+    }
+
+    // After the opening quote
+    fn string(&mut self) -> Result<String, ParseError> {
+        let mut out = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(out),
+                Some('\\') => match self.chars.next() {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('/') => out.push('/'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('u') => {
+                        let high = self.hex4()?;
+                        // Characters outside the BMP come as a surrogate pair
+                        let code = if (0xD800..0xDC00).contains(&high) {
+                            if self.chars.next() != Some('\\') || self.chars.next() != Some('u') {
+                                return Err(self.error("unpaired surrogate"));
+                            }
+                            let low = self.hex4()?;
+                            0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+                        } else {
+                            high
+                        };
+                        out.push(
+                            char::from_u32(code).ok_or_else(|| self.error("invalid \\u escape"))?,
+                        );
+                    }
+                    _ => return Err(self.error("invalid escape")),
+                },
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in a string"));
+                }
+                Some(c) => out.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let digits: String = (0..4).filter_map(|_| self.chars.next()).collect();
+        u32::from_str_radix(&digits, 16).map_err(|_| self.error("invalid \\u escape"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::ReportFormatter;
+    use crate::formatters::json::JsonFormatter;
+    use crate::report::Date;
+
+    #[test]
+    fn round_trip() {
+        let report = Report::new("Say \"hi\" \u{1F600}")
+            .section(Section::untitled().items(["tab\there", "\u{1}"]))
+            .section(Section::new("Empty"))
+            .section(
+                Section::new("Table")
+                    .table(
+                        Table::new(vec![
+                            Column::new("n", ColumnType::Integer),
+                            Column::new("x", ColumnType::Decimal),
+                            Column::new("d", ColumnType::Date),
+                        ])
+                        .row(vec![
+                            Value::Integer(i64::MIN),
+                            Value::Decimal(1e-7),
+                            Value::Date(Date::new(2025, 1, 30)),
+                        ])
+                        .row(vec![Value::Empty, Value::Decimal(3.0), Value::Empty])
+                        .row(vec![
+                            Value::Decimal(2.0),
+                            Value::Integer(3),
+                            Value::text("2025-01-30"),
+                        ])
+                        .row(vec![
+                            Value::text("12"),
+                            Value::Decimal(f64::NEG_INFINITY),
+                            Value::Integer(20250130),
+                        ]),
+                    )
+                    .key_values([
+                        ("t", Value::text("")),
+                        ("inf", Value::Decimal(f64::INFINITY)),
+                        ("x", Value::Decimal(0.1 + 0.2)),
+                        ("d", Value::Date(Date::new(1999, 12, 31))),
+                        ("e", Value::Empty),
+                    ]),
+            );
+
+        let json = JsonFormatter::default().format(&report).unwrap();
+
+        assert_eq!(JsonParser.parse(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn syntax_errors_carry_the_line() {
+        let err = JsonParser
+            .parse(b"{\n  \"title\": \"T\",\n  \"sections\": [,]\n}")
+            .unwrap_err();
+
+        assert_eq!(err.line, 3);
+    }
+}
@@ -0,0 +1,149 @@
+// src/parsers/mod.rs
+// The way back: rebuild a Report from the output of a formatter (ours or another system's)
+// One parser per module, mirroring formatters/. Parse with one, render with any formatter.
+//
+// Round trip: parse(format(report)) == report, with the formatter's default settings
+// - xml, json, csv: always
+// - text: always, but TextFormatter refuses what plain text can't show (a table without
+//   columns, a row without any value, a value that would read as its column's type)
+// Other settings (a CSV delimiter, a date style...) write for people, not for the parsers.
+// A NaN decimal reads back as NaN, which is never equal to itself.
+
+pub mod csv;
+pub mod json;
+pub mod text;
+pub mod xml;
+
+use std::fmt;
+
+use crate::report::{ColumnType, Date, Report, Value};
+
+#[derive(Debug)]
+pub struct ParseError {
+    // 1-based, 0 when the position is unknown
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "line {}: {}", line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub trait ReportParser {
+    // Same name as the formatter whose output it reads
+    fn name(&self) -> &str;
+    fn parse(&self, input: &[u8]) -> Result<Report, ParseError>;
+}
+
+// Helpers shared by the parsers
+
+fn utf8(input: &[u8]) -> Result<&str, ParseError> {
+    std::str::from_utf8(input).map_err(|e| {
+        let line = input[..e.valid_up_to()]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count()
+            + 1;
+        ParseError::new(line, "invalid UTF-8")
+    })
+}
+
+// "text", "integer"... as written by ColumnType::name()
+fn column_type(name: &str) -> Option<ColumnType> {
+    [
+        ColumnType::Text,
+        ColumnType::Integer,
+        ColumnType::Decimal,
+        ColumnType::Date,
+    ]
+    .into_iter()
+    .find(|kind| kind.name() == name)
+}
+
+// A value from its textual form, as written by the formatters
+fn typed_value(kind: ColumnType, text: &str) -> Option<Value> {
+    match kind {
+        ColumnType::Text => Some(Value::text(text)),
+        ColumnType::Integer => text.parse().ok().map(Value::Integer),
+        ColumnType::Decimal => text.parse().ok().map(Value::Decimal),
+        ColumnType::Date => parse_date(text).map(Value::Date),
+    }
+}
+
+// YYYY-MM-DD
+fn parse_date(text: &str) -> Option<Date> {
+    let mut parts = text.splitn(3, '-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    Date::checked(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::ReportFormatter;
+    use crate::formatters::csv::CsvFormatter;
+    use crate::formatters::json::JsonFormatter;
+    use crate::formatters::text::TextFormatter;
+    use crate::formatters::xml::XmlFormatter;
+    use crate::report::{Column, Section, Table};
+
+    #[test]
+    fn every_parser_reads_back_its_formatter() {
+        let report = Report::new("Say \"hi\"\nagain")
+            .section(Section::untitled().items(["", "- x"]).items(["y"]))
+            .section(Section::new(""))
+            .section(Section::untitled().items(Vec::<String>::new()))
+            .section(
+                Section::new("Table")
+                    .table(
+                        Table::new(vec![
+                            Column::new("n", ColumnType::Integer),
+                            Column::new("x", ColumnType::Decimal),
+                            Column::new("d", ColumnType::Date),
+                        ])
+                        .row(vec![
+                            Value::Integer(-3),
+                            Value::Decimal(f64::INFINITY),
+                            Value::Date(Date::new(2025, 1, 30)),
+                        ])
+                        .row(vec![
+                            Value::text("12"),
+                            Value::Decimal(0.1 + 0.2),
+                            Value::text("2025-01-31"),
+                        ]),
+                    )
+                    .key_values([("a : b", Value::text("")), ("e", Value::Empty)])
+                    .key_values(Vec::<(String, Value)>::new()),
+            );
+        let pairs: [(&dyn ReportFormatter, &dyn ReportParser); 4] = [
+            (&XmlFormatter::default(), &xml::XmlParser),
+            (&JsonFormatter::default(), &json::JsonParser),
+            (&CsvFormatter::default(), &csv::CsvParser),
+            (&TextFormatter::default(), &text::TextParser),
+        ];
+
+        for (formatter, parser) in pairs {
+            let output = formatter.format(&report).unwrap();
+            assert_eq!(parser.parse(&output).unwrap(), report, "{}", parser.name());
+        }
+    }
+}
@@ -0,0 +1,429 @@
+// src/parsers/text.rs
+// Reads the plain text written by TextFormatter (see formatters/text.rs)
+// parse(format(report)) gives the report back:
+// - title, headings, items and keys come back exactly, quoted ones unescaped
+// - consecutive items, or key/values, form one block; a blank line ends it
+// - a table is recognized by its dashed rule, which also gives the type of each column
+// - cells and "key : value" values are typed as cell_value() and read_value() say
+// - "(no items)", "(no entries)" and a "---" line are an empty block and a section without heading
+// Anything else is an error.
+
+use super::{ParseError, ReportParser, parse_date, typed_value, utf8};
+use crate::formatters::text::{NO_ENTRIES, NO_ITEMS};
+use crate::report::{Block, Column, ColumnType, Report, Section, Table, Value};
+
+pub struct TextParser;
+
+impl ReportParser for TextParser {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Report, ParseError> {
+        let lines: Vec<&str> = utf8(input)?.lines().collect();
+        let title = lines
+            .first()
+            .and_then(|l| l.strip_prefix("=== ")?.strip_suffix(" ==="))
+            .ok_or_else(|| ParseError::new(1, "expected \"=== title ===\""))?;
+        let mut report = Report::new(read_text(title));
+
+        // Whether the next item or key/value adds to the block of the previous line
+        let mut open = false;
+        let mut i = 1;
+        while i < lines.len() {
+            let line = lines[i];
+            let number = i + 1;
+            // A blank line comes before a heading, or ends a block
+            if line.is_empty() {
+                let next = lines.get(i + 1).copied();
+                match next.and_then(|l| l.strip_prefix("--- ")?.strip_suffix(" ---")) {
+                    Some(heading) => {
+                        report.sections.push(Section::new(read_text(heading)));
+                        i += 2;
+                    }
+                    None if next == Some("---") => {
+                        report.sections.push(Section::untitled());
+                        i += 2;
+                    }
+                    None if report.sections.last().is_some_and(|s| !s.blocks.is_empty()) => i += 1,
+                    None => return Err(ParseError::new(number, "blank line outside a heading")),
+                }
+                open = false;
+                continue;
+            }
+            if report.sections.is_empty() {
+                report.sections.push(Section::untitled());
+            }
+            let blocks = &mut report.sections.last_mut().expect("a section exists").blocks;
+
+            if let Some(item) = line.strip_prefix("- ") {
+                let item = read_text(item);
+                match blocks.last_mut() {
+                    Some(Block::Items(items)) if open => items.push(item),
+                    _ => blocks.push(Block::Items(vec![item])),
+                }
+                open = true;
+                i += 1;
+            } else if let Some(spans) = lines.get(i + 1).and_then(|l| rule_spans(l)) {
+                let (table, consumed) = parse_table(&lines[i..], &spans);
+                blocks.push(Block::Table(table));
+                open = false;
+                i += consumed;
+            } else if line == NO_ITEMS {
+                blocks.push(Block::Items(Vec::new()));
+                open = false;
+                i += 1;
+            } else if line == NO_ENTRIES {
+                blocks.push(Block::KeyValues(Vec::new()));
+                open = false;
+                i += 1;
+            } else if let Some((key, value)) = split_key_value(line) {
+                let pair = (key, read_value(value));
+                match blocks.last_mut() {
+                    Some(Block::KeyValues(pairs)) if open => pairs.push(pair),
+                    _ => blocks.push(Block::KeyValues(vec![pair])),
+                }
+                open = true;
+                i += 1;
+            } else {
+                return Err(ParseError::new(
+                    number,
+                    format!("unrecognized line \"{}\"", line),
+                ));
+            }
+        }
+        Ok(report)
+    }
+}
+
+// A key/value value as TextFormatter writes it: 120 is an integer, 7749.20 a decimal,
+// 2025-01-02 a date, "..." a quoted text (see unquote()), anything else text
+pub(crate) fn read_value(text: &str) -> Value {
+    if text.is_empty() {
+        return Value::Empty;
+    }
+    if let Some(text) = unquote(text) {
+        return Value::Text(text);
+    }
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let unsigned = text.strip_prefix('-').unwrap_or(text);
+    if digits(unsigned)
+        && let Ok(n) = text.parse()
+    {
+        return Value::Integer(n);
+    }
+    if let Some((whole, fraction)) = unsigned.split_once('.')
+        && digits(whole)
+        && digits(fraction)
+        && let Ok(x) = text.parse()
+    {
+        return Value::Decimal(x);
+    }
+    match parse_date(text) {
+        Some(date) => Value::Date(date),
+        None => Value::text(text),
+    }
+}
+
+// A title, heading, item or key: quoted if TextFormatter had to, as is otherwise
+fn read_text(text: &str) -> String {
+    unquote(text).unwrap_or_else(|| text.to_string())
+}
+
+// "key : value", the key padded with spaces, or quoted if it had to be
+fn split_key_value(line: &str) -> Option<(String, &str)> {
+    if line.starts_with('"') {
+        let end = closing_quote(line)?;
+        let value = line[end + 1..].trim_start_matches(' ').strip_prefix(": ")?;
+        return Some((unquote(&line[..=end])?, value));
+    }
+    let (key, value) = line.split_once(" : ")?;
+    Some((key.trim_end().to_string(), value))
+}
+
+// Byte index of the quote that ends a quoted text starting the line
+fn closing_quote(line: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in line.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// The text between double quotes, with \" \\ \n and \r escapes
+pub(crate) fn unquote(text: &str) -> Option<String> {
+    let quoted = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                c @ ('\\' | '"') => unescaped.push(c),
+                'n' => unescaped.push('\n'),
+                'r' => unescaped.push('\r'),
+                _ => return None,
+            },
+            '"' => return None,
+            c => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+// A cell of a column of this type: the column type first, then as read_value() says
+pub(crate) fn cell_value(kind: ColumnType, cell: &str) -> Value {
+    match kind {
+        ColumnType::Text => read_value(cell),
+        kind => typed_value(kind, cell).unwrap_or_else(|| read_value(cell)),
+    }
+}
+
+// Columns of a rule line like "-------  ----#  ---", with the type their last character gives
+fn rule_spans(line: &str) -> Option<Vec<(usize, usize, ColumnType)>> {
+    if line.is_empty()
+        || !line
+            .chars()
+            .all(|c| matches!(c, '-' | '#' | '.' | '/' | ' '))
+    {
+        return None;
+    }
+    let mut spans = Vec::new();
+    let mut start = None;
+    let mut last = '-';
+    for (i, c) in line.chars().chain([' ']).enumerate() {
+        match (c, start) {
+            (' ', Some(s)) => {
+                let kind = match last {
+                    '#' => ColumnType::Integer,
+                    '.' => ColumnType::Decimal,
+                    '/' => ColumnType::Date,
+                    _ => ColumnType::Text,
+                };
+                spans.push((s, i, kind));
+                start = None;
+            }
+            (' ', None) => {}
+            // Only the last character of a column may be a type
+            (_, Some(_)) if last != '-' => return None,
+            (_, None) => start = Some(i),
+            _ => {}
+        }
+        last = c;
+    }
+    Some(spans)
+}
+
+// Header, rule, then rows until a blank line, an item or the next table
+fn parse_table(lines: &[&str], spans: &[(usize, usize, ColumnType)]) -> (Table, usize) {
+    let cut = |line: &str| -> Vec<String> {
+        let chars: Vec<char> = line.chars().collect();
+        spans
+            .iter()
+            .map(|&(start, end, _)| {
+                let end = end.min(chars.len());
+                let start = start.min(end);
+                chars[start..end]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            })
+            .collect()
+    };
+    let columns = cut(lines[0])
+        .into_iter()
+        .zip(spans)
+        .map(|(name, &(_, _, kind))| Column::new(read_text(&name), kind))
+        .collect();
+    let mut table = Table::new(columns);
+    let mut consumed = 2;
+    while let Some(line) = lines.get(consumed) {
+        let next_is_rule = lines
+            .get(consumed + 1)
+            .is_some_and(|l| rule_spans(l).is_some());
+        if line.is_empty() || line.starts_with("- ") || next_is_rule {
+            break;
+        }
+        let cells = cut(line)
+            .into_iter()
+            .zip(spans)
+            .map(|(cell, &(_, _, kind))| cell_value(kind, &cell))
+            .collect();
+        table = table.row(cells);
+        consumed += 1;
+    }
+    (table, consumed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::ReportFormatter;
+    use crate::formatters::text::TextFormatter;
+    use crate::report::Date;
+
+    #[test]
+    fn title_headings_and_items_round_trip() {
+        let report = Report::new("Monthly Sales")
+            .section(Section::untitled().items(["Product A: 120 units", "- dash"]))
+            .section(Section::new("Empty"))
+            .section(Section::new("More").items(["x"]));
+
+        let text = TextFormatter::default().format(&report).unwrap();
+
+        assert_eq!(TextParser.parse(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn typed_tables_and_key_values_round_trip() {
+        let report = Report::new("T").section(
+            Section::new("S")
+                .table(
+                    Table::new(vec![
+                        Column::new("Product", ColumnType::Text),
+                        Column::new("Units", ColumnType::Integer),
+                        Column::new("Revenue", ColumnType::Decimal),
+                        Column::new("Last order", ColumnType::Date),
+                        Column::new("N", ColumnType::Integer),
+                    ])
+                    .row(vec![
+                        Value::text("Product A"),
+                        Value::Integer(-120),
+                        Value::Decimal(2_398.8),
+                        Value::Date(Date::new(2025, 1, 30)),
+                        Value::Integer(1),
+                    ])
+                    .row(vec![
+                        Value::text("B"),
+                        Value::Empty,
+                        Value::Decimal(0.125),
+                        Value::Empty,
+                        Value::text("n/a"),
+                    ]),
+                )
+                .key_values([
+                    ("Period", Value::text("December 2024")),
+                    ("Total units", Value::Integer(243)),
+                    ("Total revenue", Value::Decimal(7_749.2)),
+                    ("Generated on", Value::Date(Date::new(2025, 1, 2))),
+                    ("Code", Value::text("0042")),
+                    ("Quoted", Value::text("\"R&D\" \\ 2025-01-02")),
+                    ("Blank", Value::text("")),
+                    ("None", Value::Empty),
+                ]),
+        );
+
+        let text = TextFormatter::default().format(&report).unwrap();
+
+        assert!(String::from_utf8_lossy(&text).contains("Code          : \"0042\""));
+        assert_eq!(TextParser.parse(&text).unwrap(), report);
+    }
+
+    fn round_trip(report: &Report) -> Report {
+        TextParser
+            .parse(&TextFormatter::default().format(report).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn line_breaks_and_quotes_round_trip() {
+        let report = Report::new("Two\nlines")
+            .section(Section::new("\"Quoted\" \\ heading\r\n").items(["a\nb", "\"x\"", "", "---"]))
+            .section(
+                Section::new("S").table(
+                    Table::new(vec![
+                        Column::new("", ColumnType::Text),
+                        Column::new("- name", ColumnType::Integer),
+                    ])
+                    .row(vec![Value::text("one\ntwo"), Value::Integer(1)])
+                    .row(vec![Value::text("-"), Value::text("2")])
+                    .row(vec![Value::text(" padded "), Value::Empty]),
+                ),
+            );
+
+        assert_eq!(round_trip(&report), report);
+    }
+
+    #[test]
+    fn keys_with_separators_round_trip() {
+        let report = Report::new("T").section(Section::untitled().key_values([
+            ("a : b", Value::Integer(1)),
+            ("ends :", Value::text("x : y")),
+            ("- dash", Value::text("two\nlines")),
+            ("\"q\"", Value::Empty),
+            ("trailing ", Value::Decimal(1.5)),
+        ]));
+
+        assert_eq!(round_trip(&report), report);
+    }
+
+    #[test]
+    fn empty_blocks_and_sections_round_trip() {
+        let report = Report::new("")
+            .section(Section::untitled())
+            .section(Section::untitled().items(Vec::<String>::new()))
+            .section(Section::new("").key_values(Vec::<(String, Value)>::new()))
+            .section(Section::untitled().items(["x"]));
+
+        assert_eq!(round_trip(&report), report);
+        assert_eq!(round_trip(&Report::new("Nothing")), Report::new("Nothing"));
+    }
+
+    #[test]
+    fn consecutive_blocks_of_a_kind_stay_apart() {
+        let table =
+            Table::new(vec![Column::new("N", ColumnType::Integer)]).row(vec![Value::Integer(1)]);
+        let report = Report::new("T").section(
+            Section::untitled()
+                .items(["a"])
+                .items(["b"])
+                .items(Vec::<String>::new())
+                .items(["c"])
+                .key_values([("k", Value::Integer(1))])
+                .key_values([("k", Value::Integer(2))])
+                .table(table.clone())
+                .table(table.clone())
+                .items(["d"])
+                .table(table)
+                .key_values([("k", Value::Integer(3))]),
+        );
+
+        assert_eq!(round_trip(&report), report);
+    }
+
+    #[test]
+    fn what_text_cannot_show_is_an_error() {
+        let table = |column: ColumnType, value: Value| {
+            Report::new("T").section(
+                Section::untitled()
+                    .table(Table::new(vec![Column::new("C", column)]).row(vec![value])),
+            )
+        };
+
+        assert!(
+            TextFormatter::default()
+                .format(&table(ColumnType::Decimal, Value::Integer(3)))
+                .is_err()
+        );
+        assert!(
+            TextFormatter::default()
+                .format(&table(ColumnType::Text, Value::Empty))
+                .is_err()
+        );
+        assert!(
+            TextFormatter::default()
+                .format(&Report::new("T").section(Section::untitled().table(Table::new(vec![]))))
+                .is_err()
+        );
+        assert!(
+            TextFormatter::default()
+                .format(&table(ColumnType::Integer, Value::Decimal(3.0)))
+                .is_ok()
+        );
+    }
+}
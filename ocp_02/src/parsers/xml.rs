@@ -0,0 +1,406 @@
+// src/parsers/xml.rs
+// Reads the documents written by XmlFormatter (see formatters/xml.rs and schema/report.xsd)
+// The root element must be in XmlFormatter::NAMESPACE: a document of another schema version is refused.
+// A cell without a type attribute has its column type, an empty one is an empty value.
+
+use super::{ParseError, ReportParser, column_type, typed_value, utf8};
+use crate::formatters::xml::XmlFormatter;
+use crate::report::{Block, Column, ColumnType, Report, Section, Table, Value};
+
+pub struct XmlParser;
+
+impl ReportParser for XmlParser {
+    fn name(&self) -> &str {
+        "xml"
+    }
+
+    fn parse(&self, input: &[u8]) -> Result<Report, ParseError> {
+        let root = Reader::new(utf8(input)?).document()?;
+        if root.name != "report" {
+            return Err(root.error(&format!("expected <report>, found <{}>", root.name)));
+        }
+        if root.attribute("xmlns") != Some(XmlFormatter::NAMESPACE) {
+            return Err(root.error(&format!(
+                "the document is not in the {} namespace",
+                XmlFormatter::NAMESPACE
+            )));
+        }
+
+        let mut children = root.elements();
+        let title = match children.next() {
+            Some(title) if title.name == "title" => title.text(),
+            _ => return Err(root.error("<title> must come first")),
+        };
+        let mut report = Report::new(title);
+        for element in children {
+            if element.name != "section" {
+                return Err(element.error(&format!("unexpected <{}>", element.name)));
+            }
+            let mut section = match element.attribute("heading") {
+                Some(heading) => Section::new(heading),
+                None => Section::untitled(),
+            };
+            for block in element.elements() {
+                section.blocks.push(match block.name.as_str() {
+                    "items" => Block::Items(block.elements().map(Element::text).collect()),
+                    "table" => Block::Table(parse_table(block)?),
+                    "entries" => Block::KeyValues(parse_entries(block)?),
+                    other => return Err(block.error(&format!("unexpected <{}>", other))),
+                });
+            }
+            report.sections.push(section);
+        }
+        Ok(report)
+    }
+}
+
+fn parse_table(element: &Element) -> Result<Table, ParseError> {
+    let mut children = element.elements();
+    let columns = match children.next() {
+        Some(columns) if columns.name == "columns" => columns,
+        _ => return Err(element.error("<columns> must come first")),
+    };
+    let mut table = Table::new(Vec::new());
+    for column in columns.elements() {
+        let name = column
+            .attribute("name")
+            .ok_or_else(|| column.error("column without name"))?;
+        let kind = column
+            .attribute("type")
+            .and_then(column_type)
+            .ok_or_else(|| column.error("column without valid type"))?;
+        table.columns.push(Column::new(name, kind));
+    }
+    for row in children {
+        let cells: Vec<&Element> = row.elements().collect();
+        if cells.len() != table.columns.len() {
+            return Err(row.error("a table row must have one cell per column"));
+        }
+        let values = table
+            .columns
+            .iter()
+            .zip(cells)
+            .map(|(column, cell)| value(cell, Some(column.kind)))
+            .collect::<Result<_, _>>()?;
+        table.rows.push(values);
+    }
+    Ok(table)
+}
+
+fn parse_entries(element: &Element) -> Result<Vec<(String, Value)>, ParseError> {
+    element
+        .elements()
+        .map(|entry| {
+            let key = entry
+                .attribute("key")
+                .ok_or_else(|| entry.error("entry without key"))?;
+            Ok((key.to_string(), value(entry, None)?))
+        })
+        .collect()
+}
+
+// The element's own type wins over the default one (the column's)
+fn value(element: &Element, default: Option<ColumnType>) -> Result<Value, ParseError> {
+    let text = element.text();
+    let kind = match element.attribute("type") {
+        Some(name) => Some(column_type(name).ok_or_else(|| element.error("invalid type"))?),
+        None if text.is_empty() => None,
+        None => default,
+    };
+    match kind {
+        None if text.is_empty() => Ok(Value::Empty),
+        None => Err(element.error("value without type")),
+        Some(kind) => typed_value(kind, &text)
+            .ok_or_else(|| element.error(&format!("invalid {} \"{}\"", kind.name(), text))),
+    }
+}
+
+// =========================
+// A small XML reader
+// =========================
+
+// Enough XML 1.0 for these documents: elements, attributes, text, character and entity
+// references, CDATA, comments and processing instructions. No DTD.
+// Prefixes are kept in names ("xsi:schemaLocation"), the report vocabulary has none.
+
+struct Element {
+    name: String,
+    line: usize,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    // Text content, whitespace included: it belongs to the value
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Text(text) => Some(text.as_str()),
+                Node::Element(_) => None,
+            })
+            .collect()
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, message)
+    }
+}
+
+struct Reader<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            rest: input.strip_prefix('\u{FEFF}').unwrap_or(input),
+            line: 1,
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, message)
+    }
+
+    fn advance(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        self.line += taken.matches('\n').count();
+        self.rest = rest;
+        taken
+    }
+
+    // Up to and including the delimiter, returns what comes before it
+    fn until(&mut self, delimiter: &str) -> Result<&'a str, ParseError> {
+        let end = self
+            .rest
+            .find(delimiter)
+            .ok_or_else(|| self.error(&format!("missing \"{}\"", delimiter)))?;
+        let taken = self.advance(end);
+        self.advance(delimiter.len());
+        Ok(taken)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let len = self.rest.len() - self.rest.trim_start().len();
+        self.advance(len);
+    }
+
+    // Comments, processing instructions and the XML declaration
+    fn skip_misc(&mut self) -> Result<(), ParseError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest.starts_with("<?") {
+                self.until("?>")?;
+            } else if self.rest.starts_with("<!--") {
+                self.until("-->")?;
+            } else if self.rest.starts_with("<!DOCTYPE") {
+                return Err(self.error("DTDs are not supported"));
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn document(mut self) -> Result<Element, ParseError> {
+        self.skip_misc()?;
+        if !self.rest.starts_with('<') {
+            return Err(self.error("expected the root element"));
+        }
+        let root = self.element()?;
+        self.skip_misc()?;
+        if !self.rest.is_empty() {
+            return Err(self.error("content after the root element"));
+        }
+        Ok(root)
+    }
+
+    // At '<'
+    fn element(&mut self) -> Result<Element, ParseError> {
+        let line = self.line;
+        self.advance(1);
+        let name = self.name()?;
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest.starts_with("/>") {
+                self.advance(2);
+                return Ok(Element {
+                    name,
+                    line,
+                    attributes,
+                    children: Vec::new(),
+                });
+            }
+            if self.rest.starts_with('>') {
+                self.advance(1);
+                break;
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            if !self.rest.starts_with('=') {
+                return Err(self.error(&format!("attribute {} without value", attribute)));
+            }
+            self.advance(1);
+            self.skip_whitespace();
+            let quote = match self.rest.chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(self.error("attribute values must be quoted")),
+            };
+            self.advance(1);
+            let raw = self.until(if quote == '"' { "\"" } else { "'" })?;
+            if raw.contains('<') {
+                return Err(self.error("'<' in an attribute value"));
+            }
+            let value = self.unescape(raw)?;
+            if attributes.iter().any(|(n, _)| *n == attribute) {
+                return Err(self.error(&format!("duplicate attribute {}", attribute)));
+            }
+            attributes.push((attribute, value));
+        }
+
+        let mut children = Vec::new();
+        loop {
+            if self.rest.starts_with("</") {
+                self.advance(2);
+                let closing = self.name()?;
+                self.skip_whitespace();
+                if closing != name || !self.rest.starts_with('>') {
+                    return Err(self.error(&format!("expected </{}>", name)));
+                }
+                self.advance(1);
+                return Ok(Element {
+                    name,
+                    line,
+                    attributes,
+                    children,
+                });
+            } else if self.rest.starts_with("<!--") {
+                self.until("-->")?;
+            } else if self.rest.starts_with("<![CDATA[") {
+                self.advance(9);
+                children.push(Node::Text(self.until("]]>")?.to_string()));
+            } else if self.rest.starts_with("<?") {
+                self.until("?>")?;
+            } else if self.rest.starts_with('<') {
+                children.push(Node::Element(self.element()?));
+            } else if self.rest.is_empty() {
+                return Err(self.error(&format!("<{}> is never closed", name)));
+            } else {
+                let end = self.rest.find('<').unwrap_or(self.rest.len());
+                let raw = self.advance(end);
+                children.push(Node::Text(self.unescape(raw)?));
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, ParseError> {
+        let len = self
+            .rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/' | '<'))
+            .unwrap_or(self.rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        Ok(self.advance(len).to_string())
+    }
+
+    fn unescape(&self, raw: &str) -> Result<String, ParseError> {
+        let mut out = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(start) = rest.find('&') {
+            out.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find(';')
+                .ok_or_else(|| self.error("unterminated reference"))?;
+            let reference = &rest[start + 1..start + end];
+            let c = match reference {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => reference
+                    .strip_prefix("#x")
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| reference.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            out.push(c.ok_or_else(|| self.error(&format!("unknown reference &{};", reference)))?);
+            rest = &rest[start + end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatters::ReportFormatter;
+    use crate::report::Date;
+
+    #[test]
+    fn round_trip() {
+        let report = Report::new("R&D <beta> 'q'")
+            .section(Section::untitled().items(["  spaced  ", "a\nb", ""]))
+            .section(Section::new(""))
+            .section(
+                Section::new("\"Table\"")
+                    .table(
+                        Table::new(vec![
+                            Column::new("n", ColumnType::Integer),
+                            Column::new("x", ColumnType::Decimal),
+                            Column::new("s", ColumnType::Text),
+                        ])
+                        .row(vec![
+                            Value::Integer(7),
+                            Value::Decimal(0.1 + 0.2),
+                            Value::text(""),
+                        ])
+                        .row(vec![
+                            Value::Empty,
+                            Value::text("n/a"),
+                            Value::Date(Date::new(2025, 1, 30)),
+                        ]),
+                    )
+                    .key_values([("k", Value::Decimal(-1.5)), ("e", Value::Empty)]),
+            );
+
+        let xml = XmlFormatter::default().format(&report).unwrap();
+
+        assert_eq!(XmlParser.parse(&xml).unwrap(), report);
+    }
+
+    #[test]
+    fn other_namespaces_are_refused() {
+        let xml =
+            "<?xml version=\"1.0\"?>\n<report xmlns=\"urn:other\">\n<title>T</title></report>";
+
+        let err = XmlParser.parse(xml.as_bytes()).unwrap_err();
+
+        assert_eq!(err.line, 2);
+        assert!(err.message.contains("namespace"));
+    }
+}
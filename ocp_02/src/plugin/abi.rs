@@ -0,0 +1,154 @@
+// src/plugin/abi.rs
+// The contract between the host and a formatter plugin, in C terms
+// Rust trait objects have no stable layout across compilers, so a plugin never sees a Report
+// or a dyn ReportFormatter: it exports a table of C functions and reads a C view of the report.
+// Plugins include this very file (#[path = ".../abi.rs"] mod abi;), it must stay self-contained.
+//
+// Rules
+// - The library exports `report_formatter_plugin`, returning a pointer to a static PluginVTable
+// - abi_version is the first field and stays there forever, it is checked before anything else
+// - Any change to the structures below bumps ABI_VERSION
+// - Strings are UTF-8, not NUL-terminated, and only valid during the call that receives them
+//   (except the vtable strings, which live as long as the library)
+// - render must be thread-safe: the host calls it from several threads at once (see batch.rs),
+//   so any state it keeps between calls is the plugin's to synchronize
+
+#![allow(dead_code)]
+
+use std::ffi::c_void;
+use std::marker::PhantomData;
+
+pub const ABI_VERSION: u32 = 1;
+pub const ENTRY_POINT: &str = "report_formatter_plugin";
+
+// Signature of the entry point
+pub type EntryPoint = unsafe extern "C" fn() -> *const PluginVTable;
+
+#[repr(C)]
+pub struct PluginVTable {
+    pub abi_version: u32,
+    pub name: FfiStr<'static>,
+    // Comma-separated, without dots, preferred one first ("rst,rest")
+    pub extensions: FfiStr<'static>,
+    pub mime_type: FfiStr<'static>,
+    // Returns 0 on success. Any other value is an error, reported with the plugin name.
+    pub render: unsafe extern "C" fn(report: &FfiReport<'_>, out: &FfiWriter) -> i32,
+}
+
+// Cell kinds (FfiCell::kind), same meaning as ColumnType. Empty cells have KIND_EMPTY.
+pub const KIND_EMPTY: u32 = 0;
+pub const KIND_TEXT: u32 = 1;
+pub const KIND_INTEGER: u32 = 2;
+pub const KIND_DECIMAL: u32 = 3;
+pub const KIND_DATE: u32 = 4;
+
+// Block kinds (FfiBlock::kind)
+// - items: cells are the items
+// - table: columns give name and kind of each column, cells are the rows one after the other
+// - key_values: cells go by pairs, key then value
+pub const BLOCK_ITEMS: u32 = 0;
+pub const BLOCK_TABLE: u32 = 1;
+pub const BLOCK_KEY_VALUES: u32 = 2;
+
+#[repr(C)]
+pub struct FfiReport<'a> {
+    pub title: FfiStr<'a>,
+    pub sections: FfiSlice<'a, FfiSection<'a>>,
+}
+
+#[repr(C)]
+pub struct FfiSection<'a> {
+    pub has_heading: bool,
+    pub heading: FfiStr<'a>,
+    pub blocks: FfiSlice<'a, FfiBlock<'a>>,
+}
+
+#[repr(C)]
+pub struct FfiBlock<'a> {
+    pub kind: u32,
+    pub columns: FfiSlice<'a, FfiCell<'a>>,
+    pub cells: FfiSlice<'a, FfiCell<'a>>,
+}
+
+// A value as the report displays it
+#[repr(C)]
+pub struct FfiCell<'a> {
+    pub kind: u32,
+    pub text: FfiStr<'a>,
+}
+
+#[repr(C)]
+pub struct FfiStr<'a> {
+    ptr: *const u8,
+    len: usize,
+    _data: PhantomData<&'a str>,
+}
+
+// Just a &str with a C layout
+unsafe impl Send for FfiStr<'_> {}
+unsafe impl Sync for FfiStr<'_> {}
+
+impl<'a> FfiStr<'a> {
+    pub const fn new(s: &'a str) -> Self {
+        Self {
+            ptr: s.as_ptr(),
+            len: s.len(),
+            _data: PhantomData,
+        }
+    }
+
+    // None when the other side sent invalid UTF-8
+    pub fn to_str(&self) -> Option<&'a str> {
+        if self.len == 0 {
+            return Some("");
+        }
+        // Safety: ptr/len come from a &str (or a plugin honoring the contract above)
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr, self.len) };
+        std::str::from_utf8(bytes).ok()
+    }
+}
+
+#[repr(C)]
+pub struct FfiSlice<'a, T> {
+    ptr: *const T,
+    len: usize,
+    _data: PhantomData<&'a [T]>,
+}
+
+// Just a &[T] with a C layout
+unsafe impl<T: Sync> Send for FfiSlice<'_, T> {}
+unsafe impl<T: Sync> Sync for FfiSlice<'_, T> {}
+
+impl<'a, T> FfiSlice<'a, T> {
+    pub fn new(items: &'a [T]) -> Self {
+        Self {
+            ptr: items.as_ptr(),
+            len: items.len(),
+            _data: PhantomData,
+        }
+    }
+
+    pub fn as_slice(&self) -> &'a [T] {
+        if self.len == 0 {
+            return &[];
+        }
+        // Safety: ptr/len come from a slice that outlives 'a
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+// Where the plugin writes its output
+#[repr(C)]
+pub struct FfiWriter {
+    pub context: *mut c_void,
+    // Writes the whole buffer, returns 0 on success
+    pub write: unsafe extern "C" fn(context: *mut c_void, data: *const u8, len: usize) -> i32,
+}
+
+impl FfiWriter {
+    // Returns false when the host could not write, the plugin should then stop and return an error
+    pub fn write_all(&self, bytes: &[u8]) -> bool {
+        // Safety: context and write are provided together by the host for this call
+        unsafe { (self.write)(self.context, bytes.as_ptr(), bytes.len()) == 0 }
+    }
+}
@@ -0,0 +1,91 @@
+// src/plugin/library.rs
+// Minimal dynamic library loading (dlopen on Unix, LoadLibrary on Windows)
+// Libraries are never unloaded: the formatters they provide live until the end of the program.
+
+use std::ffi::{CString, c_void};
+use std::path::Path;
+
+pub struct Library {
+    handle: *mut c_void,
+}
+
+impl Library {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let handle = sys::open(path)?;
+        Ok(Self { handle })
+    }
+
+    // Address of an exported symbol
+    pub fn symbol(&self, name: &str) -> Option<*mut c_void> {
+        let name = CString::new(name).ok()?;
+        let address = sys::symbol(self.handle, &name);
+        (!address.is_null()).then_some(address)
+    }
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::ffi::{CStr, CString, c_char, c_int, c_void};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    const RTLD_NOW: c_int = 2;
+
+    unsafe extern "C" {
+        fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+        fn dlerror() -> *mut c_char;
+    }
+
+    pub fn open(path: &Path) -> Result<*mut c_void, String> {
+        let path = CString::new(path.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+        // Safety: path is a valid NUL-terminated string, dlerror is read right after the failure
+        unsafe {
+            let handle = dlopen(path.as_ptr(), RTLD_NOW);
+            if handle.is_null() {
+                let message = dlerror();
+                if message.is_null() {
+                    return Err("dlopen failed".to_string());
+                }
+                return Err(CStr::from_ptr(message).to_string_lossy().into_owned());
+            }
+            Ok(handle)
+        }
+    }
+
+    pub fn symbol(handle: *mut c_void, name: &CStr) -> *mut c_void {
+        // Safety: handle comes from a successful dlopen and is never closed
+        unsafe { dlsym(handle, name.as_ptr()) }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::ffi::{CStr, c_char, c_void};
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn LoadLibraryW(filename: *const u16) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+        fn GetLastError() -> u32;
+    }
+
+    pub fn open(path: &Path) -> Result<*mut c_void, String> {
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+        // Safety: wide is a valid NUL-terminated UTF-16 string
+        unsafe {
+            let handle = LoadLibraryW(wide.as_ptr());
+            if handle.is_null() {
+                return Err(format!("LoadLibrary failed (error {})", GetLastError()));
+            }
+            Ok(handle)
+        }
+    }
+
+    pub fn symbol(handle: *mut c_void, name: &CStr) -> *mut c_void {
+        // Safety: handle comes from a successful LoadLibraryW and is never freed
+        unsafe { GetProcAddress(handle, name.as_ptr()) }
+    }
+}
@@ -0,0 +1,416 @@
+// src/plugin/mod.rs
+// Formatters loaded at runtime from shared libraries (cdylib crates)
+// A third party builds a plugin against abi.rs, drops the .so/.dll/.dylib in a directory,
+// and the format shows up in the registry. Nothing here, nor in Report, changes.
+//
+// Loading a library runs its code: only load plugins from a directory you trust.
+
+pub mod abi;
+mod library;
+
+use std::ffi::c_void;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::formatters::ReportFormatter;
+use crate::registry::{FormatterRegistry, RegistryError};
+use crate::report::{Block, ColumnType, Report};
+use abi::{FfiBlock, FfiCell, FfiReport, FfiSection, FfiSlice, FfiStr, FfiWriter, PluginVTable};
+use library::Library;
+
+#[derive(Debug)]
+pub enum PluginError {
+    // The file is not a loadable library (wrong platform, missing dependency...)
+    Load(String),
+    // The library doesn't export abi::ENTRY_POINT
+    MissingEntryPoint,
+    // Built against another version of abi.rs
+    AbiMismatch { expected: u32, found: u32 },
+    // Missing or malformed name, extensions or MIME type
+    InvalidMetadata(&'static str),
+    Registry(RegistryError),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Load(message) => write!(f, "cannot load library: {}", message),
+            PluginError::MissingEntryPoint => {
+                write!(f, "no \"{}\" entry point", abi::ENTRY_POINT)
+            }
+            PluginError::AbiMismatch { expected, found } => write!(
+                f,
+                "plugin built for ABI version {}, this program supports version {}",
+                found, expected
+            ),
+            PluginError::InvalidMetadata(field) => write!(f, "invalid plugin {}", field),
+            PluginError::Registry(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+// A formatter implemented by a plugin, usable as any other dyn ReportFormatter
+pub struct PluginFormatter {
+    vtable: &'static PluginVTable,
+    name: &'static str,
+    extensions: Vec<&'static str>,
+    mime_type: &'static str,
+}
+
+impl PluginFormatter {
+    // Checks the ABI version first: nothing else in the table can be trusted before that
+    pub fn from_vtable(vtable: &'static PluginVTable) -> Result<Self, PluginError> {
+        if vtable.abi_version != abi::ABI_VERSION {
+            return Err(PluginError::AbiMismatch {
+                expected: abi::ABI_VERSION,
+                found: vtable.abi_version,
+            });
+        }
+        let name = vtable
+            .name
+            .to_str()
+            .filter(|n| !n.is_empty())
+            .ok_or(PluginError::InvalidMetadata("name"))?;
+        let extensions = vtable
+            .extensions
+            .to_str()
+            .ok_or(PluginError::InvalidMetadata("extensions"))?
+            .split(',')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .collect();
+        let mime_type = vtable
+            .mime_type
+            .to_str()
+            .filter(|m| m.contains('/'))
+            .ok_or(PluginError::InvalidMetadata("MIME type"))?;
+        Ok(Self {
+            vtable,
+            name,
+            extensions,
+            mime_type,
+        })
+    }
+}
+
+impl ReportFormatter for PluginFormatter {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+
+    fn mime_type(&self) -> &str {
+        self.mime_type
+    }
+
+    fn write(&self, report: &Report, out: &mut dyn Write) -> io::Result<()> {
+        let mut sink = Sink { out, error: None };
+        let writer = FfiWriter {
+            context: &mut sink as *mut Sink as *mut c_void,
+            write: write_to_sink,
+        };
+        let status = with_ffi_report(report, |ffi| {
+            // Safety: the vtable passed the ABI check, ffi and writer outlive the call, and
+            // render is thread-safe by contract, as ReportFormatter: Sync requires
+            unsafe { (self.vtable.render)(ffi, &writer) }
+        });
+        match (status, sink.error) {
+            (0, _) => Ok(()),
+            (_, Some(error)) => Err(error),
+            (code, None) => Err(io::Error::other(format!(
+                "plugin \"{}\" failed with code {}",
+                self.name, code
+            ))),
+        }
+    }
+}
+
+// Loads one plugin library
+pub fn load_plugin(path: &Path) -> Result<PluginFormatter, PluginError> {
+    let library = Library::open(path).map_err(PluginError::Load)?;
+    let entry = library
+        .symbol(abi::ENTRY_POINT)
+        .ok_or(PluginError::MissingEntryPoint)?;
+    // Safety: the symbol is the entry point declared by the contract, the library stays loaded
+    // so the vtable it returns is 'static
+    let vtable = unsafe {
+        let entry: abi::EntryPoint = std::mem::transmute(entry);
+        entry().as_ref()
+    };
+    PluginFormatter::from_vtable(vtable.ok_or(PluginError::MissingEntryPoint)?)
+}
+
+// Loads and registers every library of a directory (.so, .dll or .dylib depending on the platform)
+// One bad plugin doesn't stop the others: each file gets its own outcome, the formatter name on success
+pub fn load_plugins(
+    dir: &Path,
+    registry: &mut FormatterRegistry,
+) -> io::Result<Vec<(PathBuf, Result<String, PluginError>)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(std::env::consts::DLL_EXTENSION))
+        })
+        .collect();
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let outcome = load_plugin(&path).and_then(|formatter| {
+                let name = formatter.name().to_string();
+                registry
+                    .register(Box::new(formatter))
+                    .map(|()| name)
+                    .map_err(PluginError::Registry)
+            });
+            (path, outcome)
+        })
+        .collect())
+}
+
+// =========================
+// Host side of the FFI
+// =========================
+
+struct Sink<'a> {
+    out: &'a mut dyn Write,
+    // The first write error, handed back to the caller instead of the plugin's error code
+    error: Option<io::Error>,
+}
+
+unsafe extern "C" fn write_to_sink(context: *mut c_void, data: *const u8, len: usize) -> i32 {
+    // Safety: context is the Sink set up in PluginFormatter::write, data/len a buffer from the plugin
+    let (sink, bytes) = unsafe {
+        let bytes = if len == 0 {
+            &[][..]
+        } else {
+            std::slice::from_raw_parts(data, len)
+        };
+        (&mut *(context as *mut Sink), bytes)
+    };
+    if sink.error.is_some() {
+        return -1;
+    }
+    match sink.out.write_all(bytes) {
+        Ok(()) => 0,
+        Err(e) => {
+            sink.error = Some(e);
+            -1
+        }
+    }
+}
+
+fn kind_code(kind: Option<ColumnType>) -> u32 {
+    match kind {
+        None => abi::KIND_EMPTY,
+        Some(ColumnType::Text) => abi::KIND_TEXT,
+        Some(ColumnType::Integer) => abi::KIND_INTEGER,
+        Some(ColumnType::Decimal) => abi::KIND_DECIMAL,
+        Some(ColumnType::Date) => abi::KIND_DATE,
+    }
+}
+
+// Builds the C view of the report, valid for the duration of f
+fn with_ffi_report<R>(report: &Report, f: impl FnOnce(&FfiReport<'_>) -> R) -> R {
+    // Every cell as (kind, displayed text), the FFI structures then borrow from these
+    let cells: Vec<Vec<Vec<(u32, String)>>> = report
+        .sections
+        .iter()
+        .map(|section| {
+            section
+                .blocks
+                .iter()
+                .map(|block| match block {
+                    Block::Items(items) => items
+                        .iter()
+                        .map(|item| (abi::KIND_TEXT, item.clone()))
+                        .collect(),
+                    Block::Table(table) => table
+                        .rows
+                        .iter()
+                        .flatten()
+                        .map(|value| (kind_code(value.kind()), value.to_string()))
+                        .collect(),
+                    Block::KeyValues(pairs) => pairs
+                        .iter()
+                        .flat_map(|(key, value)| {
+                            [
+                                (abi::KIND_TEXT, key.clone()),
+                                (kind_code(value.kind()), value.to_string()),
+                            ]
+                        })
+                        .collect(),
+                })
+                .collect()
+        })
+        .collect();
+    let columns: Vec<Vec<Vec<FfiCell>>> = report
+        .sections
+        .iter()
+        .map(|s| s.blocks.iter().map(ffi_columns).collect())
+        .collect();
+    let cells: Vec<Vec<Vec<FfiCell>>> = cells
+        .iter()
+        .map(|blocks| blocks.iter().map(|c| ffi_cells(c)).collect())
+        .collect();
+    let blocks: Vec<Vec<FfiBlock>> = report
+        .sections
+        .iter()
+        .zip(columns.iter().zip(&cells))
+        .map(|(section, (columns, cells))| {
+            section
+                .blocks
+                .iter()
+                .zip(columns.iter().zip(cells))
+                .map(|(block, (columns, cells))| FfiBlock {
+                    kind: match block {
+                        Block::Items(_) => abi::BLOCK_ITEMS,
+                        Block::Table(_) => abi::BLOCK_TABLE,
+                        Block::KeyValues(_) => abi::BLOCK_KEY_VALUES,
+                    },
+                    columns: FfiSlice::new(columns),
+                    cells: FfiSlice::new(cells),
+                })
+                .collect()
+        })
+        .collect();
+    let sections: Vec<FfiSection> = report
+        .sections
+        .iter()
+        .zip(&blocks)
+        .map(|(section, blocks)| FfiSection {
+            has_heading: section.heading.is_some(),
+            heading: FfiStr::new(section.heading.as_deref().unwrap_or("")),
+            blocks: FfiSlice::new(blocks),
+        })
+        .collect();
+
+    f(&FfiReport {
+        title: FfiStr::new(&report.title),
+        sections: FfiSlice::new(&sections),
+    })
+}
+
+fn ffi_cells(cells: &[(u32, String)]) -> Vec<FfiCell<'_>> {
+    cells
+        .iter()
+        .map(|(kind, text)| FfiCell {
+            kind: *kind,
+            text: FfiStr::new(text),
+        })
+        .collect()
+}
+
+fn ffi_columns(block: &Block) -> Vec<FfiCell<'_>> {
+    match block {
+        Block::Table(table) => table
+            .columns
+            .iter()
+            .map(|c| FfiCell {
+                kind: kind_code(Some(c.kind)),
+                text: FfiStr::new(&c.name),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{Column, Section, Table, Value};
+
+    // An in-process stand-in for a plugin: same vtable, same C calls, no library
+    unsafe extern "C" fn render_outline(report: &FfiReport<'_>, out: &FfiWriter) -> i32 {
+        let mut text = format!("{}\n", report.title.to_str().unwrap());
+        for section in report.sections.as_slice() {
+            if section.has_heading {
+                text += &format!("# {}\n", section.heading.to_str().unwrap());
+            }
+            for block in section.blocks.as_slice() {
+                let cells: Vec<&str> = block
+                    .cells
+                    .as_slice()
+                    .iter()
+                    .map(|c| c.text.to_str().unwrap())
+                    .collect();
+                text += &format!(
+                    "{} {}/{}: {}\n",
+                    block.kind,
+                    block.columns.as_slice().len(),
+                    cells.len(),
+                    cells.join(",")
+                );
+            }
+        }
+        if out.write_all(text.as_bytes()) { 0 } else { 7 }
+    }
+
+    static OUTLINE: PluginVTable = PluginVTable {
+        abi_version: abi::ABI_VERSION,
+        name: FfiStr::new("outline"),
+        extensions: FfiStr::new("outline, txt"),
+        mime_type: FfiStr::new("text/plain"),
+        render: render_outline,
+    };
+
+    static FUTURE: PluginVTable = PluginVTable {
+        abi_version: abi::ABI_VERSION + 1,
+        name: FfiStr::new("future"),
+        extensions: FfiStr::new("f"),
+        mime_type: FfiStr::new("text/plain"),
+        render: render_outline,
+    };
+
+    #[test]
+    fn renders_through_the_c_interface() {
+        let formatter = PluginFormatter::from_vtable(&OUTLINE).unwrap();
+        let report = Report::new("T")
+            .section(Section::untitled().items(["a", "b"]))
+            .section(
+                Section::new("S")
+                    .table(
+                        Table::new(vec![Column::new("n", ColumnType::Integer)])
+                            .row(vec![Value::Integer(1)])
+                            .row(vec![Value::Empty]),
+                    )
+                    .key_values([("k", Value::Decimal(1.5))]),
+            );
+
+        assert_eq!(formatter.extensions(), ["outline", "txt"]);
+        assert_eq!(
+            String::from_utf8(formatter.format(&report).unwrap()).unwrap(),
+            "T\n0 0/2: a,b\n# S\n1 1/2: 1,\n2 0/2: k,1.50\n"
+        );
+    }
+
+    #[test]
+    fn incompatible_plugins_are_rejected() {
+        assert!(matches!(
+            PluginFormatter::from_vtable(&FUTURE),
+            Err(PluginError::AbiMismatch { found, .. }) if found == abi::ABI_VERSION + 1
+        ));
+
+        let dir = std::env::temp_dir().join(format!("ex_02_ocp_plugins_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let fake = dir.join(format!("fake.{}", std::env::consts::DLL_EXTENSION));
+        fs::write(&fake, b"not a library").unwrap();
+        let mut registry = FormatterRegistry::new();
+
+        let outcomes = load_plugins(&dir, &mut registry).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(outcomes.len(), 1);
+        assert!(matches!(outcomes[0].1, Err(PluginError::Load(_))));
+        assert!(registry.formats().next().is_none());
+    }
+}
@@ -0,0 +1,502 @@
+// src/query.rs
+// A query stage between the report data and the formatter
+// Tables are filtered, sorted, cut and grouped into sections, with subtotals, so one report
+// yields "top 10 products" or "by category" views that any formatter renders as usual.
+// Item lists have no columns: they get their own filter and sort. Key/value blocks are left as is.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::report::{Block, ColumnType, Report, Section, Table, Value, parse_item};
+
+#[derive(Debug, PartialEq)]
+pub enum QueryError {
+    // No table of the report has this column
+    UnknownColumn(String),
+    // Subtotals only add up integer and decimal columns
+    NotNumeric(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::UnknownColumn(name) => write!(f, "no table has a \"{}\" column", name),
+            QueryError::NotNumeric(name) => {
+                write!(f, "column \"{}\" is not numeric, it can't be totaled", name)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+type Predicate = Box<dyn Fn(&Value) -> bool>;
+type ItemPredicate = Box<dyn Fn(&str) -> bool>;
+
+// Steps run in a fixed order: filter, sort, limit, group, subtotals
+#[derive(Default)]
+pub struct Query {
+    filters: Vec<(String, Predicate)>,
+    sort: Option<(String, Order)>,
+    item_filters: Vec<ItemPredicate>,
+    item_sort: Option<Order>,
+    limit: Option<usize>,
+    group_by: Option<String>,
+    subtotals: Vec<String>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Keeps the rows whose cell in this column matches. Several filters must all match.
+    pub fn filter(
+        mut self,
+        column: impl Into<String>,
+        keep: impl Fn(&Value) -> bool + 'static,
+    ) -> Self {
+        self.filters.push((column.into(), Box::new(keep)));
+        self
+    }
+
+    // Empty cells always come last, whatever the order
+    pub fn sort_by(mut self, column: impl Into<String>, order: Order) -> Self {
+        self.sort = Some((column.into(), order));
+        self
+    }
+
+    // Keeps the items of every bullet list that match. Several filters must all match.
+    pub fn filter_items(mut self, keep: impl Fn(&str) -> bool + 'static) -> Self {
+        self.item_filters.push(Box::new(keep));
+        self
+    }
+
+    // Items with a measure ("Label: 120 units") sort by it and come before the others,
+    // which sort by their text
+    pub fn sort_items(mut self, order: Order) -> Self {
+        self.item_sort = Some(order);
+        self
+    }
+
+    // At most this many rows per table, taken after sorting (and before grouping)
+    pub fn limit(mut self, rows: usize) -> Self {
+        self.limit = Some(rows);
+        self
+    }
+
+    // One section per distinct value of the column, in order of first appearance.
+    // The column itself is dropped from the grouped tables: the heading tells it.
+    pub fn group_by(mut self, column: impl Into<String>) -> Self {
+        self.group_by = Some(column.into());
+        self
+    }
+
+    // A "Total <column>" line after each table, or after each group's table
+    pub fn subtotal(mut self, column: impl Into<String>) -> Self {
+        self.subtotals.push(column.into());
+        self
+    }
+
+    pub fn apply(&self, report: &Report) -> Result<Report, QueryError> {
+        self.check(report)?;
+
+        let mut result = Report::new(report.title.clone());
+        for section in &report.sections {
+            // Blocks before a grouped table stay in the original section, the groups follow it
+            let mut current = Section {
+                heading: section.heading.clone(),
+                blocks: Vec::new(),
+            };
+            for block in &section.blocks {
+                let table = match block {
+                    Block::Table(table) => table,
+                    Block::Items(items) => {
+                        current.blocks.push(Block::Items(self.select_items(items)));
+                        continue;
+                    }
+                    _ => {
+                        current.blocks.push(block.clone());
+                        continue;
+                    }
+                };
+                let table = self.select(table);
+                let group = self
+                    .group_by
+                    .as_ref()
+                    .and_then(|name| Some((name, column_index(&table, name)?)));
+                match group {
+                    Some((name, index)) => {
+                        // The group headings replace the heading of a section left empty
+                        if !current.blocks.is_empty() {
+                            result.sections.push(current);
+                        }
+                        for (key, rows) in groups(&table, index) {
+                            let mut grouped = Table::new(table.columns.clone());
+                            grouped.rows = rows;
+                            grouped.columns.remove(index);
+                            grouped.rows.iter_mut().for_each(|row| {
+                                row.remove(index);
+                            });
+                            let heading = format!("{}: {}", name, display_key(&key));
+                            let mut section = Section::new(heading);
+                            self.push_table(&mut section, grouped);
+                            result.sections.push(section);
+                        }
+                        current = Section::untitled();
+                    }
+                    None => self.push_table(&mut current, table),
+                }
+            }
+            if !current.blocks.is_empty() || current.heading.is_some() {
+                result.sections.push(current);
+            }
+        }
+        Ok(result)
+    }
+
+    // Every column the query names must exist somewhere, subtotaled ones must be numeric
+    fn check(&self, report: &Report) -> Result<(), QueryError> {
+        let tables: Vec<&Table> = report
+            .sections
+            .iter()
+            .flat_map(|s| &s.blocks)
+            .filter_map(|b| match b {
+                Block::Table(table) => Some(table),
+                _ => None,
+            })
+            .collect();
+        let find = |name: &str| {
+            tables
+                .iter()
+                .flat_map(|t| &t.columns)
+                .find(|c| c.name == name)
+                .ok_or_else(|| QueryError::UnknownColumn(name.to_string()))
+        };
+
+        let named = self
+            .filters
+            .iter()
+            .map(|(name, _)| name)
+            .chain(self.sort.as_ref().map(|(name, _)| name))
+            .chain(&self.group_by);
+        for name in named {
+            find(name)?;
+        }
+        for name in &self.subtotals {
+            if !find(name)?.kind.is_numeric() {
+                return Err(QueryError::NotNumeric(name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    // Filter, sort and limit the rows of one table. Tables without the named columns pass through.
+    fn select(&self, table: &Table) -> Table {
+        let mut rows: Vec<Vec<Value>> = table
+            .rows
+            .iter()
+            .filter(|row| {
+                self.filters.iter().all(|(name, keep)| {
+                    column_index(table, name).is_none_or(|index| keep(&row[index]))
+                })
+            })
+            .cloned()
+            .collect();
+
+        if let Some((name, order)) = &self.sort
+            && let Some(index) = column_index(table, name)
+        {
+            // Stable: rows with equal keys keep their original order
+            rows.sort_by(|a, b| compare(&a[index], &b[index], *order));
+        }
+        if let Some(limit) = self.limit {
+            rows.truncate(limit);
+        }
+
+        Table {
+            columns: table.columns.clone(),
+            rows,
+        }
+    }
+
+    fn select_items(&self, items: &[String]) -> Vec<String> {
+        let mut items: Vec<String> = items
+            .iter()
+            .filter(|item| self.item_filters.iter().all(|keep| keep(item)))
+            .cloned()
+            .collect();
+        if let Some(order) = self.item_sort {
+            items.sort_by(|a, b| compare_items(a, b, order));
+        }
+        items
+    }
+
+    fn push_table(&self, section: &mut Section, table: Table) {
+        let totals: Vec<(String, Value)> = self
+            .subtotals
+            .iter()
+            .filter_map(|name| {
+                let index = column_index(&table, name)?;
+                Some((
+                    format!("Total {}", name),
+                    sum(&table, index, table.columns[index].kind),
+                ))
+            })
+            .collect();
+        section.blocks.push(Block::Table(table));
+        if !totals.is_empty() {
+            section.blocks.push(Block::KeyValues(totals));
+        }
+    }
+}
+
+fn column_index(table: &Table, name: &str) -> Option<usize> {
+    table.columns.iter().position(|c| c.name == name)
+}
+
+// Rows split by the value of one column, groups in order of first appearance
+fn groups(table: &Table, index: usize) -> Vec<(Value, Vec<Vec<Value>>)> {
+    let mut groups: Vec<(Value, Vec<Vec<Value>>)> = Vec::new();
+    for row in &table.rows {
+        match groups.iter_mut().find(|(key, _)| *key == row[index]) {
+            Some((_, rows)) => rows.push(row.clone()),
+            None => groups.push((row[index].clone(), vec![row.clone()])),
+        }
+    }
+    groups
+}
+
+fn display_key(key: &Value) -> String {
+    match key {
+        Value::Empty => "(none)".to_string(),
+        key => key.to_string(),
+    }
+}
+
+// Integer columns add up to an integer, decimal ones to a decimal. Empty cells count as nothing.
+// An integer total too large for an i64 is given as a decimal.
+fn sum(table: &Table, index: usize, kind: ColumnType) -> Value {
+    let cells = table.rows.iter().map(|row| &row[index]);
+    if matches!(kind, ColumnType::Integer)
+        && let Some(total) = cells.clone().try_fold(0i64, |total, v| match v {
+            Value::Integer(n) => total.checked_add(*n),
+            _ => Some(total),
+        })
+    {
+        return Value::Integer(total);
+    }
+    Value::Decimal(cells.filter_map(number).sum())
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(n) => Some(*n as f64),
+        Value::Decimal(x) => Some(*x),
+        _ => None,
+    }
+}
+
+// Numbers compare by value (integers and decimals mix), text and dates naturally
+fn compare(a: &Value, b: &Value, order: Order) -> Ordering {
+    let ordering = match (a, b) {
+        (Value::Empty, Value::Empty) => return Ordering::Equal,
+        (Value::Empty, _) => return Ordering::Greater,
+        (_, Value::Empty) => return Ordering::Less,
+        (Value::Text(a), Value::Text(b)) => a.cmp(b),
+        (Value::Date(a), Value::Date(b)) => a.cmp(b),
+        _ => match (number(a), number(b)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            // Mixed kinds in one column: group them by kind
+            _ => rank(a).cmp(&rank(b)),
+        },
+    };
+    match order {
+        Order::Ascending => ordering,
+        Order::Descending => ordering.reverse(),
+    }
+}
+
+fn compare_items(a: &str, b: &str, order: Order) -> Ordering {
+    let measure = |item| parse_item(item).map(|(_, value, _)| value);
+    let ordering = match (measure(a), measure(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => return Ordering::Less,
+        (None, Some(_)) => return Ordering::Greater,
+        (None, None) => a.cmp(b),
+    };
+    match order {
+        Order::Ascending => ordering,
+        Order::Descending => ordering.reverse(),
+    }
+}
+
+fn rank(value: &Value) -> u8 {
+    match value {
+        Value::Integer(_) | Value::Decimal(_) => 0,
+        Value::Date(_) => 1,
+        Value::Text(_) => 2,
+        Value::Empty => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Column;
+
+    fn products() -> Report {
+        Report::new("Products").section(
+            Section::new("Catalog").items(["All products"]).table(
+                Table::new(vec![
+                    Column::new("Product", ColumnType::Text),
+                    Column::new("Category", ColumnType::Text),
+                    Column::new("Units", ColumnType::Integer),
+                ])
+                .row(vec![
+                    Value::text("A"),
+                    Value::text("Tools"),
+                    Value::Integer(5),
+                ])
+                .row(vec![
+                    Value::text("B"),
+                    Value::text("Toys"),
+                    Value::Integer(12),
+                ])
+                .row(vec![Value::text("C"), Value::text("Tools"), Value::Empty])
+                .row(vec![
+                    Value::text("D"),
+                    Value::text("Toys"),
+                    Value::Integer(7),
+                ]),
+            ),
+        )
+    }
+
+    fn rows(report: &Report, section: usize) -> &[Vec<Value>] {
+        match &report.sections[section].blocks[..] {
+            [.., Block::Table(table)] | [.., Block::Table(table), Block::KeyValues(_)] => {
+                &table.rows
+            }
+            _ => panic!("no table in section {}", section),
+        }
+    }
+
+    #[test]
+    fn filter_sort_and_limit_rows() {
+        let query = Query::new()
+            .filter("Category", |v| {
+                *v == Value::text("Toys") || *v == Value::text("Tools")
+            })
+            .filter("Product", |v| *v != Value::text("A"))
+            .sort_by("Units", Order::Descending)
+            .limit(2);
+
+        let top = query.apply(&products()).unwrap();
+
+        let names: Vec<String> = rows(&top, 0).iter().map(|r| r[0].to_string()).collect();
+        assert_eq!(names, ["B", "D"]);
+        assert_eq!(
+            top.sections[0].blocks[0],
+            Block::Items(vec!["All products".into()])
+        );
+    }
+
+    #[test]
+    fn groups_become_sections_with_subtotals() {
+        let query = Query::new().group_by("Category").subtotal("Units");
+
+        let by_category = query.apply(&products()).unwrap();
+
+        let headings: Vec<_> = by_category
+            .sections
+            .iter()
+            .map(|s| s.heading.as_deref())
+            .collect();
+        assert_eq!(
+            headings,
+            [
+                Some("Catalog"),
+                Some("Category: Tools"),
+                Some("Category: Toys")
+            ]
+        );
+        assert_eq!(
+            rows(&by_category, 1),
+            [
+                vec![Value::text("A"), Value::Integer(5)],
+                vec![Value::text("C"), Value::Empty]
+            ]
+        );
+        assert_eq!(
+            by_category.sections[2].blocks[1],
+            Block::KeyValues(vec![("Total Units".into(), Value::Integer(19))])
+        );
+    }
+
+    #[test]
+    fn item_lists_are_filtered_and_sorted() {
+        let report = Report::new("Stock").section(Section::untitled().items([
+            "Bolts: 120 units",
+            "Nuts: 1,500 units",
+            "Out of stock",
+            "Washers: 80 units",
+            "Discontinued",
+        ]));
+
+        let query = Query::new()
+            .filter_items(|item| !item.starts_with("Out"))
+            .sort_items(Order::Descending);
+
+        assert_eq!(
+            query.apply(&report).unwrap().sections[0].blocks[0],
+            Block::Items(vec![
+                "Nuts: 1,500 units".into(),
+                "Bolts: 120 units".into(),
+                "Washers: 80 units".into(),
+                "Discontinued".into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn an_integer_total_too_large_becomes_a_decimal() {
+        let report = Report::new("Big").section(
+            Section::untitled().table(
+                Table::new(vec![Column::new("Units", ColumnType::Integer)])
+                    .row(vec![Value::Integer(i64::MAX)])
+                    .row(vec![Value::Integer(1)]),
+            ),
+        );
+
+        let totaled = Query::new().subtotal("Units").apply(&report).unwrap();
+
+        assert_eq!(
+            totaled.sections[0].blocks[1],
+            Block::KeyValues(vec![(
+                "Total Units".into(),
+                Value::Decimal(i64::MAX as f64 + 1.0)
+            )])
+        );
+    }
+
+    #[test]
+    fn unknown_or_non_numeric_columns_are_errors() {
+        let report = products();
+
+        assert_eq!(
+            Query::new()
+                .sort_by("Price", Order::Ascending)
+                .apply(&report),
+            Err(QueryError::UnknownColumn("Price".into()))
+        );
+        assert_eq!(
+            Query::new().subtotal("Product").apply(&report),
+            Err(QueryError::NotNumeric("Product".into()))
+        );
+    }
+}
@@ -0,0 +1,303 @@
+// src/registry.rs
+// Runtime catalog of the available formatters
+// Callers ask for a format by name, file name/extension or MIME type and get a &dyn ReportFormatter.
+// Adding a format means registering one more formatter, the callers don't change.
+
+use std::fmt;
+
+use crate::config::{ConfigError, Configurable, Options};
+use crate::formatters::ReportFormatter;
+use crate::formatters::csv::CsvFormatter;
+use crate::formatters::html::HtmlFormatter;
+use crate::formatters::json::JsonFormatter;
+use crate::formatters::markdown::MarkdownFormatter;
+use crate::formatters::pdf::PdfFormatter;
+use crate::formatters::svg::SvgChartFormatter;
+use crate::formatters::terminal::TerminalFormatter;
+use crate::formatters::text::TextFormatter;
+use crate::formatters::xlsx::XlsxFormatter;
+use crate::formatters::xml::XmlFormatter;
+use crate::formatters::yaml::YamlFormatter;
+
+#[derive(Debug)]
+pub enum RegistryError {
+    // Another formatter already answers to this name
+    DuplicateName(String),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::DuplicateName(name) => {
+                write!(f, "a formatter named \"{}\" is already registered", name)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FormatterRegistry {
+    formatters: Vec<Box<dyn ReportFormatter>>,
+}
+
+impl FormatterRegistry {
+    pub fn new() -> Self {
+        Self {
+            formatters: Vec::new(),
+        }
+    }
+
+    // A registry preloaded with the formatters shipped with this crate
+    pub fn with_builtin_formatters() -> Self {
+        let mut registry = Self::new();
+        let builtin: [Box<dyn ReportFormatter>; 11] = [
+            Box::new(TextFormatter::default()),
+            Box::new(HtmlFormatter::default()),
+            Box::new(PdfFormatter::default()),
+            Box::new(XmlFormatter::default()),
+            Box::new(JsonFormatter::default()),
+            Box::new(CsvFormatter::default()),
+            Box::new(YamlFormatter::default()),
+            Box::new(MarkdownFormatter::default()),
+            Box::new(SvgChartFormatter::default()),
+            Box::new(XlsxFormatter::default()),
+            // Colors and boxes on a terminal, plain text when redirected
+            Box::new(TerminalFormatter::detect()),
+        ];
+        for formatter in builtin {
+            registry
+                .register(formatter)
+                .expect("builtin formatter names are unique");
+        }
+        registry
+    }
+
+    // The builtin formatters, with the ones named in the config file built from their options
+    pub fn from_config(sections: Vec<Options>) -> Result<Self, ConfigError> {
+        let mut registry = Self::with_builtin_formatters();
+        for mut options in sections {
+            let build = CONFIGURABLE
+                .iter()
+                .find(|(name, _)| *name == options.formatter)
+                .map(|(_, build)| build)
+                .ok_or_else(|| {
+                    let message = format!("no formatter named \"{}\"", options.formatter);
+                    ConfigError::new(options.line, message)
+                })?;
+            let formatter = build(&mut options)?;
+            options.finish()?;
+            registry.replace(formatter);
+        }
+        Ok(registry)
+    }
+
+    // Takes the place of the formatter with the same name, or is added after the others
+    pub fn replace(&mut self, formatter: Box<dyn ReportFormatter>) {
+        match self
+            .formatters
+            .iter_mut()
+            .find(|f| f.name().eq_ignore_ascii_case(formatter.name()))
+        {
+            Some(existing) => *existing = formatter,
+            None => self.formatters.push(formatter),
+        }
+    }
+
+    // Names must be unique. Extensions and MIME types may be shared: the first registered wins.
+    pub fn register(&mut self, formatter: Box<dyn ReportFormatter>) -> Result<(), RegistryError> {
+        if self.by_name(formatter.name()).is_some() {
+            return Err(RegistryError::DuplicateName(formatter.name().to_string()));
+        }
+        self.formatters.push(formatter);
+        Ok(())
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&dyn ReportFormatter> {
+        self.find(|f| f.name().eq_ignore_ascii_case(name))
+    }
+
+    // Accepts a bare extension ("xml", ".xml") or a file name ("out/report.xml")
+    pub fn by_extension(&self, file_name: &str) -> Option<&dyn ReportFormatter> {
+        let extension = match file_name.rsplit_once('.') {
+            Some((_, extension)) => extension,
+            None => file_name,
+        };
+        self.find(|f| {
+            f.extensions()
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    // Parameters are ignored: "text/html; charset=utf-8" finds the HTML formatter
+    pub fn by_mime_type(&self, mime_type: &str) -> Option<&dyn ReportFormatter> {
+        let essence = mime_type.split(';').next().unwrap_or_default().trim();
+        self.find(|f| f.mime_type().eq_ignore_ascii_case(essence))
+    }
+
+    // Best effort lookup: a MIME type if it looks like one, otherwise a name, then a file name
+    pub fn lookup(&self, query: &str) -> Option<&dyn ReportFormatter> {
+        if query.contains('/')
+            && let Some(formatter) = self.by_mime_type(query)
+        {
+            return Some(formatter);
+        }
+        self.by_name(query).or_else(|| self.by_extension(query))
+    }
+
+    // All registered formatters, in registration order (e.g. to fill a drop-down list)
+    pub fn formats(&self) -> impl Iterator<Item = &dyn ReportFormatter> {
+        self.formatters.iter().map(|f| f.as_ref())
+    }
+
+    fn find(
+        &self,
+        predicate: impl Fn(&dyn ReportFormatter) -> bool,
+    ) -> Option<&dyn ReportFormatter> {
+        self.formats().find(|&f| predicate(f))
+    }
+}
+
+type Builder = fn(&mut Options) -> Result<Box<dyn ReportFormatter>, ConfigError>;
+
+// Every builtin formatter, by the name of its config section
+const CONFIGURABLE: [(&str, Builder); 11] = [
+    ("csv", build::<CsvFormatter>),
+    ("html", build::<HtmlFormatter>),
+    ("json", build::<JsonFormatter>),
+    ("markdown", build::<MarkdownFormatter>),
+    ("pdf", build::<PdfFormatter>),
+    ("svg", build::<SvgChartFormatter>),
+    ("terminal", build::<TerminalFormatter>),
+    ("text", build::<TextFormatter>),
+    ("xlsx", build::<XlsxFormatter>),
+    ("xml", build::<XmlFormatter>),
+    ("yaml", build::<YamlFormatter>),
+];
+
+fn build<F: Configurable + 'static>(
+    options: &mut Options,
+) -> Result<Box<dyn ReportFormatter>, ConfigError> {
+    Ok(Box::new(F::from_options(options)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_by_name_extension_and_mime_type() {
+        let registry = FormatterRegistry::with_builtin_formatters();
+
+        assert_eq!(registry.lookup("HTML").unwrap().name(), "html");
+        assert_eq!(registry.lookup("report.xml").unwrap().name(), "xml");
+        assert_eq!(registry.lookup("out/monthly.htm").unwrap().name(), "html");
+        assert_eq!(registry.lookup(".txt").unwrap().name(), "text");
+        assert_eq!(registry.lookup("application/pdf").unwrap().name(), "pdf");
+        assert_eq!(
+            registry.lookup("text/html; charset=utf-8").unwrap().name(),
+            "html"
+        );
+        assert_eq!(registry.lookup("data.yml").unwrap().name(), "yaml");
+        assert_eq!(registry.lookup("README.md").unwrap().name(), "markdown");
+        assert!(registry.lookup("application/msword").is_none());
+        assert!(registry.lookup("report.doc").is_none());
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let mut registry = FormatterRegistry::with_builtin_formatters();
+
+        let result = registry.register(Box::new(HtmlFormatter::default()));
+
+        assert!(matches!(result, Err(RegistryError::DuplicateName(name)) if name == "html"));
+        assert_eq!(registry.formats().count(), 11);
+    }
+
+    #[test]
+    fn configured_formatters_replace_the_builtin_ones() {
+        use crate::report::{Report, Section, Value};
+
+        let config = "[csv]\ndelimiter = ;\n\n[html]\ntheme = unstyled\n";
+        let registry =
+            FormatterRegistry::from_config(crate::config::parse(config).unwrap()).unwrap();
+
+        assert_eq!(registry.formats().count(), 11);
+        let report =
+            Report::new("T").section(Section::untitled().key_values([("A", Value::Integer(1))]));
+        let csv =
+            String::from_utf8(registry.by_name("csv").unwrap().format(&report).unwrap()).unwrap();
+        assert!(csv.starts_with("report;section;"));
+        let html =
+            String::from_utf8(registry.by_name("html").unwrap().format(&report).unwrap()).unwrap();
+        assert!(!html.contains("<style>"));
+
+        let error = |config: &str| {
+            FormatterRegistry::from_config(crate::config::parse(config).unwrap())
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            error("\n[word]"),
+            "config line 2: no formatter named \"word\""
+        );
+        assert_eq!(
+            error("[pdf]\nx = 1"),
+            "config line 2: [pdf] has no option \"x\""
+        );
+        assert_eq!(
+            error("[csv]\ndecimal_separator = ,"),
+            "config line 2: [csv] has no option \"decimal_separator\""
+        );
+        assert_eq!(
+            error("[csv]\ndelimeter = ;"),
+            "config line 2: [csv] has no option \"delimeter\""
+        );
+    }
+
+    #[test]
+    fn indent_and_date_style_come_from_the_config() {
+        use crate::report::{Date, Report, Section, Value};
+
+        let config = "[json]\nindent = 4\n\n[yaml]\nindent = 4\n\n[text]\ndate_style = long\n\
+                      decimal_locale = de\n\n[markdown]\ndate_style = dmy\n";
+        let registry =
+            FormatterRegistry::from_config(crate::config::parse(config).unwrap()).unwrap();
+
+        let report = Report::new("T").section(Section::untitled().key_values([
+            ("On", Value::Date(Date::new(2025, 1, 30))),
+            ("Total", Value::Decimal(7749.2)),
+        ]));
+        let output = |name: &str| {
+            String::from_utf8(registry.by_name(name).unwrap().format(&report).unwrap()).unwrap()
+        };
+        assert!(
+            output("json").starts_with("{\n    \"title\": \"T\",\n    \"sections\": [\n        {")
+        );
+        assert!(
+            output("yaml")
+                .contains("\n    - heading: null\n      blocks:\n          - key_values:\n")
+        );
+        assert!(output("xml").contains("\n  <title>T</title>"));
+        let text = output("text");
+        assert!(text.contains("On    : 30 January 2025"));
+        assert!(text.contains("Total : 7.749,20"));
+        assert!(output("markdown").contains("- **On:** 30/01/2025"));
+
+        let error = |config: &str| {
+            FormatterRegistry::from_config(crate::config::parse(config).unwrap())
+                .err()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            error("[xml]\nindent = 0"),
+            "config line 2: [xml] indent: invalid value \"0\", expected 1 to 8"
+        );
+        assert_eq!(
+            error("[html]\ndate_style = julian"),
+            "config line 2: [html] date_style: invalid value \"julian\", expected iso, dmy, mdy, long"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+// src/report.rs
+// The report model. It knows nothing about output formats.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::formatters::ReportFormatter;
+use crate::query::{Query, QueryError};
+
+// A report is a title followed by sections
+#[derive(Clone, PartialEq, Debug)]
+pub struct Report {
+    pub title: String,
+    pub sections: Vec<Section>,
+}
+
+impl Report {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn section(mut self, section: Section) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    // The report has a .generate() method which calls the .format() method of the formatter
+    // The call will be resolved at runtime (via a vtable)
+    // A formatter can fail (a template, a plugin...): the error is the caller's to handle
+    pub fn generate(&self, formatter: &dyn ReportFormatter) -> io::Result<Vec<u8>> {
+        formatter.format(self)
+    }
+
+    // A view of the same data (top N, by category...) to hand to any formatter
+    pub fn query(&self, query: &Query) -> Result<Report, QueryError> {
+        query.apply(self)
+    }
+
+    // Same, but streamed to a file, a socket... without building the output in memory
+    pub fn generate_to(
+        &self,
+        formatter: &dyn ReportFormatter,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        formatter.write(self, out)
+    }
+}
+
+// A section groups blocks under an optional heading
+#[derive(Clone, PartialEq, Debug)]
+pub struct Section {
+    pub heading: Option<String>,
+    pub blocks: Vec<Block>,
+}
+
+impl Section {
+    pub fn new(heading: impl Into<String>) -> Self {
+        Self {
+            heading: Some(heading.into()),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn untitled() -> Self {
+        Self {
+            heading: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn items<I, S>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.blocks
+            .push(Block::Items(items.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    pub fn table(mut self, table: Table) -> Self {
+        self.blocks.push(Block::Table(table));
+        self
+    }
+
+    pub fn key_values<I, K>(mut self, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Value)>,
+        K: Into<String>,
+    {
+        self.blocks.push(Block::KeyValues(
+            pairs.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        ));
+        self
+    }
+}
+
+// The building blocks every formatter must be able to render
+#[derive(Clone, PartialEq, Debug)]
+pub enum Block {
+    // A bullet list of free text
+    Items(Vec<String>),
+    // Rows of typed cells under named columns
+    Table(Table),
+    // Label / value pairs (totals, metadata...)
+    KeyValues(Vec<(String, Value)>),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(mut self, cells: Vec<Value>) -> Self {
+        assert_eq!(
+            cells.len(),
+            self.columns.len(),
+            "a table row must have one cell per column"
+        );
+        self.rows.push(cells);
+        self
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Column {
+    pub name: String,
+    pub kind: ColumnType,
+}
+
+impl Column {
+    pub fn new(name: impl Into<String>, kind: ColumnType) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Decimal,
+    Date,
+}
+
+impl ColumnType {
+    // Numbers read better right-aligned
+    pub fn is_numeric(self) -> bool {
+        matches!(self, ColumnType::Integer | ColumnType::Decimal)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnType::Text => "text",
+            ColumnType::Integer => "integer",
+            ColumnType::Decimal => "decimal",
+            ColumnType::Date => "date",
+        }
+    }
+}
+
+// A typed cell or key/value value
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Date(Date),
+}
+
+impl Value {
+    pub fn text(s: impl Into<String>) -> Self {
+        Value::Text(s.into())
+    }
+
+    pub fn kind(&self) -> Option<ColumnType> {
+        match self {
+            Value::Empty => None,
+            Value::Text(_) => Some(ColumnType::Text),
+            Value::Integer(_) => Some(ColumnType::Integer),
+            Value::Decimal(_) => Some(ColumnType::Decimal),
+            Value::Date(_) => Some(ColumnType::Date),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Empty => Ok(()),
+            Value::Text(s) => f.write_str(s),
+            Value::Integer(n) => write!(f, "{}", n),
+            // Two decimals, like an amount, unless that would round the value
+            Value::Decimal(x) if format!("{:.2}", x).parse() == Ok(*x) => write!(f, "{:.2}", x),
+            Value::Decimal(x) => write!(f, "{}", x),
+            Value::Date(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+// Items often carry a measure: "Label: 120 units" -> ("Label", 120, "units")
+pub fn parse_item(item: &str) -> Option<(&str, f64, &str)> {
+    let (label, rest) = item.split_once(':')?;
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | ',' | '-' | '+')))
+        .unwrap_or(rest.len());
+    let value = rest[..end].replace(',', "").parse().ok()?;
+    Some((label.trim(), value, rest[end..].trim()))
+}
+
+// A calendar date, rendered as ISO 8601 (YYYY-MM-DD)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Self {
+        assert!((1..=12).contains(&month), "month out of range");
+        Self::checked(year, month, day).expect("day out of range")
+    }
+
+    // None for a day the calendar doesn't have: 2025-02-29, 2025-04-31, month 13...
+    pub fn checked(year: i32, month: u8, day: u8) -> Option<Self> {
+        ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day))
+            .then_some(Self { year, month, day })
+    }
+
+    // The day `days` after 1970-01-01 (H. Hinnant's civil_from_days)
+    pub fn from_unix_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self::new(year as i32, month as u8, day as u8)
+    }
+
+    // Days since 1970-01-01, negative before (days_from_civil)
+    pub fn unix_days(self) -> i64 {
+        let (month, day) = (i64::from(self.month), i64::from(self.day));
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+// Gregorian calendar: February has 29 days every 4 years, except centuries not divisible by 400
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_the_calendar_does_not_have_are_rejected() {
+        assert_eq!(Date::checked(2025, 2, 28), Some(Date::new(2025, 2, 28)));
+        assert_eq!(Date::checked(2025, 2, 29), None);
+        assert!(Date::checked(2024, 2, 29).is_some());
+        assert!(Date::checked(2000, 2, 29).is_some());
+        assert_eq!(Date::checked(1900, 2, 29), None);
+        assert_eq!(Date::checked(2025, 4, 31), None);
+        assert_eq!(Date::checked(2025, 13, 1), None);
+        assert_eq!(Date::checked(2025, 1, 0), None);
+    }
+}
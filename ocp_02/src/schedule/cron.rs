@@ -0,0 +1,380 @@
+// src/schedule/cron.rs
+// Cron expressions: "minute hour day-of-month month day-of-week", times in UTC
+//
+//   0 6 * * *        every day at 06:00
+//   */15 8-18 * * 1-5  every quarter of an hour, office hours, Monday to Friday
+//   0 7 1 * *        the first of every month at 07:00
+//   @daily, @hourly, @weekly, @monthly, @yearly
+//
+// Each field takes *, a number, a range (1-5), a step (*/15, 8-18/2) or a list of those (1,15).
+// Months and weekdays also take their English abbreviations (jan, mon...). Sunday is 0 or 7.
+// As in cron, when both the day of the month and the day of the week are restricted,
+// a day matching either one is enough.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::report::Date;
+
+// A point in time to the minute, in UTC
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct DateTime {
+    pub date: Date,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl DateTime {
+    pub fn new(date: Date, hour: u8, minute: u8) -> Self {
+        assert!(hour < 24, "hour out of range");
+        assert!(minute < 60, "minute out of range");
+        Self { date, hour, minute }
+    }
+
+    // The current minute, from the system clock
+    pub fn now() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        Self::from_unix_minutes(seconds / 60)
+    }
+
+    pub fn from_unix_minutes(minutes: i64) -> Self {
+        let date = Date::from_unix_days(minutes.div_euclid(24 * 60));
+        let minute_of_day = minutes.rem_euclid(24 * 60);
+        Self::new(date, (minute_of_day / 60) as u8, (minute_of_day % 60) as u8)
+    }
+
+    pub fn unix_minutes(self) -> i64 {
+        self.date.unix_days() * 24 * 60 + i64::from(self.hour) * 60 + i64::from(self.minute)
+    }
+
+    // 0 for Sunday to 6 for Saturday (1970-01-01 was a Thursday)
+    fn weekday(self) -> u8 {
+        (self.date.unix_days() + 4).rem_euclid(7) as u8
+    }
+}
+
+// ISO 8601 without seconds: 2025-01-31 06:00
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:02}:{:02}", self.date, self.hour, self.minute)
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("\"{}\" is not a YYYY-MM-DD HH:MM time", s);
+        let (date, time) = s.trim().split_once([' ', 'T']).ok_or_else(invalid)?;
+        let mut date = date.splitn(3, '-').map(str::parse::<i32>);
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+            (date.next(), date.next(), date.next())
+        else {
+            return Err(invalid());
+        };
+        let (hour, minute) = time.split_once(':').ok_or_else(invalid)?;
+        let (Ok(hour), Ok(minute)) = (hour.parse::<u8>(), minute.parse::<u8>()) else {
+            return Err(invalid());
+        };
+        if hour > 23 || minute > 59 {
+            return Err(invalid());
+        }
+        let (Ok(month), Ok(day)) = (u8::try_from(month), u8::try_from(day)) else {
+            return Err(invalid());
+        };
+        let date = Date::checked(year, month, day).ok_or_else(invalid)?;
+        Ok(Self::new(date, hour, minute))
+    }
+}
+
+#[derive(Debug)]
+pub struct CronError {
+    pub expression: String,
+    pub message: String,
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid cron expression \"{}\": {}",
+            self.expression, self.message
+        )
+    }
+}
+
+impl std::error::Error for CronError {}
+
+// Each field is a set of allowed values, one bit per value
+#[derive(Clone, PartialEq, Debug)]
+pub struct CronExpression {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // Written as "*": doesn't take part in the day-of-month OR day-of-week rule
+    any_day: bool,
+    any_weekday: bool,
+}
+
+struct Field {
+    name: &'static str,
+    min: u8,
+    max: u8,
+    names: &'static [&'static str],
+}
+
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAY: Field = Field {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ],
+};
+// 7 is accepted for Sunday and folded onto 0
+const WEEKDAY: Field = Field {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+};
+
+// A schedule that never matches in this many years never will (e.g. "0 0 30 2 *")
+const SEARCH_YEARS: i32 = 8;
+
+impl CronExpression {
+    // The first matching minute strictly after `after`, None if there is none
+    pub fn next_after(&self, after: DateTime) -> Option<DateTime> {
+        let mut t = DateTime::from_unix_minutes(after.unix_minutes() + 1);
+        let last_year = after.date.year + SEARCH_YEARS;
+        while t.date.year <= last_year {
+            if !has(self.months, t.date.month) {
+                // First day of the next month
+                let (year, month) = match t.date.month {
+                    12 => (t.date.year + 1, 1),
+                    month => (t.date.year, month + 1),
+                };
+                t = DateTime::new(Date::new(year, month, 1), 0, 0);
+            } else if !self.matches_day(t) {
+                t = DateTime::new(Date::from_unix_days(t.date.unix_days() + 1), 0, 0);
+            } else if !has(self.hours, t.hour) {
+                t = DateTime::from_unix_minutes(t.unix_minutes() - i64::from(t.minute) + 60);
+            } else if !has(self.minutes, t.minute) {
+                t = DateTime::from_unix_minutes(t.unix_minutes() + 1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    // Every matching minute in (after, until], oldest first
+    pub fn between(&self, after: DateTime, until: DateTime) -> impl Iterator<Item = DateTime> + '_ {
+        std::iter::successors(self.next_after(after), move |&t| self.next_after(t))
+            .take_while(move |&t| t <= until)
+    }
+
+    fn matches_day(&self, t: DateTime) -> bool {
+        let day = has(self.days, t.date.day);
+        let weekday = has(self.weekdays, t.weekday());
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+fn has(set: u64, value: u8) -> bool {
+    set & (1 << value) != 0
+}
+
+impl FromStr for CronExpression {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let error = |message: String| CronError {
+            expression: expression.to_string(),
+            message,
+        };
+        let expanded = match expression.trim().to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            other if other.starts_with('@') => {
+                return Err(error(format!("unknown macro {}", other)));
+            }
+            other => other.to_string(),
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(error(format!("expected 5 fields, found {}", fields.len())));
+        };
+
+        let mut weekdays = parse_field(weekday, &WEEKDAY).map_err(error)?;
+        if has(weekdays, 7) {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            source: expression.trim().to_string(),
+            minutes: parse_field(minute, &MINUTE).map_err(error)?,
+            hours: parse_field(hour, &HOUR).map_err(error)?,
+            days: parse_field(day, &DAY).map_err(error)?,
+            months: parse_field(month, &MONTH).map_err(error)?,
+            weekdays,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+}
+
+impl fmt::Display for CronExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn parse_field(text: &str, field: &Field) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u8, String> {
+        let number = match field.names.iter().position(|name| *name == text) {
+            Some(index) => field.min + index as u8,
+            None => text
+                .parse()
+                .map_err(|_| format!("{}: \"{}\" is not a number", field.name, text))?,
+        };
+        if (field.min..=field.max).contains(&number) {
+            Ok(number)
+        } else {
+            Err(format!(
+                "{}: {} is not in {}-{}",
+                field.name, number, field.min, field.max
+            ))
+        }
+    };
+
+    let mut set = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u8>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("{}: invalid step \"{}\"", field.name, step)),
+            },
+            None => (part, 1),
+        };
+        let (first, last) = match range {
+            "*" => (field.min, field.max),
+            range => match range.split_once('-') {
+                Some((first, last)) => (value(first)?, value(last)?),
+                // "5/15" means from 5 to the end, every 15
+                None if step > 1 => (value(range)?, field.max),
+                None => {
+                    let single = value(range)?;
+                    (single, single)
+                }
+            },
+        };
+        if first > last {
+            return Err(format!("{}: empty range {}", field.name, range));
+        }
+        for v in (first..=last).step_by(usize::from(step)) {
+            set |= 1 << v;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime {
+        text.parse().unwrap()
+    }
+
+    fn cron(text: &str) -> CronExpression {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn next_matching_minutes() {
+        assert_eq!(
+            cron("0 6 * * *").next_after(at("2025-01-31 06:00")),
+            Some(at("2025-02-01 06:00"))
+        );
+        assert_eq!(
+            cron("*/15 8-18 * * mon-fri").next_after(at("2025-01-31 18:50")),
+            Some(at("2025-02-03 08:00"))
+        );
+        assert_eq!(
+            cron("@monthly").next_after(at("2024-12-15 10:00")),
+            Some(at("2025-01-01 00:00"))
+        );
+        assert_eq!(
+            cron("0 0 29 feb *").next_after(at("2025-01-01 00:00")),
+            Some(at("2028-02-29 00:00"))
+        );
+        assert_eq!(cron("0 0 30 2 *").next_after(at("2025-01-01 00:00")), None);
+        // The 13th or any Friday, Sunday written as 7
+        let either = cron("0 12 13 * 5,7");
+        assert_eq!(
+            either.next_after(at("2025-06-10 00:00")),
+            Some(at("2025-06-13 12:00"))
+        );
+        assert_eq!(
+            either.next_after(at("2025-06-13 12:00")),
+            Some(at("2025-06-15 12:00"))
+        );
+
+        let hourly: Vec<_> = cron("@hourly")
+            .between(at("2025-01-01 22:30"), at("2025-01-02 01:00"))
+            .collect();
+        assert_eq!(
+            hourly,
+            [
+                at("2025-01-01 23:00"),
+                at("2025-01-02 00:00"),
+                at("2025-01-02 01:00")
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        let error = |text: &str| text.parse::<CronExpression>().err().unwrap().message;
+
+        assert_eq!(error("0 6 * *"), "expected 5 fields, found 4");
+        assert_eq!(error("60 * * * *"), "minute: 60 is not in 0-59");
+        assert_eq!(error("* * * foo *"), "month: \"foo\" is not a number");
+        assert_eq!(error("*/0 * * * *"), "minute: invalid step \"0\"");
+        assert_eq!(error("* 18-8 * * *"), "hour: empty range 18-8");
+        assert!(at("2025-01-31 06:00").to_string() == "2025-01-31 06:00");
+        assert!("2025-13-01 00:00".parse::<DateTime>().is_err());
+        assert!("2025-02-31 06:00".parse::<DateTime>().is_err());
+        assert!("2024-02-29 06:00".parse::<DateTime>().is_ok());
+    }
+}
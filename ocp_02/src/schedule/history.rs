@@ -0,0 +1,182 @@
+// src/schedule/history.rs
+// What the scheduler did, kept in a local file so a restart knows which runs it missed
+// One line per run, tab separated and appended as it happens:
+//
+//   daily-sales	2025-02-03 06:00	written	/srv/reports/daily-sales/2025-02-03_0600.html
+//   daily-sales	2025-02-04 06:00	failed	sales: connection refused
+//   quarter-hour	2025-02-04 09:45	skipped	3 missed runs
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::cron::DateTime;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum RunStatus {
+    Written(PathBuf),
+    Failed(String),
+    // Missed runs dropped by the catch-up policy, counted up to this one
+    Skipped(usize),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RunRecord {
+    pub job: String,
+    // The time the run was due, not when it happened
+    pub scheduled: DateTime,
+    pub status: RunStatus,
+}
+
+impl fmt::Display for RunRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}: ", self.job, self.scheduled)?;
+        match &self.status {
+            RunStatus::Written(path) => write!(f, "written to {}", path.display()),
+            RunStatus::Failed(message) => write!(f, "failed, {}", message),
+            RunStatus::Skipped(1) => write!(f, "missed, skipped"),
+            RunStatus::Skipped(count) => write!(f, "{} missed runs skipped", count),
+        }
+    }
+}
+
+// In memory only by default; open() keeps it in a file as well
+#[derive(Default)]
+pub struct RunHistory {
+    path: Option<PathBuf>,
+    records: Vec<RunRecord>,
+}
+
+impl RunHistory {
+    // Reads the runs recorded so far, if the file exists
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let records = match fs::read_to_string(&path) {
+            Ok(text) => parse(&text, &path)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            path: Some(path),
+            records,
+        })
+    }
+
+    pub fn record(&mut self, record: RunRecord) -> io::Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line(&record))?;
+        }
+        self.records.push(record);
+        Ok(())
+    }
+
+    // The last run due for this job, whatever came of it
+    pub fn last(&self, job: &str) -> Option<&RunRecord> {
+        self.records.iter().rev().find(|r| r.job == job)
+    }
+
+    pub fn runs<'a>(&'a self, job: &'a str) -> impl Iterator<Item = &'a RunRecord> {
+        self.records.iter().filter(move |r| r.job == job)
+    }
+}
+
+fn line(record: &RunRecord) -> String {
+    // Tabs and line breaks would break the line format
+    let clean = |text: &str| text.replace(['\t', '\r', '\n'], " ");
+    let (status, detail) = match &record.status {
+        RunStatus::Written(path) => ("written", clean(&path.to_string_lossy())),
+        RunStatus::Failed(message) => ("failed", clean(message)),
+        RunStatus::Skipped(count) => ("skipped", format!("{} missed runs", count)),
+    };
+    format!(
+        "{}\t{}\t{}\t{}",
+        clean(&record.job),
+        record.scheduled,
+        status,
+        detail
+    )
+}
+
+fn parse(text: &str, path: &Path) -> io::Result<Vec<RunRecord>> {
+    let mut records = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |message: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} line {}: {}", path.display(), number + 1, message),
+            )
+        };
+        let fields: Vec<&str> = line.splitn(4, '\t').collect();
+        let [job, scheduled, status, detail] = fields[..] else {
+            return Err(invalid("expected 4 tab separated fields"));
+        };
+        let scheduled = scheduled.parse().map_err(|e: String| invalid(&e))?;
+        let status = match status {
+            "written" => RunStatus::Written(PathBuf::from(detail)),
+            "failed" => RunStatus::Failed(detail.to_string()),
+            "skipped" => {
+                let count = detail.split(' ').next().unwrap_or_default();
+                RunStatus::Skipped(count.parse().map_err(|_| invalid("invalid skip count"))?)
+            }
+            other => return Err(invalid(&format!("unknown status \"{}\"", other))),
+        };
+        records.push(RunRecord {
+            job: job.to_string(),
+            scheduled,
+            status,
+        });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("history_test_{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let at = |text: &str| text.parse::<DateTime>().unwrap();
+        let records = [
+            RunRecord {
+                job: "daily".to_string(),
+                scheduled: at("2025-02-03 06:00"),
+                status: RunStatus::Written(PathBuf::from("out/daily/2025-02-03_0600.html")),
+            },
+            RunRecord {
+                job: "daily".to_string(),
+                scheduled: at("2025-02-04 06:00"),
+                status: RunStatus::Failed("no\tdata\n".to_string()),
+            },
+            RunRecord {
+                job: "hourly".to_string(),
+                scheduled: at("2025-02-04 09:00"),
+                status: RunStatus::Skipped(3),
+            },
+        ];
+
+        let mut history = RunHistory::open(&path).unwrap();
+        for record in records.clone() {
+            history.record(record).unwrap();
+        }
+        let reopened = RunHistory::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reopened.runs("daily").count(), 2);
+        assert_eq!(reopened.last("hourly"), Some(&records[2]));
+        assert_eq!(
+            reopened.last("daily").unwrap().status,
+            RunStatus::Failed("no data ".to_string())
+        );
+        assert_eq!(reopened.last("weekly"), None);
+    }
+}
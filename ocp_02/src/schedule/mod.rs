@@ -0,0 +1,396 @@
+// src/schedule/mod.rs
+// Recurring reports: at each tick the scheduler finds the jobs that are due, asks their data
+// source for a fresh Report and renders it with their formatter into
+// <output>/<job>/<YYYY-MM-DD_HHMM>.<ext>
+//
+// Sources and formatters are looked up by name, so a job is just configuration: a new kind of
+// data or a new format never changes the scheduler.
+// Runs are kept in a RunHistory. When the program was down, or a tick came late, the job's
+// catch-up policy decides what happens to the runs it missed.
+
+pub mod cron;
+pub mod history;
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::batch::folder_name;
+use crate::registry::FormatterRegistry;
+use crate::report::Report;
+use cron::{CronError, CronExpression, DateTime};
+use history::{RunHistory, RunRecord, RunStatus};
+
+// Where a job's data comes from: a database query, an export dropped by another system...
+// `at` is the time the run was due, so a late or catch-up run reports on the right period
+pub trait DataSource {
+    fn load(&self, at: DateTime) -> Result<Report, Box<dyn Error>>;
+}
+
+impl<F: Fn(DateTime) -> Result<Report, Box<dyn Error>>> DataSource for F {
+    fn load(&self, at: DateTime) -> Result<Report, Box<dyn Error>> {
+        self(at)
+    }
+}
+
+// What to do with the runs missed while the scheduler wasn't running
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum CatchUp {
+    // Forget them, wait for the next run
+    Skip,
+    // One run for the most recent of them (a dashboard only needs the latest figures)
+    #[default]
+    Latest,
+    // Every one of them, oldest first (each run covers its own period), up to MAX_CATCH_UP
+    All,
+}
+
+// A run due this long ago is late, not missed
+const GRACE_MINUTES: i64 = 1;
+// Beyond this, the oldest missed runs are skipped even with CatchUp::All
+const MAX_CATCH_UP: usize = 100;
+
+pub struct Job {
+    name: String,
+    schedule: CronExpression,
+    source: String,
+    formatter: String,
+    catch_up: CatchUp,
+}
+
+impl Job {
+    pub fn new(
+        name: &str,
+        schedule: &str,
+        source: &str,
+        formatter: &str,
+    ) -> Result<Self, CronError> {
+        Ok(Self {
+            name: name.to_string(),
+            schedule: schedule.parse()?,
+            source: source.to_string(),
+            formatter: formatter.to_string(),
+            catch_up: CatchUp::default(),
+        })
+    }
+
+    pub fn catch_up(mut self, catch_up: CatchUp) -> Self {
+        self.catch_up = catch_up;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum ScheduleError {
+    // The name is the job's folder: "../x", "/srv" or "a:b" would write elsewhere, or nowhere
+    InvalidJobName(String),
+    DuplicateJob(String),
+    UnknownSource(String),
+    UnknownFormatter(String),
+    // The run happened but the history file could not be written, so it will run again
+    History { job: String, error: io::Error },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::InvalidJobName(name) => {
+                write!(
+                    f,
+                    "\"{}\" can't be a job name, it must be a folder name",
+                    name
+                )
+            }
+            ScheduleError::DuplicateJob(name) => {
+                write!(f, "a job named \"{}\" is already scheduled", name)
+            }
+            ScheduleError::UnknownSource(name) => write!(f, "no data source named \"{}\"", name),
+            ScheduleError::UnknownFormatter(name) => write!(f, "no formatter named \"{}\"", name),
+            ScheduleError::History { job, error } => {
+                write!(f, "could not record the run of {}: {}", job, error)
+            }
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+pub struct Scheduler {
+    formatters: FormatterRegistry,
+    sources: Vec<(String, Box<dyn DataSource>)>,
+    jobs: Vec<Job>,
+    output: PathBuf,
+    history: RunHistory,
+    // First tick: jobs that never ran start counting from there
+    started: Option<DateTime>,
+}
+
+impl Scheduler {
+    pub fn new(
+        formatters: FormatterRegistry,
+        output: impl Into<PathBuf>,
+        history: RunHistory,
+    ) -> Self {
+        Self {
+            formatters,
+            sources: Vec::new(),
+            jobs: Vec::new(),
+            output: output.into(),
+            history,
+            started: None,
+        }
+    }
+
+    // A later source with the same name replaces the earlier one
+    pub fn register_source(&mut self, name: &str, source: impl DataSource + 'static) {
+        self.sources.retain(|(n, _)| n != name);
+        self.sources.push((name.to_string(), Box::new(source)));
+    }
+
+    pub fn add_job(&mut self, job: Job) -> Result<(), ScheduleError> {
+        if folder_name(&job.name) != job.name {
+            return Err(ScheduleError::InvalidJobName(job.name));
+        }
+        if self.jobs.iter().any(|j| j.name == job.name) {
+            return Err(ScheduleError::DuplicateJob(job.name));
+        }
+        if !self.sources.iter().any(|(name, _)| *name == job.source) {
+            return Err(ScheduleError::UnknownSource(job.source));
+        }
+        if self.formatters.by_name(&job.formatter).is_none() {
+            return Err(ScheduleError::UnknownFormatter(job.formatter));
+        }
+        self.jobs.push(job);
+        Ok(())
+    }
+
+    pub fn history(&self) -> &RunHistory {
+        &self.history
+    }
+
+    // Runs every job due since its last run, up to `now`, and returns what was recorded
+    pub fn tick(&mut self, now: DateTime) -> Result<Vec<RunRecord>, ScheduleError> {
+        let started = *self
+            .started
+            .get_or_insert_with(|| DateTime::from_unix_minutes(now.unix_minutes() - 1));
+        let mut records = Vec::new();
+        for job in &self.jobs {
+            let after = self
+                .history
+                .last(&job.name)
+                .map_or(started, |r| r.scheduled);
+            let due: Vec<DateTime> = job.schedule.between(after, now).collect();
+            let Some(&latest) = due.last() else {
+                continue;
+            };
+            let on_time = now.unix_minutes() - latest.unix_minutes() <= GRACE_MINUTES;
+
+            let runs = match job.catch_up {
+                CatchUp::Skip if on_time => &due[due.len() - 1..],
+                CatchUp::Skip => &[][..],
+                CatchUp::Latest => &due[due.len() - 1..],
+                CatchUp::All => &due[due.len().saturating_sub(MAX_CATCH_UP)..],
+            };
+            let skipped = due.len() - runs.len();
+            if skipped > 0 {
+                // Recorded so the next tick doesn't see them as missed again
+                let last_skipped = due[skipped - 1];
+                records.push(RunRecord {
+                    job: job.name.clone(),
+                    scheduled: last_skipped,
+                    status: RunStatus::Skipped(skipped),
+                });
+            }
+            for &at in runs {
+                records.push(RunRecord {
+                    job: job.name.clone(),
+                    scheduled: at,
+                    status: self.run_job(job, at),
+                });
+            }
+        }
+        for record in &records {
+            self.history
+                .record(record.clone())
+                .map_err(|error| ScheduleError::History {
+                    job: record.job.clone(),
+                    error,
+                })?;
+        }
+        Ok(records)
+    }
+
+    // Ticks at the start of every minute until `stop` is set, for a long-running service
+    // Each run recorded goes to on_run; stops at the first history that can't be written
+    pub fn run(
+        &mut self,
+        stop: &AtomicBool,
+        mut on_run: impl FnMut(&RunRecord),
+    ) -> Result<(), ScheduleError> {
+        while !stop.load(Ordering::Relaxed) {
+            for record in self.tick(DateTime::now())? {
+                on_run(&record);
+            }
+            // Short sleeps until the next minute, so setting `stop` is noticed quickly
+            let next_minute = Instant::now() + Duration::from_secs(60 - seconds_into_minute());
+            while !stop.load(Ordering::Relaxed) && Instant::now() < next_minute {
+                thread::sleep(Duration::from_millis(500));
+            }
+        }
+        Ok(())
+    }
+
+    fn run_job(&self, job: &Job, at: DateTime) -> RunStatus {
+        let Some((_, source)) = self.sources.iter().find(|(name, _)| *name == job.source) else {
+            return RunStatus::Failed(ScheduleError::UnknownSource(job.source.clone()).to_string());
+        };
+        let Some(formatter) = self.formatters.by_name(&job.formatter) else {
+            return RunStatus::Failed(
+                ScheduleError::UnknownFormatter(job.formatter.clone()).to_string(),
+            );
+        };
+        let report = match source.load(at) {
+            Ok(report) => report,
+            Err(e) => return RunStatus::Failed(format!("{}: {}", job.source, e)),
+        };
+
+        let mut file = format!("{}_{:02}{:02}", at.date, at.hour, at.minute);
+        if let Some(extension) = formatter.extensions().first() {
+            file = format!("{}.{}", file, extension);
+        }
+        let path = self.output.join(&job.name).join(file);
+        let written = fs::create_dir_all(self.output.join(&job.name))
+            .and_then(|()| File::create(&path))
+            .and_then(|file| {
+                let mut out = BufWriter::new(file);
+                formatter.write(&report, &mut out)?;
+                out.flush()
+            });
+        match written {
+            Ok(()) => RunStatus::Written(path),
+            Err(e) => RunStatus::Failed(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
+
+fn seconds_into_minute() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Section;
+
+    #[test]
+    fn due_jobs_run_and_missed_runs_follow_the_policy() {
+        let output = std::env::temp_dir().join(format!("schedule_test_{}", std::process::id()));
+        let at = |text: &str| text.parse::<DateTime>().unwrap();
+        let mut scheduler = Scheduler::new(
+            FormatterRegistry::with_builtin_formatters(),
+            &output,
+            RunHistory::default(),
+        );
+        scheduler.register_source("sales", |at: DateTime| -> Result<Report, Box<dyn Error>> {
+            Ok(Report::new("Sales").section(Section::new(at.date.to_string())))
+        });
+        scheduler.register_source("down", |_| -> Result<Report, Box<dyn Error>> {
+            Err("connection refused".into())
+        });
+        let jobs = [
+            Job::new("daily", "0 6 * * *", "sales", "text")
+                .unwrap()
+                .catch_up(CatchUp::All),
+            Job::new("latest", "0 6 * * *", "sales", "json").unwrap(),
+            Job::new("skip", "0 6 * * *", "sales", "csv")
+                .unwrap()
+                .catch_up(CatchUp::Skip),
+            Job::new("broken", "0 * * * *", "down", "text").unwrap(),
+        ];
+        for job in jobs {
+            scheduler.add_job(job).unwrap();
+        }
+        assert!(matches!(
+            scheduler.add_job(Job::new("x", "@daily", "nowhere", "text").unwrap()),
+            Err(ScheduleError::UnknownSource(_))
+        ));
+        for name in ["../x", "/srv/x", "a\\b", "..", "", "tab\t"] {
+            assert!(matches!(
+                scheduler.add_job(Job::new(name, "@daily", "sales", "text").unwrap()),
+                Err(ScheduleError::InvalidJobName(_))
+            ));
+        }
+
+        // On time: everything due runs
+        let first = scheduler.tick(at("2025-02-03 06:00")).unwrap();
+        assert_eq!(first.len(), 4);
+        let written = output.join("daily").join("2025-02-03_0600.txt");
+        assert_eq!(first[0].status, RunStatus::Written(written.clone()));
+        assert!(
+            std::fs::read_to_string(&written)
+                .unwrap()
+                .contains("2025-02-03")
+        );
+        assert_eq!(
+            first[3].status,
+            RunStatus::Failed("down: connection refused".to_string())
+        );
+        assert!(scheduler.tick(at("2025-02-03 06:00")).unwrap().is_empty());
+
+        // Two days and a half later: the 4th and 5th were missed
+        let late = scheduler.tick(at("2025-02-05 18:30")).unwrap();
+        let runs = |job: &str| -> Vec<String> {
+            late.iter()
+                .filter(|r| r.job == job)
+                .map(|r| r.to_string())
+                .collect()
+        };
+        assert_eq!(runs("daily").len(), 2);
+        assert!(runs("daily")[1].starts_with("daily at 2025-02-05 06:00: written to"));
+        assert_eq!(
+            runs("latest")[0],
+            "latest at 2025-02-04 06:00: missed, skipped"
+        );
+        assert!(runs("latest")[1].starts_with("latest at 2025-02-05 06:00: written"));
+        assert_eq!(
+            runs("skip"),
+            ["skip at 2025-02-05 06:00: 2 missed runs skipped"]
+        );
+        assert_eq!(
+            runs("broken"),
+            [
+                "broken at 2025-02-05 17:00: 59 missed runs skipped",
+                "broken at 2025-02-05 18:00: failed, down: connection refused",
+            ]
+        );
+        assert_eq!(scheduler.history().runs("skip").count(), 2);
+
+        // A history that can't be written is the caller's problem, not a line on stderr
+        let history = output.join("history.tsv");
+        let mut scheduler = Scheduler::new(
+            FormatterRegistry::with_builtin_formatters(),
+            &output,
+            RunHistory::open(&history).unwrap(),
+        );
+        std::fs::create_dir(&history).unwrap();
+        scheduler.register_source("sales", |_| -> Result<Report, Box<dyn Error>> {
+            Ok(Report::new("Sales"))
+        });
+        scheduler
+            .add_job(Job::new("daily", "0 6 * * *", "sales", "text").unwrap())
+            .unwrap();
+        assert!(matches!(
+            scheduler.tick(at("2025-02-03 06:00")),
+            Err(ScheduleError::History { .. })
+        ));
+
+        std::fs::remove_dir_all(&output).unwrap();
+    }
+}
@@ -0,0 +1,26 @@
+{# Loaded at runtime by TemplateFormatter::from_file, edit freely without recompiling #}
+**{{ title | upper }}** — {{ items | length }} highlights
+
+{% for item in items %}
+{{ loop.index }}. {{ item }}
+{% endfor %}
+{% for section in sections %}
+{% if section.heading %}
+
+### {{ section.heading }}
+{% endif %}
+{% for table in section.tables %}
+
+{% for column in table.columns %}| {{ column.name }} {% endfor %}|
+{% for column in table.columns %}{% if column.numeric %}| ---: {% else %}| --- {% endif %}{% endfor %}|
+{% for row in table.rows %}
+{% for cell in row %}| {{ cell }} {% endfor %}|
+{% endfor %}
+{% endfor %}
+{% if section.entries %}
+
+{% endif %}
+{% for entry in section.entries %}
+- {{ entry.key }}: {{ entry.value }}
+{% endfor %}
+{% endfor %}
@@ -4,28 +4,237 @@
 // Generic Static Dispatch Based Solution - Reporter
 // =========================
 
+use std::fmt;
+
 // =========================
 // Abstractions
 // =========================
 
 // The report doesn't know about specific formats
+// A report is a title followed by sections
 pub struct Report {
-    title: String,
-    data: Vec<String>,
+    pub title: String,
+    pub sections: Vec<Section>,
 }
 
-// However, the report has a .generate() method which calls the .format() method of the formatter
-// The call will be resolve at compile time
 impl Report {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn section(mut self, section: Section) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    // The report has a .generate() method which calls the .format() method of the formatter
+    // The call will be resolve at compile time
     // Generic version using static dispatch
     pub fn generate<F: ReportFormatter>(&self, formatter: &F) -> String {
-        formatter.format(&self.title, &self.data)
+        formatter.format(self)
+    }
+}
+
+// A section groups blocks under an optional heading
+pub struct Section {
+    pub heading: Option<String>,
+    pub blocks: Vec<Block>,
+}
+
+impl Section {
+    pub fn new(heading: impl Into<String>) -> Self {
+        Self {
+            heading: Some(heading.into()),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn untitled() -> Self {
+        Self {
+            heading: None,
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn items<I, S>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.blocks
+            .push(Block::Items(items.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    pub fn table(mut self, table: Table) -> Self {
+        self.blocks.push(Block::Table(table));
+        self
+    }
+
+    pub fn key_values<I, K>(mut self, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Value)>,
+        K: Into<String>,
+    {
+        self.blocks.push(Block::KeyValues(
+            pairs.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        ));
+        self
+    }
+}
+
+// The building blocks every formatter must be able to render
+pub enum Block {
+    // A bullet list of free text
+    Items(Vec<String>),
+    // Rows of typed cells under named columns
+    Table(Table),
+    // Label / value pairs (totals, metadata...)
+    KeyValues(Vec<(String, Value)>),
+}
+
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(mut self, cells: Vec<Value>) -> Self {
+        assert_eq!(
+            cells.len(),
+            self.columns.len(),
+            "a table row must have one cell per column"
+        );
+        self.rows.push(cells);
+        self
+    }
+}
+
+pub struct Column {
+    pub name: String,
+    pub kind: ColumnType,
+}
+
+impl Column {
+    pub fn new(name: impl Into<String>, kind: ColumnType) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Decimal,
+    Date,
+}
+
+impl ColumnType {
+    // Numbers read better right-aligned
+    pub fn is_numeric(self) -> bool {
+        matches!(self, ColumnType::Integer | ColumnType::Decimal)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnType::Text => "text",
+            ColumnType::Integer => "integer",
+            ColumnType::Decimal => "decimal",
+            ColumnType::Date => "date",
+        }
+    }
+}
+
+// A typed cell or key/value value
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Date(Date),
+}
+
+impl Value {
+    pub fn text(s: impl Into<String>) -> Self {
+        Value::Text(s.into())
+    }
+
+    pub fn kind(&self) -> Option<ColumnType> {
+        match self {
+            Value::Empty => None,
+            Value::Text(_) => Some(ColumnType::Text),
+            Value::Integer(_) => Some(ColumnType::Integer),
+            Value::Decimal(_) => Some(ColumnType::Decimal),
+            Value::Date(_) => Some(ColumnType::Date),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Empty => Ok(()),
+            Value::Text(s) => f.write_str(s),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Decimal(x) => write!(f, "{:.2}", x),
+            Value::Date(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+// A calendar date, rendered as ISO 8601 (YYYY-MM-DD)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Self {
+        assert!((1..=12).contains(&month), "month out of range");
+        assert!(
+            (1..=days_in_month(year, month)).contains(&day),
+            "day out of range"
+        );
+        Self { year, month, day }
+    }
+}
+
+// Gregorian calendar: February has 29 days every 4 years, except centuries not divisible by 400
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
     }
 }
 
 // If a type wants to have the ReportFormatter trait it must implement the .format() method
+// It receives the whole structured report and renders each block natively
 pub trait ReportFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String;
+    fn format(&self, report: &Report) -> String;
 }
 
 // =========================
@@ -36,69 +245,361 @@ pub trait ReportFormatter {
 pub struct TextFormatter;
 
 impl ReportFormatter for TextFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String {
-        let mut output = format!("=== {} ===", title);
-        for item in data {
-            output.push_str(&format!("\n- {}", item));
+    fn format(&self, report: &Report) -> String {
+        let mut output = format!("=== {} ===", report.title);
+        for section in &report.sections {
+            if let Some(heading) = &section.heading {
+                output.push_str(&format!("\n\n--- {} ---", heading));
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        for item in items {
+                            output.push_str(&format!("\n- {}", item));
+                        }
+                    }
+                    Block::Table(table) => output.push_str(&text_table(table)),
+                    Block::KeyValues(pairs) => output.push_str(&text_key_values(pairs)),
+                }
+            }
         }
         output
     }
 }
 
+// Columns are padded to their widest cell, numbers are right-aligned
+fn text_table(table: &Table) -> String {
+    let cells: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| row.iter().map(ToString::to_string).collect())
+        .collect();
+
+    let widths: Vec<usize> = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(column.name.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |values: &mut dyn Iterator<Item = &str>| -> String {
+        let padded: Vec<String> = values
+            .zip(&table.columns)
+            .zip(&widths)
+            .map(|((value, column), &width)| {
+                if column.kind.is_numeric() {
+                    format!("{:>width$}", value)
+                } else {
+                    format!("{:<width$}", value)
+                }
+            })
+            .collect();
+        format!("\n{}", padded.join("  ").trim_end())
+    };
+
+    let mut output = line(&mut table.columns.iter().map(|c| c.name.as_str()));
+    let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+    output.push_str(&format!("\n{}", rule.join("  ")));
+    for row in &cells {
+        output.push_str(&line(&mut row.iter().map(String::as_str)));
+    }
+    output
+}
+
+fn text_key_values(pairs: &[(String, Value)]) -> String {
+    let width = pairs
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut output = String::new();
+    for (key, value) in pairs {
+        output.push_str(&format!("\n{:<width$} : {}", key, value));
+    }
+    output
+}
+
 // HTML output (same structure as initial example)
 pub struct HtmlFormatter;
 
 impl ReportFormatter for HtmlFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String {
-        let mut output = format!("<h1>{}</h1>\n<ul>\n", title);
-        for item in data {
-            output.push_str(&format!("  <li>{}</li>\n", item));
+    fn format(&self, report: &Report) -> String {
+        let mut output = format!("<h1>{}</h1>\n", report.title);
+        for section in &report.sections {
+            if let Some(heading) = &section.heading {
+                output.push_str(&format!("<h2>{}</h2>\n", heading));
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        output.push_str("<ul>\n");
+                        for item in items {
+                            output.push_str(&format!("  <li>{}</li>\n", item));
+                        }
+                        output.push_str("</ul>\n");
+                    }
+                    Block::Table(table) => output.push_str(&html_table(table)),
+                    Block::KeyValues(pairs) => output.push_str(&html_key_values(pairs)),
+                }
+            }
         }
-        output.push_str("</ul>");
+        output.truncate(output.trim_end().len());
         output
     }
 }
 
-// Fake PDF output (same spirit as before)
+fn html_table(table: &Table) -> String {
+    let mut output = String::from("<table>\n  <thead>\n    <tr>");
+    for column in &table.columns {
+        output.push_str(&format!("<th>{}</th>", column.name));
+    }
+    output.push_str("</tr>\n  </thead>\n  <tbody>\n");
+    for row in &table.rows {
+        output.push_str("    <tr>");
+        for (value, column) in row.iter().zip(&table.columns) {
+            if column.kind.is_numeric() {
+                output.push_str(&format!("<td class=\"num\">{}</td>", value));
+            } else {
+                output.push_str(&format!("<td>{}</td>", value));
+            }
+        }
+        output.push_str("</tr>\n");
+    }
+    output.push_str("  </tbody>\n</table>\n");
+    output
+}
+
+fn html_key_values(pairs: &[(String, Value)]) -> String {
+    let mut output = String::from("<dl>\n");
+    for (key, value) in pairs {
+        output.push_str(&format!("  <dt>{}</dt><dd>{}</dd>\n", key, value));
+    }
+    output.push_str("</dl>\n");
+    output
+}
+
+// PDF output: a minimal PDF 1.4 document, A4 pages of standard fonts
+// The title and headings are in Helvetica Bold, the blocks in Courier laid out as in the
+// text output, so table columns stay aligned. Kept to ASCII so the document fits a String.
 pub struct PdfFormatter;
 
+const PDF_LINES_PER_PAGE: usize = 50;
+
 impl ReportFormatter for PdfFormatter {
-    fn format(&self, title: &str, _data: &[String]) -> String {
-        format!("PDF: {} [binary data]", title)
+    fn format(&self, report: &Report) -> String {
+        let mut lines = vec![("/F1 14", report.title.clone())];
+        for section in &report.sections {
+            if let Some(heading) = &section.heading {
+                lines.push(("/F2 10", String::new()));
+                lines.push(("/F1 12", heading.clone()));
+            }
+            for block in &section.blocks {
+                let text = match block {
+                    Block::Items(items) => {
+                        items.iter().map(|item| format!("\n- {}", item)).collect()
+                    }
+                    Block::Table(table) => text_table(table),
+                    Block::KeyValues(pairs) => text_key_values(pairs),
+                };
+                lines.extend(
+                    text.lines()
+                        .skip(1)
+                        .map(|line| ("/F2 10", line.to_string())),
+                );
+            }
+        }
+
+        // Objects 1 to 4, then a content stream and a page for each page
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            String::new(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>".to_string(),
+        ];
+        let mut pages = Vec::new();
+        for page in lines.chunks(PDF_LINES_PER_PAGE) {
+            let mut content = String::from("BT 15 TL 56 786 Td");
+            for (font, line) in page {
+                content.push_str(&format!("\n{} Tf ({}) Tj T*", font, pdf_text(line)));
+            }
+            content.push_str("\nET");
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                content.len(),
+                content
+            ));
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                objects.len()
+            ));
+            pages.push(format!("{} 0 R", objects.len()));
+        }
+        objects[1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            pages.join(" "),
+            pages.len()
+        );
+
+        // The cross-reference table gives the byte offset of each object
+        let mut output = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(output.len());
+            output.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref = output.len();
+        output.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            output.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        output.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF",
+            objects.len() + 1,
+            xref
+        ));
+        output
     }
 }
 
+// A PDF string literal: parentheses and backslashes escaped, non-ASCII characters as '?'
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
 // New XML output - extension without modification
 pub struct XmlFormatter;
 
 impl ReportFormatter for XmlFormatter {
-    fn format(&self, title: &str, data: &[String]) -> String {
+    fn format(&self, report: &Report) -> String {
         let mut output = String::from("<report>\n");
-        output.push_str(&format!("  <title>{}</title>\n", title));
-        output.push_str("  <items>\n");
+        output.push_str(&format!("  <title>{}</title>\n", report.title));
 
-        for item in data {
-            output.push_str(&format!("    <item>{}</item>\n", item));
+        for section in &report.sections {
+            match &section.heading {
+                Some(heading) => output.push_str(&format!("  <section heading=\"{}\">\n", heading)),
+                None => output.push_str("  <section>\n"),
+            }
+            for block in &section.blocks {
+                match block {
+                    Block::Items(items) => {
+                        output.push_str("    <items>\n");
+                        for item in items {
+                            output.push_str(&format!("      <item>{}</item>\n", item));
+                        }
+                        output.push_str("    </items>\n");
+                    }
+                    Block::Table(table) => output.push_str(&xml_table(table)),
+                    Block::KeyValues(pairs) => output.push_str(&xml_key_values(pairs)),
+                }
+            }
+            output.push_str("  </section>\n");
         }
 
-        output.push_str("  </items>\n</report>");
+        output.push_str("</report>");
         output
     }
 }
 
+fn xml_table(table: &Table) -> String {
+    let mut output = String::from("    <table>\n      <columns>\n");
+    for column in &table.columns {
+        output.push_str(&format!(
+            "        <column name=\"{}\" type=\"{}\"/>\n",
+            column.name,
+            column.kind.name()
+        ));
+    }
+    output.push_str("      </columns>\n");
+    for row in &table.rows {
+        output.push_str("      <row>");
+        for value in row {
+            output.push_str(&format!("<cell>{}</cell>", value));
+        }
+        output.push_str("</row>\n");
+    }
+    output.push_str("    </table>\n");
+    output
+}
+
+fn xml_key_values(pairs: &[(String, Value)]) -> String {
+    let mut output = String::from("    <entries>\n");
+    for (key, value) in pairs {
+        match value.kind() {
+            Some(kind) => output.push_str(&format!(
+                "      <entry key=\"{}\" type=\"{}\">{}</entry>\n",
+                key,
+                kind.name(),
+                value
+            )),
+            None => output.push_str(&format!("      <entry key=\"{}\"/>\n", key)),
+        }
+    }
+    output.push_str("    </entries>\n");
+    output
+}
+
 // =========================
 // Usage
 // =========================
 
 fn main() {
-    let report = Report {
-        title: "Monthly Sales".to_string(),
-        data: vec![
-            "Product A: 120 units".to_string(),
-            "Product B: 98 units".to_string(),
-            "Product C: 143 units".to_string(),
-        ],
-    };
+    let report = Report::new("Monthly Sales")
+        .section(Section::untitled().items([
+            "Product A: 120 units",
+            "Product B: 98 units",
+            "Product C: 143 units",
+        ]))
+        .section(
+            Section::new("Sales by product").table(
+                Table::new(vec![
+                    Column::new("Product", ColumnType::Text),
+                    Column::new("Units", ColumnType::Integer),
+                    Column::new("Revenue", ColumnType::Decimal),
+                    Column::new("Last order", ColumnType::Date),
+                ])
+                .row(vec![
+                    Value::text("Product A"),
+                    Value::Integer(120),
+                    Value::Decimal(2_398.80),
+                    Value::Date(Date::new(2025, 1, 30)),
+                ])
+                .row(vec![
+                    Value::text("Product B"),
+                    Value::Integer(98),
+                    Value::Decimal(4_890.20),
+                    Value::Date(Date::new(2025, 1, 28)),
+                ])
+                .row(vec![
+                    Value::text("Product C"),
+                    Value::Integer(143),
+                    Value::Decimal(1_429.99),
+                    Value::Empty,
+                ]),
+            ),
+        )
+        .section(Section::new("Summary").key_values([
+            ("Period", Value::text("January 2025")),
+            ("Total units", Value::Integer(361)),
+            ("Total revenue", Value::Decimal(8_718.99)),
+            ("Generated on", Value::Date(Date::new(2025, 2, 1))),
+        ]));
 
     println!("\n--- TEXT ---\n{}", report.generate(&TextFormatter));
     println!("\n--- HTML ---\n{}", report.generate(&HtmlFormatter));
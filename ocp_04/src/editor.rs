@@ -0,0 +1,308 @@
+// src/editor.rs
+// The text being edited, what the processings report about it, and what a run does when
+// one of them fails. Whatever the Processing trait looks like, this part doesn't change:
+// ocp_05, ocp_06 and ocp_07 include this very file (#[path = ".../editor.rs"] mod editor;).
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::spell_checker::SpellChecker;
+
+// Here the content of the Editor is a String, with what the processings found in it
+// Processings report through the diagnostics: the text only ever holds the user's text
+pub struct EditorContent {
+    pub content: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl EditorContent {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    // Byte range in the content as it was when reported, None when it is about the whole text
+    // A later processing that changes the text (LowerCase, a correction) is not accounted for:
+    // the range may then point at other characters
+    pub range: Option<Range<usize>>,
+    pub processing: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, processing: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            range: None,
+            processing: processing.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn at(mut self, range: Range<usize>) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+// warning SpellChecker 24..30: unknown word "cheker"
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<7} {}", self.severity, self.processing)?;
+        if let Some(range) = &self.range {
+            write!(f, " {}..{}", range.start, range.end)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+// What one run found, by severity
+#[derive(Default, PartialEq, Debug)]
+pub struct Summary {
+    pub infos: usize,
+    pub warnings: usize,
+    pub errors: usize,
+}
+
+impl Summary {
+    fn of(diagnostics: &[Diagnostic]) -> Self {
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+        Self {
+            infos: count(Severity::Info),
+            warnings: count(Severity::Warning),
+            errors: count(Severity::Error),
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} error(s), {} warning(s), {} info(s)",
+            self.errors, self.warnings, self.infos
+        )
+    }
+}
+
+// Why a processing could not do its job
+// Whether the run goes on depends on the TxtProcessor's OnError
+#[derive(Debug)]
+pub struct ProcessingError {
+    pub processing: String,
+    pub message: String,
+}
+
+impl ProcessingError {
+    pub fn new(processing: &str, message: impl Into<String>) -> Self {
+        Self {
+            processing: processing.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::new(Severity::Error, &self.processing, self.message.clone())
+    }
+}
+
+impl fmt::Display for ProcessingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed: {}", self.processing, self.message)
+    }
+}
+
+impl std::error::Error for ProcessingError {}
+
+// What a run does when one of its processings fails
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum OnError {
+    // Stop there, the content keeps what the earlier processings did
+    #[default]
+    Abort,
+    // Report the failure as an error diagnostic and go on with the next processing
+    Skip,
+    // Stop there and give the content back as it was before the run
+    Rollback,
+}
+
+impl OnError {
+    // Ok when the run can go on
+    pub fn recover(
+        self,
+        context: &mut EditorContent,
+        error: ProcessingError,
+    ) -> Result<(), ProcessingError> {
+        if self != OnError::Skip {
+            return Err(error);
+        }
+        context.report(error.diagnostic());
+        Ok(())
+    }
+
+    // A whole run under this policy: apply() runs the processings, calling recover() when one
+    // fails. Returns what this run found, the diagnostics of earlier runs are left out.
+    pub fn run(
+        self,
+        content: &mut EditorContent,
+        apply: impl FnOnce(&mut EditorContent) -> Result<(), ProcessingError>,
+    ) -> Result<Summary, ProcessingError> {
+        let before = content.diagnostics.len();
+        // Only kept when there may be something to roll back to
+        let original = (self == OnError::Rollback).then(|| content.content.clone());
+        if let Err(error) = apply(content) {
+            if let Some(original) = original {
+                content.content = original;
+                // Their ranges point into the text thrown away
+                content.diagnostics.truncate(before);
+            }
+            content.report(error.diagnostic());
+            return Err(error);
+        }
+        Ok(Summary::of(&content.diagnostics[before..]))
+    }
+}
+
+// What SpellChecker finds, reported under the name of the processing running it
+// A warning for each unknown word, with the closest ones, an info for each word corrected
+pub fn check_spelling(checker: &SpellChecker, context: &mut EditorContent, processing: &str) {
+    for misspelling in checker.correct(&mut context.content) {
+        let word = &misspelling.word;
+        let (severity, message) = match &misspelling.correction {
+            Some(correction) => (
+                Severity::Info,
+                format!("\"{}\" corrected to \"{}\"", word, correction),
+            ),
+            None if misspelling.suggestions.is_empty() => {
+                (Severity::Warning, format!("unknown word \"{}\"", word))
+            }
+            None => {
+                let closest: Vec<&str> = misspelling
+                    .suggestions
+                    .iter()
+                    .map(|s| s.word.as_str())
+                    .collect();
+                let message = format!(
+                    "unknown word \"{}\", did you mean {}?",
+                    word,
+                    closest.join(", ")
+                );
+                (Severity::Warning, message)
+            }
+        };
+        let diagnostic = Diagnostic::new(severity, processing, message);
+        context.report(diagnostic.at(misspelling.range));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lowercase(content: &mut EditorContent) -> Result<(), ProcessingError> {
+        content.content = content.content.to_lowercase();
+        content.report(Diagnostic::new(
+            Severity::Info,
+            "LowerCase",
+            "text lowercased",
+        ));
+        Ok(())
+    }
+
+    fn fail(_: &mut EditorContent) -> Result<(), ProcessingError> {
+        Err(ProcessingError::new("MaxLength", "too long"))
+    }
+
+    // lowercase, fail, lowercase again, under the policy
+    fn run(on_error: OnError) -> (EditorContent, Result<Summary, ProcessingError>) {
+        let mut content = EditorContent::new("TEXT");
+        content.report(Diagnostic::new(
+            Severity::Warning,
+            "Earlier",
+            "from an earlier run",
+        ));
+        let result = on_error.run(&mut content, |content| {
+            lowercase(content)?;
+            fail(content).or_else(|error| on_error.recover(content, error))?;
+            lowercase(content)
+        });
+        (content, result)
+    }
+
+    #[test]
+    fn abort_keeps_what_was_done() {
+        let (content, result) = run(OnError::Abort);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "MaxLength failed: too long"
+        );
+        assert_eq!(content.content, "text");
+        let diagnostics: Vec<String> = content.diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            diagnostics,
+            [
+                "warning Earlier: from an earlier run",
+                "info    LowerCase: text lowercased",
+                "error   MaxLength: too long"
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_reports_and_goes_on() {
+        let (content, result) = run(OnError::Skip);
+
+        assert_eq!(
+            result.unwrap(),
+            Summary {
+                infos: 2,
+                warnings: 0,
+                errors: 1
+            }
+        );
+        assert_eq!(content.diagnostics.len(), 4);
+    }
+
+    #[test]
+    fn rollback_gives_the_text_back() {
+        let (content, result) = run(OnError::Rollback);
+
+        assert!(result.is_err());
+        assert_eq!(content.content, "TEXT");
+        let severities: Vec<Severity> = content.diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(severities, [Severity::Warning, Severity::Error]);
+    }
+}
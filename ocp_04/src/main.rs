@@ -4,33 +4,62 @@
 // Naïve Solution - Txt Processor with Plugins
 // =========================
 
+// The spell checker itself (dictionary, suggestions) lives in spell_checker.rs
+// The text, its diagnostics and what a run does on errors live in editor.rs
+
+mod editor;
+mod spell_checker;
+
+use editor::{
+    Diagnostic, EditorContent, OnError, ProcessingError, Severity, Summary, check_spelling,
+};
+use spell_checker::{Dictionary, SpellChecker};
+
 // =========================
 // Abstractions
 // =========================
 
 // A TxtProcessor knows nothing about the processing nor the text
-pub struct TxtProcessor;
+#[derive(Default)]
+pub struct TxtProcessor {
+    on_error: OnError,
+}
 
 impl TxtProcessor {
-    pub fn run<P1: Processing, P2: Processing>(
+    pub fn on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    // A third processing meant a third parameter, and one more step below
+    pub fn run<P1: Processing, P2: Processing, P3: Processing>(
         &self,
         processing1: &P1,
         processing2: &P2,
+        processing3: &P3,
         content: &mut EditorContent,
-    ) {
-        processing1.apply(content);
-        processing2.apply(content);
+    ) -> Result<Summary, ProcessingError> {
+        self.on_error.run(content, |content| {
+            self.step(processing1, content)
+                .and_then(|()| self.step(processing2, content))
+                .and_then(|()| self.step(processing3, content))
+        })
     }
-}
 
-// Here the content of the Editor is just a String
-pub struct EditorContent {
-    pub content: String,
+    fn step<P: Processing>(
+        &self,
+        processing: &P,
+        content: &mut EditorContent,
+    ) -> Result<(), ProcessingError> {
+        processing
+            .apply(content)
+            .or_else(|error| self.on_error.recover(content, error))
+    }
 }
 
 // If a type wants to have the Processing trait it must implement the .apply() method
 pub trait Processing {
-    fn apply(&self, context: &mut EditorContent);
+    fn apply(&self, context: &mut EditorContent) -> Result<(), ProcessingError>;
 }
 
 // =========================
@@ -41,18 +70,40 @@ pub trait Processing {
 pub struct LowerCase;
 
 impl Processing for LowerCase {
-    fn apply(&self, context: &mut EditorContent) {
-        context.content = context.content.to_lowercase();
-        context.content.push_str("\n[LowerCase OK]");
+    fn apply(&self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        let lowercase = context.content.to_lowercase();
+        if lowercase != context.content {
+            context.content = lowercase;
+            context.report(Diagnostic::new(
+                Severity::Info,
+                "LowerCase",
+                "text lowercased",
+            ));
+        }
+        Ok(())
     }
 }
 
 // SpellChecker processing
-pub struct SpellChecker;
-
 impl Processing for SpellChecker {
-    fn apply(&self, context: &mut EditorContent) {
-        context.content.push_str("\n[SpellChecker OK]");
+    fn apply(&self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        check_spelling(self, context, "SpellChecker");
+        Ok(())
+    }
+}
+
+// Length limit, as for a post or a commit subject
+// It fails on a text too long, so it goes after the processings that change the text
+pub struct MaxLength(pub usize);
+
+impl Processing for MaxLength {
+    fn apply(&self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        let length = context.content.chars().count();
+        if length > self.0 {
+            let message = format!("{} characters, the limit is {}", length, self.0);
+            return Err(ProcessingError::new("MaxLength", message));
+        }
+        Ok(())
     }
 }
 
@@ -61,17 +112,37 @@ impl Processing for SpellChecker {
 // =========================
 
 fn main() {
-    let processor = TxtProcessor;
+    let mut processor = TxtProcessor::default();
+
+    // Hunspell dictionary: en.dic and the affix rules of en.aff
+    let dictionary =
+        match Dictionary::load(concat!(env!("CARGO_MANIFEST_DIR"), "/dictionaries/en.dic")) {
+            Ok(dictionary) => dictionary,
+            Err(e) => {
+                eprintln!("cannot load the dictionary: {}", e);
+                return;
+            }
+        };
+    println!("{} words in the dictionary", dictionary.len());
 
     let lowercase = LowerCase;
-    let spell_checker = SpellChecker;
-
-    let mut context = EditorContent {
-        content: String::from("HELLO WORLD"),
-    };
-
-    processor.run(&lowercase, &spell_checker, &mut context);
-
-    println!("--- FINAL CONTENT ---");
-    println!("{}", context.content);
+    let spell_checker = SpellChecker::new(dictionary).auto_correct(true);
+
+    let max_length = MaxLength(72);
+
+    let text = "HELLO WROLD, THIS SPELL CHEKER FLAGS MISSPELED WORDZ AND SUGESTS A FIX";
+    for on_error in [OnError::Abort, OnError::Skip, OnError::Rollback] {
+        processor = processor.on_error(on_error);
+        let mut context = EditorContent::new(text);
+
+        println!("--- ON ERROR: {:?} ---", on_error);
+        match processor.run(&lowercase, &spell_checker, &max_length, &mut context) {
+            Ok(summary) => println!("Run complete: {}", summary),
+            Err(e) => println!("Run stopped, {}", e),
+        }
+        println!("{}", context.content);
+        for diagnostic in &context.diagnostics {
+            println!("{}", diagnostic);
+        }
+    }
 }
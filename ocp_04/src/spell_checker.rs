@@ -0,0 +1,576 @@
+// src/spell_checker.rs
+// A dictionary based spell checker
+// Words are looked up in a word list, either plain (one word per line) or Hunspell style:
+// a .dic file of stems with affix flags, and an .aff file with the prefix and suffix rules
+// that expand each stem into its forms (work/S -> work, works).
+// A misspelled word is reported with its byte range in the text and the dictionary words
+// closest to it, ranked by edit distance. When there is a single best one, the word can be
+// corrected automatically.
+// ocp_05, ocp_06 and ocp_07 include this very file (#[path = ".../spell_checker.rs"] mod
+// spell_checker;) and load the dictionaries next to it: there is no other copy.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct DictionaryError {
+    // 1-based, 0 when the position is unknown
+    pub line: usize,
+    pub message: String,
+}
+
+impl DictionaryError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for DictionaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "line {}: {}", line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for DictionaryError {}
+
+// =========================
+// Dictionary
+// =========================
+
+// Every accepted word form, lowercased
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            words: words
+                .into_iter()
+                .map(|w| w.as_ref().trim().to_lowercase())
+                .filter(|w| !w.is_empty())
+                .collect(),
+        }
+    }
+
+    // One word per line, '#' starts a comment line
+    pub fn parse_word_list(text: &str) -> Self {
+        Self::from_words(
+            text.lines()
+                .filter(|line| !line.trim_start().starts_with('#')),
+        )
+    }
+
+    pub fn parse_hunspell(dic: &str, aff: &str) -> Result<Self, DictionaryError> {
+        let affixes = Affixes::parse(aff)?;
+        let mut words = HashSet::new();
+        // The first line is the (approximate) number of words
+        for (number, line) in dic.lines().enumerate().skip(1) {
+            // Anything after the first whitespace is morphological data
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (stem, flags) = match entry.split_once('/') {
+                Some((stem, flags)) => (stem, affixes.flags(flags, number + 1)?),
+                None => (entry, Vec::new()),
+            };
+            words.extend(affixes.expand(&stem.to_lowercase(), &flags));
+        }
+        Ok(Self { words })
+    }
+
+    // A .dic file with its .aff next to it, or a plain word list
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DictionaryError> {
+        let path = path.as_ref();
+        let read = |path: &Path| {
+            fs::read_to_string(path).map_err(|e| {
+                DictionaryError::new(0, format!("cannot read {}: {}", path.display(), e))
+            })
+        };
+        let text = read(path)?;
+        if path.extension().is_some_and(|e| e == "dic") {
+            let aff = read(&path.with_extension("aff"))?;
+            Self::parse_hunspell(&text, &aff)
+        } else {
+            Ok(Self::parse_word_list(&text))
+        }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+}
+
+// The affix rules of an .aff file, by flag
+struct Affixes {
+    flag_type: FlagType,
+    rules: HashMap<String, AffixClass>,
+}
+
+#[derive(Clone, Copy)]
+enum FlagType {
+    // One character per flag (the default)
+    Char,
+    // Two characters per flag
+    Long,
+    // Comma separated numbers
+    Numeric,
+}
+
+struct AffixClass {
+    prefix: bool,
+    // May combine with an affix of the other kind
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<CharClass>,
+}
+
+enum CharClass {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::OneOf(chars) => chars.contains(&c),
+            CharClass::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+}
+
+impl Affixes {
+    fn parse(aff: &str) -> Result<Self, DictionaryError> {
+        let mut affixes = Self {
+            flag_type: FlagType::Char,
+            rules: HashMap::new(),
+        };
+        for (number, line) in aff.lines().enumerate() {
+            let number = number + 1;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                ["FLAG", "long", ..] => affixes.flag_type = FlagType::Long,
+                ["FLAG", "num", ..] => affixes.flag_type = FlagType::Numeric,
+                [kind @ ("PFX" | "SFX"), flag, cross, count] if count.parse::<usize>().is_ok() => {
+                    affixes.rules.insert(
+                        flag.to_string(),
+                        AffixClass {
+                            prefix: kind == "PFX",
+                            cross_product: cross == "Y",
+                            rules: Vec::new(),
+                        },
+                    );
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, ref rest @ ..] => {
+                    let class = affixes.rules.get_mut(flag).ok_or_else(|| {
+                        DictionaryError::new(
+                            number,
+                            format!("{} rule before its {} header", flag, kind),
+                        )
+                    })?;
+                    let zero = |s: &str| {
+                        if s == "0" {
+                            String::new()
+                        } else {
+                            s.to_lowercase()
+                        }
+                    };
+                    // Flags on the affix itself (twofold affixes) are not supported
+                    let add = add.split('/').next().unwrap_or_default();
+                    class.rules.push(AffixRule {
+                        strip: zero(strip),
+                        add: zero(add),
+                        condition: parse_condition(rest.first().copied().unwrap_or("."))
+                            .ok_or_else(|| DictionaryError::new(number, "invalid condition"))?,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(affixes)
+    }
+
+    fn flags(&self, text: &str, line: usize) -> Result<Vec<String>, DictionaryError> {
+        let chars: Vec<char> = text.chars().collect();
+        match self.flag_type {
+            FlagType::Char => Ok(chars.iter().map(|c| c.to_string()).collect()),
+            FlagType::Long if chars.len().is_multiple_of(2) => {
+                Ok(chars.chunks(2).map(|pair| pair.iter().collect()).collect())
+            }
+            FlagType::Long => Err(DictionaryError::new(
+                line,
+                "odd number of characters in long flags",
+            )),
+            FlagType::Numeric => Ok(text.split(',').map(String::from).collect()),
+        }
+    }
+
+    // The stem and every form its flags allow
+    fn expand(&self, stem: &str, flags: &[String]) -> Vec<String> {
+        let classes: Vec<&AffixClass> = flags.iter().filter_map(|f| self.rules.get(f)).collect();
+        let mut forms = vec![stem.to_string()];
+        let mut crossable = Vec::new();
+        for class in classes.iter().filter(|c| !c.prefix) {
+            for form in class.rules.iter().filter_map(|r| r.apply(stem, false)) {
+                if class.cross_product {
+                    crossable.push(form.clone());
+                }
+                forms.push(form);
+            }
+        }
+        for class in classes.iter().filter(|c| c.prefix) {
+            for rule in &class.rules {
+                forms.extend(rule.apply(stem, true));
+                if class.cross_product {
+                    forms.extend(crossable.iter().filter_map(|form| rule.apply(form, true)));
+                }
+            }
+        }
+        forms
+    }
+}
+
+impl AffixRule {
+    fn apply(&self, word: &str, prefix: bool) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let n = self.condition.len();
+        if chars.len() < n {
+            return None;
+        }
+        let checked = if prefix {
+            &chars[..n]
+        } else {
+            &chars[chars.len() - n..]
+        };
+        if !checked
+            .iter()
+            .zip(&self.condition)
+            .all(|(&c, class)| class.matches(c))
+        {
+            return None;
+        }
+        if prefix {
+            let rest = word.strip_prefix(self.strip.as_str())?;
+            Some(format!("{}{}", self.add, rest))
+        } else {
+            let rest = word.strip_suffix(self.strip.as_str())?;
+            Some(format!("{}{}", rest, self.add))
+        }
+    }
+}
+
+// "." any character, "[aeiou]" one of them, "[^aeiou]" none of them, "y" exactly that one
+fn parse_condition(text: &str) -> Option<Vec<CharClass>> {
+    if text == "." {
+        return Some(Vec::new());
+    }
+    let mut classes = Vec::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        classes.push(match c {
+            '.' => CharClass::Any,
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                loop {
+                    match chars.next()? {
+                        ']' => break,
+                        '^' if set.is_empty() && !negated => negated = true,
+                        c => set.extend(c.to_lowercase()),
+                    }
+                }
+                if negated {
+                    CharClass::NoneOf(set)
+                } else {
+                    CharClass::OneOf(set)
+                }
+            }
+            c => CharClass::OneOf(c.to_lowercase().collect()),
+        });
+    }
+    Some(classes)
+}
+
+// =========================
+// Checking
+// =========================
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Suggestion {
+    pub word: String,
+    pub distance: usize,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Misspelling {
+    // Byte range of the word in the checked text (of its correction, once corrected)
+    pub range: Range<usize>,
+    pub word: String,
+    // Closest first
+    pub suggestions: Vec<Suggestion>,
+    // What replaced the word, when it was corrected automatically
+    pub correction: Option<String>,
+}
+
+impl Misspelling {
+    // The best suggestion, when no other one is as close
+    pub fn unambiguous(&self) -> Option<&str> {
+        match &self.suggestions[..] {
+            [best] => Some(&best.word),
+            [best, second, ..] if best.distance < second.distance => Some(&best.word),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Misspelling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}..{})",
+            self.word, self.range.start, self.range.end
+        )?;
+        if let Some(correction) = &self.correction {
+            write!(f, " -> {}", correction)?;
+        } else if !self.suggestions.is_empty() {
+            let words: Vec<&str> = self.suggestions.iter().map(|s| s.word.as_str()).collect();
+            write!(f, " ? {}", words.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+pub struct SpellChecker {
+    dictionary: Dictionary,
+    auto_correct: bool,
+    // Words further away than this are not suggested
+    max_distance: usize,
+    max_suggestions: usize,
+}
+
+impl SpellChecker {
+    pub fn new(dictionary: Dictionary) -> Self {
+        Self {
+            dictionary,
+            auto_correct: false,
+            max_distance: 2,
+            max_suggestions: 5,
+        }
+    }
+
+    // Replace the misspellings that have an unambiguous correction
+    pub fn auto_correct(mut self, auto_correct: bool) -> Self {
+        self.auto_correct = auto_correct;
+        self
+    }
+
+    pub fn check(&self, text: &str) -> Vec<Misspelling> {
+        words(text)
+            .filter(|(_, word)| !self.dictionary.contains(word))
+            .map(|(range, word)| Misspelling {
+                range,
+                word: word.to_string(),
+                suggestions: self.suggest(word),
+                correction: None,
+            })
+            .collect()
+    }
+
+    // Dictionary words within max_distance edits, closest first, then alphabetically
+    pub fn suggest(&self, word: &str) -> Vec<Suggestion> {
+        let word = word.to_lowercase();
+        let length = word.chars().count();
+        let mut suggestions: Vec<Suggestion> = self
+            .dictionary
+            .words
+            .iter()
+            .filter(|candidate| candidate.chars().count().abs_diff(length) <= self.max_distance)
+            .map(|candidate| Suggestion {
+                word: candidate.clone(),
+                distance: edit_distance(&word, candidate),
+            })
+            .filter(|s| s.distance <= self.max_distance)
+            .collect();
+        suggestions.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        suggestions.truncate(self.max_suggestions);
+        suggestions
+    }
+
+    // Checks the text and, with auto-correction on, fixes the unambiguous misspellings in place
+    // The ranges returned are those of the text after the fixes
+    pub fn correct(&self, text: &mut String) -> Vec<Misspelling> {
+        let mut misspellings = self.check(text);
+        if !self.auto_correct {
+            return misspellings;
+        }
+        // Each replacement moves the words after it by the difference in length
+        let mut shift = 0isize;
+        for misspelling in &mut misspellings {
+            let start = misspelling.range.start.saturating_add_signed(shift);
+            let end = misspelling.range.end.saturating_add_signed(shift);
+            misspelling.range = start..end;
+            if let Some(best) = misspelling.unambiguous() {
+                let correction = same_case(&misspelling.word, best);
+                text.replace_range(start..end, &correction);
+                shift += correction.len() as isize - (end - start) as isize;
+                misspelling.range = start..start + correction.len();
+                misspelling.correction = Some(correction);
+            }
+        }
+        misspellings
+    }
+}
+
+// Words with their byte ranges: letters, and apostrophes between letters (don't)
+// Anything with a digit in it (b2b, 3rd) is not a word to check
+fn words(text: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next_is_letter = chars.peek().is_some_and(|&(_, n)| n.is_alphanumeric());
+        let in_word =
+            c.is_alphanumeric() || (matches!(c, '\'' | '’') && start.is_some() && next_is_letter);
+        match (in_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push(s..text.len());
+    }
+    words
+        .into_iter()
+        .map(move |range| (range.clone(), &text[range]))
+        .filter(|(_, word)| !word.chars().any(|c| c.is_numeric()))
+}
+
+// Optimal string alignment distance: insertions, deletions, substitutions and
+// transpositions of two neighbours (wrold -> world) all count as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+// The correction written like the word it replaces: WROLD -> WORLD, Wrold -> World
+fn same_case(original: &str, correction: &str) -> String {
+    let mut letters = original.chars().filter(|c| c.is_alphabetic());
+    if original.chars().filter(|c| c.is_alphabetic()).count() > 1 && letters.all(char::is_uppercase)
+    {
+        return correction.to_uppercase();
+    }
+    if original.chars().next().is_some_and(char::is_uppercase) {
+        let mut chars = correction.chars();
+        if let Some(first) = chars.next() {
+            return first.to_uppercase().chain(chars).collect();
+        }
+    }
+    correction.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "\
+SET UTF-8
+SFX S Y 2
+SFX S 0 s [^y]
+SFX S y ies y
+PFX U Y 1
+PFX U 0 un .
+";
+
+    #[test]
+    fn hunspell_affixes_expand_the_stems() {
+        let dictionary = Dictionary::parse_hunspell("3\nword/S\nstudy/SU\ncheck/U\n", AFF).unwrap();
+
+        for word in [
+            "words",
+            "studies",
+            "unstudy",
+            "unstudies",
+            "uncheck",
+            "Check",
+        ] {
+            assert!(dictionary.contains(word), "{}", word);
+        }
+        assert!(!dictionary.contains("studys"));
+        assert!(!dictionary.contains("checks"));
+        assert_eq!(dictionary.len(), 8);
+    }
+
+    #[test]
+    fn misspellings_with_ranges_suggestions_and_corrections() {
+        let dictionary =
+            Dictionary::parse_word_list("# test\nhello\nworld\nwords\nword\ndon't\nthe\n");
+        let checker = SpellChecker::new(dictionary).auto_correct(true);
+        let mut text = String::from("Héllo WROLD, the wordz don't 3rd");
+
+        let misspellings = checker.correct(&mut text);
+
+        let found: Vec<String> = misspellings.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            found,
+            [
+                "Héllo (0..5) -> Hello",
+                "WROLD (6..11) -> WORLD",
+                "wordz (17..22) ? word, words, world"
+            ]
+        );
+        assert_eq!(&text[misspellings[2].range.clone()], "wordz");
+        assert_eq!(misspellings[1].unambiguous(), Some("world"));
+        assert_eq!(misspellings[2].unambiguous(), None);
+        assert_eq!(text, "Hello WORLD, the wordz don't 3rd");
+        assert_eq!(edit_distance("cheker", "checker"), 1);
+    }
+}
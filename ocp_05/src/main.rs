@@ -4,20 +4,41 @@
 // Dynamic Dispatch Based Solution - Txt Processor with Plugins
 // =========================
 
+// The spell checker itself (dictionary, suggestions) is ocp_04's spell_checker.rs, shared
+// with its dictionaries rather than copied
+// So is ocp_04's editor.rs: the text, its diagnostics and what a run does on errors
+// The processings to run, in which order and with which parameters, come from a
+// pipeline file read by pipeline.rs
+
+#[path = "../../ocp_04/src/editor.rs"]
+mod editor;
+mod pipeline;
+#[path = "../../ocp_04/src/spell_checker.rs"]
+mod spell_checker;
+
+use editor::{
+    Diagnostic, EditorContent, OnError, ProcessingError, Severity, Summary, check_spelling,
+};
+use pipeline::{Configurable, Params, PipelineError, ProcessingRegistry};
+use spell_checker::{Dictionary, SpellChecker};
+
 // =========================
 // Abstractions
 // =========================
 
 // A TxtProcessor is a vector of processing to be applied on text
 // It knows nothing about the processing nor the text
+#[derive(Default)]
 pub struct TxtProcessor {
     processings: Vec<Box<dyn Processing>>,
+    on_error: OnError,
 }
 
 impl TxtProcessor {
     pub fn new() -> Self {
         Self {
             processings: Vec::new(),
+            on_error: OnError::default(),
         }
     }
 
@@ -25,23 +46,28 @@ impl TxtProcessor {
         self.processings.push(processing);
     }
 
-    pub fn run(&mut self, content: &mut EditorContent) {
-        for processing in &mut self.processings {
-            println!("Running processing: {}", processing.name());
-            processing.apply(content); // Apply the processing to the shared content
-        }
+    pub fn on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
     }
-}
 
-// Here the content of the TxtProcessor is just a String
-pub struct EditorContent {
-    pub content: String,
+    pub fn run(&mut self, content: &mut EditorContent) -> Result<Summary, ProcessingError> {
+        let on_error = self.on_error;
+        on_error.run(content, |content| {
+            self.processings.iter_mut().try_for_each(|processing| {
+                println!("Running processing: {}", processing.name());
+                processing
+                    .apply(content) // Apply the processing to the shared content
+                    .or_else(|error| on_error.recover(content, error))
+            })
+        })
+    }
 }
 
 // If a type wants to have the Processing trait it must implement the 2 methods below
 pub trait Processing {
     fn name(&self) -> &str;
-    fn apply(&mut self, context: &mut EditorContent);
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError>;
 }
 
 // =========================
@@ -56,23 +82,83 @@ impl Processing for LowerCase {
         "LowerCase"
     }
 
-    fn apply(&mut self, context: &mut EditorContent) {
-        context.content = context.content.to_lowercase();
-        context.content.push_str("\n[LowerCase OK]");
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        let lowercase = context.content.to_lowercase();
+        if lowercase != context.content {
+            context.content = lowercase;
+            context.report(Diagnostic::new(
+                Severity::Info,
+                self.name(),
+                "text lowercased",
+            ));
+        }
+        Ok(())
     }
 }
 
-// SpellChecker processing
-pub struct SpellChecker;
+impl Configurable for LowerCase {
+    fn from_params(_: &mut Params) -> Result<Self, PipelineError> {
+        Ok(LowerCase)
+    }
+}
 
+// SpellChecker processing
 impl Processing for SpellChecker {
     fn name(&self) -> &str {
         "SpellChecker"
     }
 
-    fn apply(&mut self, context: &mut EditorContent) {
-        // Fake spell checker
-        context.content.push_str("\n[SpellChecker OK]");
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        check_spelling(self, context, self.name());
+        Ok(())
+    }
+}
+
+// dictionary = "../ocp_04/dictionaries/en.dic", auto_correct = true
+impl Configurable for SpellChecker {
+    fn from_params(params: &mut Params) -> Result<Self, PipelineError> {
+        let Some((path, line)) = params.take_path("dictionary") else {
+            return Err(PipelineError::new(
+                params.line,
+                format!("{} needs a \"dictionary\"", params.processing),
+            ));
+        };
+        let dictionary = Dictionary::load(&path).map_err(|e| {
+            PipelineError::new(line, format!("cannot load {}: {}", path.display(), e))
+        })?;
+        // Every word would be reported
+        if dictionary.len() == 0 {
+            let message = format!("{} has no words", path.display());
+            return Err(PipelineError::new(line, message));
+        }
+        let auto_correct = params.take("auto_correct")?.unwrap_or(false);
+        Ok(SpellChecker::new(dictionary).auto_correct(auto_correct))
+    }
+}
+
+// Length limit, as for a post or a commit subject
+// It fails on a text too long, so it goes after the processings that change the text
+pub struct MaxLength(pub usize);
+
+impl Processing for MaxLength {
+    fn name(&self) -> &str {
+        "MaxLength"
+    }
+
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        let length = context.content.chars().count();
+        if length > self.0 {
+            let message = format!("{} characters, the limit is {}", length, self.0);
+            return Err(ProcessingError::new(self.name(), message));
+        }
+        Ok(())
+    }
+}
+
+// limit = 280
+impl Configurable for MaxLength {
+    fn from_params(params: &mut Params) -> Result<Self, PipelineError> {
+        Ok(MaxLength(params.require("limit")?))
     }
 }
 
@@ -81,17 +167,93 @@ impl Processing for SpellChecker {
 // =========================
 
 fn main() {
-    let mut processor = TxtProcessor::new();
-
-    processor.register_processing(Box::new(LowerCase));
-    processor.register_processing(Box::new(SpellChecker));
-
-    let mut ed_context = EditorContent {
-        content: String::from("HELLO WORLD"),
+    // Editors change pipeline.toml to reorder, enable or disable steps: no rebuild needed
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/pipeline.toml");
+    let registry = ProcessingRegistry::with_builtin_processings();
+    let mut processor = match pipeline::read(path).and_then(|p| registry.build(p)) {
+        Ok(processor) => processor,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return;
+        }
     };
 
-    processor.run(&mut ed_context);
+    let mut ed_context = EditorContent::new(
+        "HELLO WROLD, THIS SPELL CHEKER FLAGS MISSPELED WORDZ AND SUGESTS A FIX",
+    );
+
+    match processor.run(&mut ed_context) {
+        Ok(summary) => println!("Run complete: {}", summary),
+        Err(e) => println!("Run stopped, {}", e),
+    }
 
     println!("--- FINAL CONTENT ---");
     println!("{}", ed_context.content);
+
+    println!("--- DIAGNOSTICS ---");
+    for diagnostic in &ed_context.diagnostics {
+        println!("{}", diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        on_error: OnError,
+        processings: Vec<Box<dyn Processing>>,
+    ) -> (EditorContent, Result<Summary, ProcessingError>) {
+        let mut processor = TxtProcessor::new().on_error(on_error);
+        for processing in processings {
+            processor.register_processing(processing);
+        }
+        let mut content = EditorContent::new("TOO LONG");
+        let result = processor.run(&mut content);
+        (content, result)
+    }
+
+    #[test]
+    fn a_failing_processing_aborts_skips_or_rolls_back() {
+        let (aborted, result) = run(
+            OnError::Abort,
+            vec![Box::new(LowerCase), Box::new(MaxLength(3))],
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "MaxLength failed: 8 characters, the limit is 3"
+        );
+        assert_eq!(aborted.content, "too long");
+        assert_eq!(aborted.diagnostics.len(), 2);
+
+        let (skipped, result) = run(
+            OnError::Skip,
+            vec![Box::new(MaxLength(3)), Box::new(LowerCase)],
+        );
+        assert_eq!(skipped.content, "too long");
+        assert_eq!(
+            result.unwrap(),
+            Summary {
+                infos: 1,
+                warnings: 0,
+                errors: 1
+            }
+        );
+
+        let (rolled_back, result) = run(
+            OnError::Rollback,
+            vec![Box::new(LowerCase), Box::new(MaxLength(3))],
+        );
+        assert!(result.is_err());
+        assert_eq!(rolled_back.content, "TOO LONG");
+        let diagnostics: Vec<String> = rolled_back
+            .diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            diagnostics,
+            ["error   MaxLength: 8 characters, the limit is 3"]
+        );
+    }
 }
@@ -0,0 +1,440 @@
+// src/pipeline.rs
+// A TxtProcessor described by a file instead of Rust code, in a small subset of TOML:
+//
+//   on_error = "rollback"
+//
+//   [[step]]
+//   name = "LowerCase"
+//
+//   [[step]]
+//   name = "SpellChecker"
+//   dictionary = "../ocp_04/dictionaries/en.dic"
+//   auto_correct = true
+//
+//   [[step]]
+//   name = "MaxLength"
+//   enabled = false
+//   limit = 280
+//
+// Steps run in the order of the file. `name` picks the processing in a ProcessingRegistry,
+// `enabled = false` keeps a step in the file without running it, and the other keys are the
+// parameters of the processing (see Configurable). A processing rejects the parameters it
+// doesn't know, so a typo is an error instead of a silently ignored setting.
+// Values are "strings", integers or booleans; '#' starts a comment.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::spell_checker::SpellChecker;
+use crate::{LowerCase, MaxLength, OnError, Processing, TxtProcessor};
+
+#[derive(Debug)]
+pub struct PipelineError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl PipelineError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pipeline line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+// A processing that can be built from the parameters of its step
+pub trait Configurable: Processing + Sized {
+    fn from_params(params: &mut Params) -> Result<Self, PipelineError>;
+}
+
+pub struct Pipeline {
+    pub on_error: OnError,
+    pub steps: Vec<Step>,
+}
+
+pub struct Step {
+    pub enabled: bool,
+    pub params: Params,
+}
+
+// The parameters of one step, consumed by the processing as it reads them
+pub struct Params {
+    pub processing: String,
+    // Line of the [[step]] header
+    pub line: usize,
+    // Relative paths are relative to the pipeline file, not to the working directory
+    dir: PathBuf,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    key: String,
+    value: String,
+    line: usize,
+}
+
+impl Params {
+    pub fn take_text(&mut self, key: &str) -> Option<(String, usize)> {
+        let index = self.entries.iter().position(|e| e.key == key)?;
+        let entry = self.entries.remove(index);
+        Some((entry.value, entry.line))
+    }
+
+    // Any type that parses from text: numbers, bool...
+    pub fn take<T: FromStr>(&mut self, key: &str) -> Result<Option<T>, PipelineError> {
+        let Some((value, line)) = self.take_text(key) else {
+            return Ok(None);
+        };
+        value.parse().map(Some).map_err(|_| {
+            PipelineError::new(
+                line,
+                format!("{}.{}: invalid value \"{}\"", self.processing, key, value),
+            )
+        })
+    }
+
+    pub fn require<T: FromStr>(&mut self, key: &str) -> Result<T, PipelineError> {
+        self.take(key)?.ok_or_else(|| {
+            PipelineError::new(
+                self.line,
+                format!("{} needs a \"{}\"", self.processing, key),
+            )
+        })
+    }
+
+    pub fn take_path(&mut self, key: &str) -> Option<(PathBuf, usize)> {
+        let (value, line) = self.take_text(key)?;
+        Some((self.dir.join(value), line))
+    }
+
+    // Whatever the processing didn't read is unknown to it
+    pub fn finish(self) -> Result<(), PipelineError> {
+        match self.entries.first() {
+            Some(entry) => Err(PipelineError::new(
+                entry.line,
+                format!("{} has no parameter \"{}\"", self.processing, entry.key),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+// =========================
+// Registry
+// =========================
+
+type Builder = fn(&mut Params) -> Result<Box<dyn Processing>, PipelineError>;
+
+fn build<P: Configurable + 'static>(
+    params: &mut Params,
+) -> Result<Box<dyn Processing>, PipelineError> {
+    Ok(Box::new(P::from_params(params)?))
+}
+
+// The processings a pipeline file can name
+// A new processing only needs a Configurable impl and a register() call
+#[derive(Default)]
+pub struct ProcessingRegistry {
+    builders: Vec<(String, Builder)>,
+}
+
+impl ProcessingRegistry {
+    pub fn new() -> Self {
+        Self {
+            builders: Vec::new(),
+        }
+    }
+
+    pub fn with_builtin_processings() -> Self {
+        let mut registry = Self::new();
+        registry.register::<LowerCase>("LowerCase");
+        registry.register::<SpellChecker>("SpellChecker");
+        registry.register::<MaxLength>("MaxLength");
+        registry
+    }
+
+    // A later processing with the same name replaces the earlier one
+    pub fn register<P: Configurable + 'static>(&mut self, name: &str) {
+        self.builders.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.builders.push((name.to_string(), build::<P>));
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.builders.iter().map(|(name, _)| name.as_str())
+    }
+
+    // Names are case-insensitive
+    fn lookup(&self, name: &str) -> Option<Builder> {
+        self.builders
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, build)| *build)
+    }
+
+    // Disabled steps aren't built, but their name must still be known
+    pub fn build(&self, pipeline: Pipeline) -> Result<TxtProcessor, PipelineError> {
+        let mut processor = TxtProcessor::new().on_error(pipeline.on_error);
+        for Step {
+            enabled,
+            mut params,
+        } in pipeline.steps
+        {
+            let Some(build) = self.lookup(&params.processing) else {
+                let names: Vec<&str> = self.names().collect();
+                return Err(PipelineError::new(
+                    params.line,
+                    format!(
+                        "no processing named \"{}\", expected {}",
+                        params.processing,
+                        names.join(", ")
+                    ),
+                ));
+            };
+            if !enabled {
+                continue;
+            }
+            let processing = build(&mut params)?;
+            params.finish()?;
+            processor.register_processing(processing);
+        }
+        Ok(processor)
+    }
+}
+
+// =========================
+// File format
+// =========================
+
+pub fn parse(source: &str, dir: &Path) -> Result<Pipeline, PipelineError> {
+    let mut on_error = OnError::default();
+    let mut steps: Vec<Params> = Vec::new();
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            if strip_comment(line) != "[[step]]" {
+                return Err(PipelineError::new(
+                    number,
+                    "only [[step]] tables are supported",
+                ));
+            }
+            steps.push(Params {
+                processing: String::new(),
+                line: number,
+                dir: dir.to_path_buf(),
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| PipelineError::new(number, "expected key = value or [[step]]"))?;
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(PipelineError::new(
+                number,
+                format!("invalid key \"{}\"", key),
+            ));
+        }
+        let value = parse_value(value).map_err(|message| PipelineError::new(number, message))?;
+
+        let Some(step) = steps.last_mut() else {
+            if key != "on_error" {
+                return Err(PipelineError::new(
+                    number,
+                    format!("\"{}\" is outside of a [[step]]", key),
+                ));
+            }
+            on_error = match value.to_lowercase().as_str() {
+                "abort" => OnError::Abort,
+                "skip" => OnError::Skip,
+                "rollback" => OnError::Rollback,
+                _ => {
+                    return Err(PipelineError::new(
+                        number,
+                        format!(
+                            "on_error: invalid value \"{}\", expected abort, skip, rollback",
+                            value
+                        ),
+                    ));
+                }
+            };
+            continue;
+        };
+        if step.entries.iter().any(|e| e.key == key) {
+            return Err(PipelineError::new(number, format!("{} is set twice", key)));
+        }
+        step.entries.push(Entry {
+            key: key.to_string(),
+            value,
+            line: number,
+        });
+    }
+
+    // name and enabled are about the step, whatever the processing
+    let mut pipeline = Pipeline {
+        on_error,
+        steps: Vec::new(),
+    };
+    for mut params in steps {
+        let (name, _) = params
+            .take_text("name")
+            .ok_or_else(|| PipelineError::new(params.line, "[[step]] without a name"))?;
+        params.processing = name;
+        let enabled = params.take("enabled")?.unwrap_or(true);
+        pipeline.steps.push(Step { enabled, params });
+    }
+    Ok(pipeline)
+}
+
+pub fn read(path: impl AsRef<Path>) -> Result<Pipeline, PipelineError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|e| PipelineError::new(0, format!("cannot read {}: {}", path.display(), e)))?;
+    parse(&source, path.parent().unwrap_or(Path::new("")))
+}
+
+// "text", with \" and \\ escapes, or a bare integer / boolean
+fn parse_value(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    let Some(quoted) = value.strip_prefix('"') else {
+        let bare = strip_comment(value);
+        if bare.is_empty() {
+            return Err("missing value".to_string());
+        }
+        return Ok(bare.to_string());
+    };
+
+    let mut text = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                let rest = strip_comment(&quoted[i + 1..]);
+                if !rest.is_empty() {
+                    return Err(format!("unexpected \"{}\" after the string", rest));
+                }
+                return Ok(text);
+            }
+            '\\' => match chars.next() {
+                Some((_, '"')) => text.push('"'),
+                Some((_, '\\')) => text.push('\\'),
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                _ => return Err("invalid escape in string".to_string()),
+            },
+            _ => text.push(c),
+        }
+    }
+    Err("missing \" at the end of the string".to_string())
+}
+
+// Outside of strings, '#' starts a comment
+fn strip_comment(text: &str) -> &str {
+    text.split('#').next().unwrap_or_default().trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EditorContent;
+
+    const PIPELINE: &str = r#"
+on_error = "skip"  # keep going
+
+[[step]]
+name = "maxlength"
+limit = 4
+
+[[step]]
+name = "LowerCase"
+
+[[step]]
+name = "SpellChecker"
+enabled = false
+dictionary = "missing.dic"
+"#;
+
+    #[test]
+    fn steps_run_in_file_order_and_disabled_ones_are_left_out() {
+        let pipeline = parse(PIPELINE, Path::new("")).unwrap();
+        assert_eq!(pipeline.on_error, OnError::Skip);
+        assert_eq!(pipeline.steps.len(), 3);
+        assert!(!pipeline.steps[2].enabled);
+
+        let mut processor = ProcessingRegistry::with_builtin_processings()
+            .build(pipeline)
+            .unwrap();
+        let mut content = EditorContent::new("TOO LONG");
+        let summary = processor.run(&mut content).unwrap();
+
+        assert_eq!(content.content, "too long");
+        assert_eq!(summary.errors, 1);
+        assert_eq!(
+            content.diagnostics[0].to_string(),
+            "error   MaxLength: 8 characters, the limit is 4"
+        );
+    }
+
+    #[test]
+    fn mistakes_point_to_their_line() {
+        let registry = ProcessingRegistry::with_builtin_processings();
+        let error = |source: &str| match parse(source, Path::new("")) {
+            Ok(pipeline) => registry.build(pipeline).err().unwrap().to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        assert_eq!(
+            error("[[step]]\nlimit = 3"),
+            "pipeline line 1: [[step]] without a name"
+        );
+        assert_eq!(
+            error("on_error = \"retry\""),
+            "pipeline line 1: on_error: invalid value \"retry\", expected abort, skip, rollback"
+        );
+        assert_eq!(
+            error("[[step]]\nname = \"UpperCase\""),
+            "pipeline line 1: no processing named \"UpperCase\", expected LowerCase, SpellChecker, MaxLength"
+        );
+        assert_eq!(
+            error("[[step]]\nname = \"MaxLength\""),
+            "pipeline line 1: MaxLength needs a \"limit\""
+        );
+        assert_eq!(
+            error("[[step]]\nname = \"MaxLength\"\nlimit = \"ten\""),
+            "pipeline line 3: MaxLength.limit: invalid value \"ten\""
+        );
+        assert_eq!(
+            error("[[step]]\nname = \"LowerCase\"\nlocale = \"tr\""),
+            "pipeline line 3: LowerCase has no parameter \"locale\""
+        );
+        assert_eq!(
+            error("[[step]]\nname = \"LowerCase\"\nname = \"MaxLength\""),
+            "pipeline line 3: name is set twice"
+        );
+        assert_eq!(
+            error("[[step]]\nname = \"LowerCase\"\nenabled = no"),
+            "pipeline line 3: LowerCase.enabled: invalid value \"no\""
+        );
+    }
+}
@@ -5,6 +5,20 @@
 // Static Dispatch Based Solution - Txt Processor with Plugins
 // =========================
 
+// The spell checker itself (dictionary, suggestions) is ocp_04's spell_checker.rs, shared
+// with its dictionaries rather than copied
+// So is ocp_04's editor.rs: the text, its diagnostics and what a run does on errors
+
+#[path = "../../ocp_04/src/editor.rs"]
+mod editor;
+#[path = "../../ocp_04/src/spell_checker.rs"]
+mod spell_checker;
+
+use editor::{
+    Diagnostic, EditorContent, OnError, ProcessingError, Severity, Summary, check_spelling,
+};
+use spell_checker::{Dictionary, SpellChecker};
+
 // =========================
 // Abstractions
 // =========================
@@ -13,12 +27,14 @@
 // It knows nothing about the processing nor the text
 pub struct TxtProcessor<T: Processing> {
     processings: Vec<T>,
+    on_error: OnError,
 }
 
 impl<T: Processing> TxtProcessor<T> {
     pub fn new() -> Self {
         Self {
             processings: Vec::new(),
+            on_error: OnError::default(),
         }
     }
 
@@ -26,23 +42,27 @@ impl<T: Processing> TxtProcessor<T> {
         self.processings.push(tool);
     }
 
-    pub fn run(&mut self, context: &mut EditorContent) {
-        for tool in &mut self.processings {
-            println!("Running tool: {}", tool.name());
-            tool.apply(context); // Direct call, no vtable
-        }
+    pub fn on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
     }
-}
 
-// Here the content of the TxtProcessor is just a String
-pub struct EditorContent {
-    pub content: String,
+    pub fn run(&mut self, context: &mut EditorContent) -> Result<Summary, ProcessingError> {
+        let on_error = self.on_error;
+        on_error.run(context, |context| {
+            self.processings.iter_mut().try_for_each(|tool| {
+                println!("Running tool: {}", tool.name());
+                tool.apply(context) // Direct call, no vtable
+                    .or_else(|error| on_error.recover(context, error))
+            })
+        })
+    }
 }
 
 // If a type wants to have the Processing trait it must implement the 2 methods below
 pub trait Processing {
     fn name(&self) -> &str;
-    fn apply(&mut self, context: &mut EditorContent);
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError>;
 }
 
 // =========================
@@ -57,23 +77,29 @@ impl Processing for LowerCase {
         "LowerCase"
     }
 
-    fn apply(&mut self, context: &mut EditorContent) {
-        context.content = context.content.to_lowercase();
-        context.content.push_str("\n[LowerCase OK]");
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        let lowercase = context.content.to_lowercase();
+        if lowercase != context.content {
+            context.content = lowercase;
+            context.report(Diagnostic::new(
+                Severity::Info,
+                self.name(),
+                "text lowercased",
+            ));
+        }
+        Ok(())
     }
 }
 
 // SpellChecker processing
-pub struct SpellChecker;
-
 impl Processing for SpellChecker {
     fn name(&self) -> &str {
         "SpellChecker"
     }
 
-    fn apply(&mut self, context: &mut EditorContent) {
-        // Fake spell checker
-        context.content.push_str("\n[SpellChecker OK]");
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        check_spelling(self, context, self.name());
+        Ok(())
     }
 }
 
@@ -82,18 +108,40 @@ impl Processing for SpellChecker {
 // =========================
 
 fn main() {
-    let mut processor = TxtProcessor::new();
+    // Hunspell dictionary: en.dic and the affix rules of en.aff
+    let dictionary = match Dictionary::load(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../ocp_04/dictionaries/en.dic"
+    )) {
+        Ok(dictionary) => dictionary,
+        Err(e) => {
+            eprintln!("cannot load the dictionary: {}", e);
+            return;
+        }
+    };
+    println!("{} words in the dictionary", dictionary.len());
+    let spell_checker = SpellChecker::new(dictionary).auto_correct(true);
+
+    let mut processor = TxtProcessor::new().on_error(OnError::Rollback);
 
     // Tools must be of the same concrete type T
     processor.register_processing(LowerCase);
-    processor.register_processing(SpellChecker);
+    processor.register_processing(spell_checker);
 
-    let mut ed_context = EditorContent {
-        content: String::from("HELLO WORLD"),
-    };
+    let mut ed_context = EditorContent::new(
+        "HELLO WROLD, THIS SPELL CHEKER FLAGS MISSPELED WORDZ AND SUGESTS A FIX",
+    );
 
-    processor.run(&mut ed_context);
+    match processor.run(&mut ed_context) {
+        Ok(summary) => println!("Run complete: {}", summary),
+        Err(e) => println!("Run stopped, {}", e),
+    }
 
     println!("--- FINAL CONTENT ---");
     println!("{}", ed_context.content);
+
+    println!("--- DIAGNOSTICS ---");
+    for diagnostic in &ed_context.diagnostics {
+        println!("{}", diagnostic);
+    }
 }
@@ -4,6 +4,20 @@
 // Static Dispatch Based Solution - Txt Processor with Plugins
 // =========================
 
+// The spell checker itself (dictionary, suggestions) is ocp_04's spell_checker.rs, shared
+// with its dictionaries rather than copied
+// So is ocp_04's editor.rs: the text, its diagnostics and what a run does on errors
+
+#[path = "../../ocp_04/src/editor.rs"]
+mod editor;
+#[path = "../../ocp_04/src/spell_checker.rs"]
+mod spell_checker;
+
+use editor::{
+    Diagnostic, EditorContent, OnError, ProcessingError, Severity, Summary, check_spelling,
+};
+use spell_checker::{Dictionary, SpellChecker};
+
 // =========================
 // Abstractions
 // =========================
@@ -12,30 +26,50 @@
 // It knows nothing about the processing nor the text
 pub struct TxTProcessor<T> {
     processings: T,
+    on_error: OnError,
 }
 
 impl<T: ToolChain> TxTProcessor<T> {
     pub fn new(tools: T) -> Self {
-        Self { processings: tools }
+        Self {
+            processings: tools,
+            on_error: OnError::default(),
+        }
     }
 
-    pub fn run(&mut self, context: &mut EditorContent) {
-        self.processings.apply(context);
+    pub fn on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    pub fn run(&mut self, context: &mut EditorContent) -> Result<Summary, ProcessingError> {
+        self.on_error.run(context, |context| {
+            self.processings.apply(context, self.on_error)
+        })
     }
 }
 
 // Apply a processing or a chain of processings
+// on_error is there for Skip: a failure in the middle of the chain must not stop the tail
 pub trait ToolChain {
-    fn apply(&mut self, context: &mut EditorContent);
+    fn apply(
+        &mut self,
+        context: &mut EditorContent,
+        on_error: OnError,
+    ) -> Result<(), ProcessingError>;
 }
 
 // Implementation for a unique processing
 // A single Processing is also a valid ToolChain
 // If we don't have this implementation there is no way to implement the recursive
 impl<T: Processing> ToolChain for T {
-    fn apply(&mut self, context: &mut EditorContent) {
+    fn apply(
+        &mut self,
+        context: &mut EditorContent,
+        on_error: OnError,
+    ) -> Result<(), ProcessingError> {
         // self.apply(context); // ! CANNOT work: .apply() calls .apply()
-        Processing::apply(self, context);
+        Processing::apply(self, context).or_else(|error| on_error.recover(context, error))
     }
 }
 
@@ -48,21 +82,20 @@ where
     Head: Processing,
     Tail: ToolChain,
 {
-    fn apply(&mut self, context: &mut EditorContent) {
-        self.0.apply(context);
-        self.1.apply(context);
+    fn apply(
+        &mut self,
+        context: &mut EditorContent,
+        on_error: OnError,
+    ) -> Result<(), ProcessingError> {
+        ToolChain::apply(&mut self.0, context, on_error)?;
+        self.1.apply(context, on_error)
     }
 }
 
-// Here the content of the TxtProcessor is just a String
-pub struct EditorContent {
-    pub content: String,
-}
-
 // If a type wants to have the Processing trait it must implement the 2 methods below
 pub trait Processing {
     fn name(&self) -> &str;
-    fn apply(&mut self, context: &mut EditorContent);
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError>;
 }
 
 // =========================
@@ -77,23 +110,48 @@ impl Processing for LowerCase {
         "LowerCase"
     }
 
-    fn apply(&mut self, context: &mut EditorContent) {
-        context.content = context.content.to_lowercase();
-        context.content.push_str("\n[LowerCase OK]");
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        let lowercase = context.content.to_lowercase();
+        if lowercase != context.content {
+            context.content = lowercase;
+            context.report(Diagnostic::new(
+                Severity::Info,
+                self.name(),
+                "text lowercased",
+            ));
+        }
+        Ok(())
     }
 }
 
 // SpellChecker processing
-pub struct SpellChecker;
-
 impl Processing for SpellChecker {
     fn name(&self) -> &str {
-        "Git Integration"
+        "SpellChecker"
+    }
+
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        check_spelling(self, context, self.name());
+        Ok(())
+    }
+}
+
+// Length limit, as for a post or a commit subject
+// It fails on a text too long, so it goes after the processings that change the text
+pub struct MaxLength(pub usize);
+
+impl Processing for MaxLength {
+    fn name(&self) -> &str {
+        "MaxLength"
     }
 
-    fn apply(&mut self, context: &mut EditorContent) {
-        // Fake spell checker
-        context.content.push_str("\n[SpellChecker OK]");
+    fn apply(&mut self, context: &mut EditorContent) -> Result<(), ProcessingError> {
+        let length = context.content.chars().count();
+        if length > self.0 {
+            let message = format!("{} characters, the limit is {}", length, self.0);
+            return Err(ProcessingError::new(self.name(), message));
+        }
+        Ok(())
     }
 }
 
@@ -102,7 +160,21 @@ impl Processing for SpellChecker {
 // =========================
 
 fn main() {
-    let mut processor = TxTProcessor::new((LowerCase, SpellChecker));
+    // Hunspell dictionary: en.dic and the affix rules of en.aff
+    let dictionary = match Dictionary::load(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../ocp_04/dictionaries/en.dic"
+    )) {
+        Ok(dictionary) => dictionary,
+        Err(e) => {
+            eprintln!("cannot load the dictionary: {}", e);
+            return;
+        }
+    };
+    println!("{} words in the dictionary", dictionary.len());
+    let spell_checker = SpellChecker::new(dictionary).auto_correct(true);
+
+    let mut processor = TxTProcessor::new((LowerCase, (spell_checker, MaxLength(72))));
 
     // At this point the chain is complete:
     // LowerCase implements Tool
@@ -110,12 +182,43 @@ fn main() {
     // therefore (SpellCheck, LowerCase) implements ToolChain
     // therefore Editor<(SpellCheck, LowerCase)> is valid
 
-    let mut ed_context = EditorContent {
-        content: String::from("HELLO WORLD"),
-    };
+    let text = "HELLO WROLD, THIS SPELL CHEKER FLAGS MISSPELED WORDZ AND SUGESTS A FIX";
+    for on_error in [OnError::Abort, OnError::Skip, OnError::Rollback] {
+        processor = processor.on_error(on_error);
+        let mut ed_context = EditorContent::new(text);
+
+        println!("--- ON ERROR: {:?} ---", on_error);
+        match processor.run(&mut ed_context) {
+            Ok(summary) => println!("Run complete: {}", summary),
+            Err(e) => println!("Run stopped, {}", e),
+        }
+        println!("{}", ed_context.content);
+        for diagnostic in &ed_context.diagnostics {
+            println!("{}", diagnostic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    processor.run(&mut ed_context);
+    #[test]
+    fn skip_goes_on_with_the_rest_of_the_chain() {
+        let mut processor =
+            TxTProcessor::new((MaxLength(3), (LowerCase, MaxLength(5)))).on_error(OnError::Skip);
+        let mut context = EditorContent::new("TOO LONG");
 
-    println!("--- FINAL CONTENT ---");
-    println!("{}", ed_context.content);
+        let summary = processor.run(&mut context).unwrap();
+
+        assert_eq!(context.content, "too long");
+        assert_eq!(summary.errors, 2);
+        assert_eq!(summary.infos, 1);
+
+        let mut processor =
+            TxTProcessor::new((LowerCase, MaxLength(3))).on_error(OnError::Rollback);
+        let mut context = EditorContent::new("TOO LONG");
+        assert!(processor.run(&mut context).is_err());
+        assert_eq!(context.content, "TOO LONG");
+    }
 }
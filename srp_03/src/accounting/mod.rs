@@ -1 +1,2 @@
 pub mod payroll;
+pub mod payroll_run;
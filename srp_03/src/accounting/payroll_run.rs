@@ -0,0 +1,122 @@
+// src/accounting/payroll_run.rs
+// Accounting's responsibility
+
+use std::sync::Mutex;
+use std::thread;
+
+use super::payroll::PayrollCalculator;
+use crate::domain::employee::Employee;
+use crate::infrastructure::db::DbError;
+use crate::infrastructure::repository::PooledEmployeeRepository;
+
+// Outcome of a payroll run
+pub struct PayrollSummary {
+    pub processed: usize,
+    pub total_pay: f64,
+    pub failures: Vec<(u32, DbError)>,
+}
+
+pub struct PayrollRun;
+
+impl PayrollRun {
+    // Compute and store the pay of every employee, in parallel
+    // One worker per pooled connection: more workers would only wait on the pool
+    pub fn run_parallel(employees: &[Employee], repo: &PooledEmployeeRepository) -> PayrollSummary {
+        let workers = repo.pool().max_size().min(employees.len()).max(1);
+        let chunk_size = employees.len().div_ceil(workers).max(1);
+        let summary = Mutex::new(PayrollSummary {
+            processed: 0,
+            total_pay: 0.0,
+            failures: Vec::new(),
+        });
+
+        thread::scope(|s| {
+            for chunk in employees.chunks(chunk_size) {
+                let summary = &summary;
+                s.spawn(move || {
+                    for employee in chunk {
+                        let pay = PayrollCalculator::calculate_pay(employee);
+                        let result = repo.save_pay(employee.id, pay);
+
+                        let mut summary = summary.lock().unwrap_or_else(|e| e.into_inner());
+                        match result {
+                            Ok(()) => {
+                                summary.processed += 1;
+                                summary.total_pay += pay;
+                            }
+                            Err(e) => summary.failures.push((employee.id, e)),
+                        }
+                    }
+                });
+            }
+        });
+
+        summary.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::pool::ConnectionPool;
+    use std::time::Duration;
+
+    fn staff() -> Vec<Employee> {
+        (1..=10)
+            .map(|id| Employee {
+                id,
+                name: format!("Employee #{}", id),
+                hours_worked: 30.0 + id as f64,
+                // Every third employee has no valid rate, their pay can't be stored
+                rate: if id % 3 == 0 {
+                    f64::NAN
+                } else {
+                    18.0 + id as f64
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parallel_run_matches_sequential_run() {
+        let employees = staff();
+        let repo = PooledEmployeeRepository::new(ConnectionPool::new(3, Duration::from_secs(2)));
+
+        let summary = PayrollRun::run_parallel(&employees, &repo);
+
+        let (mut processed, mut total_pay, mut failed) = (0, 0.0, Vec::new());
+        for employee in &employees {
+            let pay = PayrollCalculator::calculate_pay(employee);
+            match repo.save_pay(employee.id, pay) {
+                Ok(()) => {
+                    processed += 1;
+                    total_pay += pay;
+                }
+                Err(_) => failed.push(employee.id),
+            }
+        }
+        assert_eq!(failed, [3, 6, 9]);
+        assert_eq!(summary.processed, processed);
+        assert!((summary.total_pay - total_pay).abs() < 1e-6);
+        let mut failures: Vec<u32> = summary.failures.iter().map(|(id, _)| *id).collect();
+        failures.sort_unstable();
+        assert_eq!(failures, failed);
+    }
+
+    #[test]
+    fn failures_are_collected_without_stopping_the_run() {
+        let employees = staff();
+        let repo = PooledEmployeeRepository::new(ConnectionPool::new(2, Duration::from_secs(2)));
+
+        let summary = PayrollRun::run_parallel(&employees, &repo);
+
+        assert_eq!(summary.processed, 7);
+        assert_eq!(summary.failures.len(), 3);
+        assert!(
+            summary
+                .failures
+                .iter()
+                .all(|(id, e)| id % 3 == 0 && matches!(e, DbError::Query))
+        );
+    }
+}
@@ -2,14 +2,26 @@
 // Infrastructure/DBA's responsibility
 
 use std::fmt;
+use std::io::Write;
+use std::time::Duration;
 
 // Dummy database error type
 #[derive(Debug)]
-pub struct DbError;
+pub enum DbError {
+    // The (fake) query failed
+    Query,
+    // No pooled connection became available in time
+    CheckoutTimeout(Duration),
+}
 
 impl fmt::Display for DbError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Database error")
+        match self {
+            DbError::Query => write!(f, "Database error"),
+            DbError::CheckoutTimeout(timeout) => {
+                write!(f, "No database connection available after {:?}", timeout)
+            }
+        }
     }
 }
 
@@ -18,9 +30,11 @@ pub struct Database;
 
 impl Database {
     // Execute a fake SQL query
+    // stdout is locked once so concurrent callers don't interleave their lines
     pub fn execute(&self, query: &str, params: &[&dyn fmt::Debug]) -> Result<(), DbError> {
-        println!("Executing SQL: {}", query);
-        println!("With params: {:?}", params);
+        let mut out = std::io::stdout().lock();
+        let _ = writeln!(out, "Executing SQL: {}", query);
+        let _ = writeln!(out, "With params: {:?}", params);
         Ok(())
     }
 }
@@ -0,0 +1,145 @@
+// src/infrastructure/pool.rs
+// Infrastructure/DBA's responsibility
+
+use std::ops::Deref;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use super::db::{Database, DbError};
+
+// A bounded pool of database connections that can be shared across threads
+// At most `max_size` connections exist at any time. They are opened lazily
+// and returned to the pool when the PooledConnection guard is dropped.
+pub struct ConnectionPool {
+    state: Mutex<PoolState>,
+    available: Condvar,
+    max_size: usize,
+    checkout_timeout: Duration,
+}
+
+struct PoolState {
+    idle: Vec<Database>,
+    opened: usize,
+}
+
+impl ConnectionPool {
+    pub fn new(max_size: usize, checkout_timeout: Duration) -> Self {
+        assert!(
+            max_size > 0,
+            "a connection pool needs at least one connection"
+        );
+        Self {
+            state: Mutex::new(PoolState {
+                idle: Vec::with_capacity(max_size),
+                opened: 0,
+            }),
+            available: Condvar::new(),
+            max_size,
+            checkout_timeout,
+        }
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    // Borrow a connection, waiting at most `checkout_timeout` for one to be released
+    pub fn get(&self) -> Result<PooledConnection<'_>, DbError> {
+        let deadline = Instant::now() + self.checkout_timeout;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        loop {
+            if let Some(db) = state.idle.pop() {
+                return Ok(PooledConnection {
+                    pool: self,
+                    db: Some(db),
+                });
+            }
+
+            if state.opened < self.max_size {
+                state.opened += 1;
+                return Ok(PooledConnection {
+                    pool: self,
+                    db: Some(Database),
+                });
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(DbError::CheckoutTimeout(self.checkout_timeout));
+            }
+
+            state = self
+                .available
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    fn release(&self, db: Database) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.idle.push(db);
+        self.available.notify_one();
+    }
+}
+
+// A connection checked out of the pool
+// Derefs to Database and goes back to the pool on drop
+pub struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    db: Option<Database>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        self.db.as_ref().expect("connection already released")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(db) = self.db.take() {
+            self.pool.release(db);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn checkout_times_out_when_pool_is_exhausted() {
+        let pool = ConnectionPool::new(1, Duration::from_millis(20));
+        let _held = pool.get().unwrap();
+
+        let result = pool.get();
+
+        assert!(matches!(result, Err(DbError::CheckoutTimeout(_))));
+    }
+
+    #[test]
+    fn released_connection_is_reused() {
+        let pool = ConnectionPool::new(1, Duration::from_millis(20));
+        drop(pool.get().unwrap());
+
+        assert!(pool.get().is_ok());
+    }
+
+    #[test]
+    fn waiting_thread_gets_connection_once_released() {
+        let pool = ConnectionPool::new(1, Duration::from_secs(5));
+
+        thread::scope(|s| {
+            let held = pool.get().unwrap();
+            let waiter = s.spawn(|| pool.get().is_ok());
+            thread::sleep(Duration::from_millis(20));
+            drop(held);
+            assert!(waiter.join().unwrap());
+        });
+    }
+}
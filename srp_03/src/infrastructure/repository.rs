@@ -2,6 +2,7 @@
 // Infrastructure / DBA's responsibility
 
 use super::db::{Database, DbError};
+use super::pool::ConnectionPool;
 use crate::domain::employee::Employee;
 
 pub struct EmployeeRepository {
@@ -32,3 +33,41 @@ impl EmployeeRepository {
         })
     }
 }
+
+// Same responsibility, but backed by a connection pool
+// ConnectionPool is Send + Sync, so a single repository can be shared by many threads
+pub struct PooledEmployeeRepository {
+    pool: ConnectionPool,
+}
+
+impl PooledEmployeeRepository {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &ConnectionPool {
+        &self.pool
+    }
+
+    pub fn save(&self, employee: &Employee) -> Result<(), DbError> {
+        let db = self.pool.get()?;
+        db.execute(
+            "INSERT INTO employees VALUES (?, ?, ?, ?)",
+            &[
+                &employee.id,
+                &employee.name,
+                &employee.hours_worked,
+                &employee.rate,
+            ],
+        )
+    }
+
+    // The pay column is NUMERIC: like a real database, it refuses NaN and infinities
+    pub fn save_pay(&self, employee_id: u32, pay: f64) -> Result<(), DbError> {
+        if !pay.is_finite() {
+            return Err(DbError::Query);
+        }
+        let db = self.pool.get()?;
+        db.execute("INSERT INTO payslips VALUES (?, ?)", &[&employee_id, &pay])
+    }
+}
@@ -9,11 +9,14 @@ mod operations;
 
 fn main() {
     use crate::accounting::payroll::PayrollCalculator;
+    use crate::accounting::payroll_run::PayrollRun;
     use crate::domain::employee::Employee;
     use crate::hr::reporting::EmployeeReporter;
     use crate::infrastructure::db::Database;
-    use crate::infrastructure::repository::EmployeeRepository;
+    use crate::infrastructure::pool::ConnectionPool;
+    use crate::infrastructure::repository::{EmployeeRepository, PooledEmployeeRepository};
     use crate::operations::overtime::OvertimeTracker;
+    use std::time::Duration;
 
     let employee = Employee {
         id: 1,
@@ -34,6 +37,28 @@ fn main() {
     let repo = EmployeeRepository { db: Database };
     repo.save(&employee).unwrap();
 
+    // Accounting client - parallel payroll run over a pooled repository
+    let pooled_repo = PooledEmployeeRepository::new(ConnectionPool::new(4, Duration::from_secs(2)));
+    pooled_repo.save(&employee).unwrap();
+    let staff: Vec<Employee> = (2..=9)
+        .map(|id| Employee {
+            id,
+            name: format!("Employee #{}", id),
+            hours_worked: 35.0 + id as f64,
+            rate: 18.0 + id as f64,
+        })
+        .collect();
+    let summary = PayrollRun::run_parallel(&staff, &pooled_repo);
+    println!(
+        "Accounting: {} payslips stored, total = ${:.2}, {} failure(s)",
+        summary.processed,
+        summary.total_pay,
+        summary.failures.len()
+    );
+    for (id, e) in &summary.failures {
+        println!("  employee {}: {}", id, e);
+    }
+
     // HR client
     println!(
         "\nHR Text Report:\n{}",